@@ -0,0 +1,335 @@
+// Command-line interface for bigcolor
+
+mod rewrite;
+
+use std::io::{self, BufRead, IsTerminal};
+use std::process::ExitCode;
+
+use bigcolor::terminal::{escape_sequence, ColorDepth, Layer, RESET};
+use bigcolor::{declared_gamut, is_readable, BigColor, ColorFormat, Gamut, WCAG2Level, WCAG2Params, WCAG2Size};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+
+#[derive(Parser)]
+#[command(name = "bigcolor", version, about = "Convert, inspect and compare colors")]
+struct Cli {
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert colors to another format
+    Convert {
+        /// Target format
+        #[arg(short, long, value_enum)]
+        to: Format,
+        /// Colors to convert; read from stdin, one per line, when omitted
+        colors: Vec<String>,
+    },
+    /// Show every representation of a color with its luminance, name and gamut
+    Inspect {
+        /// Colors to inspect; read from stdin, one per line, when omitted
+        colors: Vec<String>,
+    },
+    /// Check the contrast of a text color on a background
+    Contrast { foreground: String, background: String },
+    /// Print a color scheme built from each color
+    Scheme {
+        #[arg(value_enum)]
+        kind: SchemeKind,
+        /// Base colors; read from stdin, one per line, when omitted
+        colors: Vec<String>,
+        /// Number of colors for analogous and monochromatic schemes
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+        /// Format of the printed colors
+        #[arg(short, long, value_enum, default_value = "hex")]
+        to: Format,
+    },
+    /// Convert or normalize color literals in files, in place
+    Rewrite(rewrite::RewriteArgs),
+}
+
+/// Output formats, one per `ColorFormat` that can be written
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Hex,
+    Hex3,
+    Hex8,
+    Rgb,
+    Prgb,
+    Hsl,
+    Hsv,
+    Hsb,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+    Cmyk,
+    Name,
+}
+
+impl From<Format> for ColorFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Hex => ColorFormat::HEX,
+            Format::Hex3 => ColorFormat::HEX3,
+            Format::Hex8 => ColorFormat::HEX8,
+            Format::Rgb => ColorFormat::RGB,
+            Format::Prgb => ColorFormat::PRGB,
+            Format::Hsl => ColorFormat::HSL,
+            Format::Hsv => ColorFormat::HSV,
+            Format::Hsb => ColorFormat::HSB,
+            Format::Lab => ColorFormat::LAB,
+            Format::Lch => ColorFormat::LCH,
+            Format::Oklab => ColorFormat::OKLAB,
+            Format::Oklch => ColorFormat::OKLCH,
+            Format::Cmyk => ColorFormat::CMYK,
+            Format::Name => ColorFormat::NAME,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SchemeKind {
+    Analogous,
+    Monochromatic,
+    Complement,
+    SplitComplement,
+    Triad,
+    Tetrad,
+}
+
+/// Representations shown by `inspect`, in order
+const INSPECT_FORMATS: [(&str, ColorFormat); 10] = [
+    ("hex", ColorFormat::HEX),
+    ("hex8", ColorFormat::HEX8),
+    ("rgb", ColorFormat::RGB),
+    ("hsl", ColorFormat::HSL),
+    ("hsv", ColorFormat::HSV),
+    ("cmyk", ColorFormat::CMYK),
+    ("lab", ColorFormat::LAB),
+    ("lch", ColorFormat::LCH),
+    ("oklab", ColorFormat::OKLAB),
+    ("oklch", ColorFormat::OKLCH),
+];
+
+/// Returns the colors from the arguments, or from stdin when there are none
+/// or the only argument is `-`
+fn inputs(colors: Vec<String>) -> Vec<String> {
+    if !colors.is_empty() && colors != ["-"] {
+        return colors;
+    }
+    io::stdin()
+        .lock()
+        .lines()
+        .map_while(Result::ok)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Parses a color, reporting invalid input on stderr
+fn parse(input: &str) -> Option<BigColor> {
+    let color = BigColor::new(input);
+    if color.is_valid() {
+        Some(color)
+    } else {
+        eprintln!("bigcolor: invalid color '{}'", input);
+        None
+    }
+}
+
+/// Names the smallest common gamut that holds the color's channels as written
+fn gamut(color: &BigColor) -> &'static str {
+    match declared_gamut(color.get_original_input()) {
+        Some(Gamut::Srgb) => "srgb",
+        Some(Gamut::DisplayP3) => "display-p3",
+        Some(Gamut::Wide) => "wide",
+        None => "unknown",
+    }
+}
+
+/// Writes commands' text output with swatch previews the terminal can show
+struct Printer {
+    depth: ColorDepth,
+}
+
+impl Printer {
+    fn new() -> Self {
+        let depth = if io::stdout().is_terminal() { ColorDepth::detect() } else { ColorDepth::None };
+        Printer { depth }
+    }
+
+    /// A two-cell swatch followed by a space, or nothing without color support
+    fn swatch(&self, color: &BigColor) -> String {
+        if self.depth == ColorDepth::None {
+            return String::new();
+        }
+        format!("{}  {} ", escape_sequence(color, Layer::Background, self.depth, None), RESET)
+    }
+}
+
+fn inspect(color: &BigColor) -> Value {
+    let mut value = json!({
+        "input": color.get_original_input(),
+        "luminance": color.get_luminance(),
+        "alpha": color.get_alpha(),
+        "name": color.to_name(),
+        "gamut": gamut(color),
+    });
+    for (key, format) in INSPECT_FORMATS {
+        value[key] = json!(color.to(format));
+    }
+    value
+}
+
+fn contrast(foreground: &BigColor, background: &BigColor) -> Value {
+    let passes = |level, size| is_readable(foreground, background, Some(WCAG2Params { level, size }));
+    json!({
+        "foreground": foreground.to_hex_string(false),
+        "background": background.to_hex_string(false),
+        "ratio": foreground.get_contrast_ratio(background),
+        "aa": passes(WCAG2Level::AA, WCAG2Size::Small),
+        "aa_large": passes(WCAG2Level::AA, WCAG2Size::Large),
+        "aaa": passes(WCAG2Level::AAA, WCAG2Size::Small),
+        "aaa_large": passes(WCAG2Level::AAA, WCAG2Size::Large),
+        "apca": foreground.get_apca_contrast(background),
+    })
+}
+
+fn scheme(color: &BigColor, kind: SchemeKind, count: Option<usize>) -> Vec<BigColor> {
+    match kind {
+        SchemeKind::Analogous => color.analogous(count, None),
+        SchemeKind::Monochromatic => color.monochromatic(count),
+        SchemeKind::Complement => vec![color.clone(), color.complement()],
+        SchemeKind::SplitComplement => color.split_complement(),
+        SchemeKind::Triad => color.triad(),
+        SchemeKind::Tetrad => color.tetrad(),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let printer = Printer::new();
+    let mut failed = false;
+    let mut results = Vec::new();
+
+    match cli.command {
+        Command::Convert { to, colors } => {
+            for input in inputs(colors) {
+                let Some(color) = parse(&input) else {
+                    failed = true;
+                    results.push(json!({ "input": input, "error": "invalid color" }));
+                    continue;
+                };
+                let output = color.to(to.into());
+                if cli.json {
+                    results.push(json!({ "input": input, "output": output }));
+                } else {
+                    println!("{}", output);
+                }
+            }
+        }
+        Command::Inspect { colors } => {
+            for input in inputs(colors) {
+                let Some(color) = parse(&input) else {
+                    failed = true;
+                    results.push(json!({ "input": input, "error": "invalid color" }));
+                    continue;
+                };
+                let details = inspect(&color);
+                if cli.json {
+                    results.push(details);
+                    continue;
+                }
+                println!("{}{}", printer.swatch(&color), input);
+                for (key, _) in INSPECT_FORMATS {
+                    println!("  {:<10}{}", key, details[key].as_str().unwrap_or_default());
+                }
+                println!("  {:<10}{:.4}", "luminance", color.get_luminance());
+                println!("  {:<10}{}", "name", color.to_name().unwrap_or("-"));
+                println!("  {:<10}{}", "gamut", gamut(&color));
+            }
+        }
+        Command::Rewrite(args) => return rewrite::run(args, cli.json),
+        Command::Contrast { foreground, background } => {
+            let (Some(fg), Some(bg)) = (parse(&foreground), parse(&background)) else {
+                return ExitCode::FAILURE;
+            };
+            let report = contrast(&fg, &bg);
+            if cli.json {
+                println!("{}", report);
+                return ExitCode::SUCCESS;
+            }
+            let mark = |key: &str| if report[key].as_bool() == Some(true) { "pass" } else { "fail" };
+            println!("{}{} on {}{}", printer.swatch(&fg), foreground, printer.swatch(&bg), background);
+            println!("  ratio     {:.2}:1", fg.get_contrast_ratio(&bg));
+            println!("  AA        {} (large text {})", mark("aa"), mark("aa_large"));
+            println!("  AAA       {} (large text {})", mark("aaa"), mark("aaa_large"));
+            println!("  APCA      Lc {:.1}", fg.get_apca_contrast(&bg));
+            return ExitCode::SUCCESS;
+        }
+        Command::Scheme { kind, colors, count, to } => {
+            for input in inputs(colors) {
+                let Some(color) = parse(&input) else {
+                    failed = true;
+                    results.push(json!({ "input": input, "error": "invalid color" }));
+                    continue;
+                };
+                let members = scheme(&color, kind, count);
+                let written: Vec<String> = members.iter().map(|member| member.to(to.into())).collect();
+                if cli.json {
+                    results.push(json!({ "input": input, "colors": written }));
+                    continue;
+                }
+                for (member, text) in members.iter().zip(&written) {
+                    println!("{}{}", printer.swatch(member), text);
+                }
+                println!();
+            }
+        }
+    }
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap_or_default());
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_reports_every_representation() {
+        let details = inspect(&BigColor::new("#1a6ef5"));
+        assert_eq!(details["hex"], "#1a6ef5");
+        assert_eq!(details["rgb"], "rgb(26, 110, 245)");
+        assert_eq!(details["gamut"], "srgb");
+        assert_eq!(details["name"], Value::Null);
+        assert_eq!(inspect(&BigColor::new("red"))["name"], "red");
+        assert_eq!(inspect(&parse("oklch(70% 0.3 145)").unwrap())["gamut"], "wide");
+        assert_eq!(gamut(&parse("oklch(65% 0.25 25)").unwrap()), "display-p3");
+        assert_eq!(gamut(&parse("oklch(60% 0.1 250)").unwrap()), "srgb");
+        assert_eq!(gamut(&BigColor::from_oklch(0.7, 0.3, 145.0, 1.0)), "unknown");
+    }
+
+    #[test]
+    fn test_contrast_and_schemes() {
+        let report = contrast(&BigColor::new("#767676"), &BigColor::new("#ffffff"));
+        assert_eq!(report["aa"], true);
+        assert_eq!(report["aaa"], false);
+        assert_eq!(scheme(&BigColor::new("#ff0000"), SchemeKind::Triad, None).len(), 3);
+        assert_eq!(scheme(&BigColor::new("#ff0000"), SchemeKind::Analogous, Some(4)).len(), 4);
+    }
+}
@@ -0,0 +1,443 @@
+// The `rewrite` subcommand: convert or normalize color literals across a file tree
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use bigcolor::scan::{rewrite_source, ColorMatch, LiteralContext, Syntax};
+use bigcolor::swatches::{read_palette, Palette, Swatch, SwatchFormat};
+use bigcolor::tokens::DesignTokens;
+use bigcolor::{BigColor, ColorFormat};
+use clap::Args;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde_json::{json, Value};
+use similar::TextDiff;
+use walkdir::WalkDir;
+
+use crate::Format;
+
+/// Extensions rewritten when no `--glob` is given
+const DEFAULT_EXTENSIONS: [&str; 13] = [
+    "css", "scss", "sass", "less", "html", "vue", "svelte", "js", "jsx", "mjs", "ts", "tsx", "mdx",
+];
+
+/// Directories never walked into
+const SKIPPED_DIRECTORIES: [&str; 4] = [".git", "node_modules", "target", "dist"];
+
+#[derive(Args)]
+pub struct RewriteArgs {
+    /// Files or directories to rewrite
+    #[arg(default_value = ".")]
+    paths: Vec<PathBuf>,
+    /// Target format. Without it, literals only change when snapped to a palette.
+    #[arg(short, long, value_enum)]
+    to: Option<Format>,
+    /// Only rewrite files matching these globs, relative to each path
+    #[arg(short, long)]
+    glob: Vec<String>,
+    /// Print a unified diff instead of writing files
+    #[arg(long)]
+    dry_run: bool,
+    /// Write nothing and exit with status 1 if any file would change or any
+    /// color is off-palette
+    #[arg(long)]
+    check: bool,
+    /// Snap colors to the nearest color of a palette: a swatch file, a design
+    /// tokens JSON file, or a text file with one color per line
+    #[arg(long)]
+    palette: Option<PathBuf>,
+    /// Largest ΔEOK at which a color is snapped; farther colors are reported
+    #[arg(long, default_value_t = 0.02, requires = "palette")]
+    threshold: f32,
+}
+
+/// A color farther from the palette than the threshold
+struct OffPalette {
+    line: usize,
+    column: usize,
+    literal: String,
+    nearest: String,
+    distance: f32,
+}
+
+/// The outcome of rewriting one file
+struct FileReport {
+    path: PathBuf,
+    original: String,
+    rewritten: String,
+    changes: usize,
+    off_palette: Vec<OffPalette>,
+}
+
+/// Reads a palette file in any format the library understands
+fn load_palette(path: &Path) -> Result<Palette, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+
+    let format = SwatchFormat::detect(&bytes).or_else(|| match extension {
+        "txt" | "json" => None,
+        other => SwatchFormat::from_extension(other),
+    });
+    if let Some(format) = format {
+        return read_palette(&bytes, format).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut palette = Palette::default();
+    if extension == "json" {
+        let tokens = DesignTokens::parse(&text)
+            .and_then(|tokens| tokens.resolve_all())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        for (name, color) in tokens {
+            palette.swatches.push(Swatch::new(Some(&name), color));
+        }
+        return Ok(palette);
+    }
+
+    // One color per line, optionally preceded by a name
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("//") || line.starts_with(';') {
+            continue;
+        }
+        let whole = BigColor::new(line);
+        let swatch = if whole.is_valid() {
+            Swatch::new(None, whole)
+        } else {
+            let (name, value) = line.split_once(char::is_whitespace).unwrap_or(("", line));
+            let color = BigColor::new(value.trim());
+            if !color.is_valid() {
+                return Err(format!("{}: invalid color line '{}'", path.display(), line));
+            }
+            Swatch::new(Some(name.trim_end_matches(':')), color)
+        };
+        palette.swatches.push(swatch);
+    }
+    Ok(palette)
+}
+
+/// Converts a `rgb(r, g, b)` style output to `rgb(r g b)` or back so it
+/// matches the separator style of the literal it replaces
+fn match_syntax(original: &str, output: String) -> String {
+    let (Some(open), Some(inner)) = (output.find('('), output.strip_suffix(')')) else {
+        return output;
+    };
+    let Some(original_open) = original.find('(') else { return output };
+    let original_commas = original[original_open..].contains(',');
+    let output_commas = output.contains(',');
+    let name = &output[..open];
+    let args = &inner[open + 1..];
+
+    if output_commas && !original_commas {
+        // Legacy to modern: rgba(1, 2, 3, 0.5) becomes rgb(1 2 3 / 0.5)
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        let name = name.strip_suffix('a').filter(|_| parts.len() == 4).unwrap_or(name);
+        match parts.as_slice() {
+            [a, b, c, alpha] => format!("{}({} {} {} / {})", name, a, b, c, alpha),
+            _ => format!("{}({})", name, parts.join(" ")),
+        }
+    } else if !output_commas && original_commas && matches!(name, "rgb" | "hsl") {
+        // Modern to legacy, which only rgb() and hsl() allow
+        let (channels, alpha) = match args.split_once('/') {
+            Some((channels, alpha)) => (channels, Some(alpha.trim())),
+            None => (args, None),
+        };
+        let channels: Vec<&str> = channels.split_whitespace().collect();
+        match alpha {
+            Some(alpha) => format!("{}a({}, {})", name, channels.join(", "), alpha),
+            None => format!("{}({})", name, channels.join(", ")),
+        }
+    } else {
+        output
+    }
+}
+
+fn is_hex(format: ColorFormat) -> bool {
+    matches!(format, ColorFormat::HEX | ColorFormat::HEX3 | ColorFormat::HEX6 | ColorFormat::HEX8)
+}
+
+/// Writes a color for a literal, keeping the literal's case and syntax
+fn render(literal: &str, color: &BigColor, format: ColorFormat) -> String {
+    let format = match format {
+        ColorFormat::NAME if color.to_name().is_none() => ColorFormat::HEX,
+        _ if is_hex(format) && color.get_alpha() < 1.0 => ColorFormat::HEX8,
+        other => other,
+    };
+    let output = match_syntax(literal, color.to(format));
+
+    // Hex digits keep their case; function names are always written in lowercase
+    let letters: Vec<char> = literal.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let uppercase = !letters.is_empty() && letters.iter().all(char::is_ascii_uppercase);
+    if uppercase && literal.starts_with('#') && output.starts_with('#') {
+        output.to_ascii_uppercase()
+    } else {
+        output
+    }
+}
+
+/// Returns the 1-based line and column of a byte offset
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(offset, |newline| offset - newline - 1) + 1;
+    (line, column)
+}
+
+fn rewrite_file(path: &Path, args: &RewriteArgs, palette: Option<&Palette>) -> Result<FileReport, String> {
+    let original = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let syntax = Syntax::from_extension(extension);
+
+    let mut changes = 0;
+    let mut off_palette = Vec::new();
+    let rewritten = rewrite_source(&original, syntax, None, |m: &ColorMatch| {
+        if m.context == LiteralContext::Helper {
+            return None;
+        }
+        let literal = m.as_str(&original);
+        let mut color = m.color.clone();
+        let mut snapped = false;
+
+        if let Some((swatch, distance)) = palette.and_then(|palette| palette.nearest(&color)) {
+            if distance <= args.threshold {
+                let alpha = color.get_alpha();
+                color = swatch.color.clone();
+                color.set_alpha(alpha);
+                snapped = true;
+            } else {
+                let (line, column) = line_column(&original, m.span.start);
+                off_palette.push(OffPalette {
+                    line,
+                    column,
+                    literal: literal.to_string(),
+                    nearest: swatch.name.clone().unwrap_or_else(|| swatch.color.to_hex_string(false)),
+                    distance,
+                });
+            }
+        }
+
+        // Parsing clips to 8-bit sRGB, so a literal whose color does not
+        // change keeps its own precision and gamut; only hex loses nothing
+        let format = args.to.map(ColorFormat::from).unwrap_or(m.format);
+        if !snapped && format == m.format && (args.to.is_none() || !is_hex(format)) {
+            return None;
+        }
+        let replacement = render(literal, &color, format);
+        (replacement != literal).then(|| {
+            changes += 1;
+            replacement
+        })
+    });
+
+    Ok(FileReport {
+        path: path.to_path_buf(),
+        original,
+        rewritten,
+        changes,
+        off_palette,
+    })
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| format!("invalid glob '{}': {}", pattern, e))?);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+/// Lists the files under the given paths that the globs select
+fn files(paths: &[PathBuf], globs: Option<&GlobSet>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for root in paths {
+        let walker = WalkDir::new(root).sort_by_file_name().into_iter().filter_entry(|entry| {
+            entry.depth() == 0 || !SKIPPED_DIRECTORIES.contains(&entry.file_name().to_str().unwrap_or_default())
+        });
+        for entry in walker.filter_map(Result::ok).filter(|entry| entry.file_type().is_file()) {
+            let path = entry.path();
+            let selected = match globs {
+                Some(globs) => {
+                    let relative = path.strip_prefix(root).unwrap_or(path);
+                    globs.is_match(relative) || globs.is_match(path)
+                }
+                None => {
+                    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+                    entry.depth() == 0 || DEFAULT_EXTENSIONS.contains(&extension)
+                }
+            };
+            if selected {
+                files.push(path.to_path_buf());
+            }
+        }
+    }
+    files
+}
+
+pub fn run(args: RewriteArgs, json_output: bool) -> ExitCode {
+    let prepared = glob_set(&args.glob).and_then(|globs| {
+        let palette = args.palette.as_deref().map(load_palette).transpose()?;
+        Ok((globs, palette))
+    });
+    let (globs, palette) = match prepared {
+        Ok(prepared) => prepared,
+        Err(message) => {
+            eprintln!("bigcolor: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    let mut results = Vec::new();
+    for path in files(&args.paths, globs.as_ref()) {
+        let report = match rewrite_file(&path, &args, palette.as_ref()) {
+            Ok(report) => report,
+            Err(message) => {
+                // Binary and unreadable files are skipped with a warning
+                eprintln!("bigcolor: skipping {}", message);
+                continue;
+            }
+        };
+        let display = report.path.strip_prefix(".").unwrap_or(&report.path).display().to_string();
+
+        for off in &report.off_palette {
+            if !json_output {
+                eprintln!(
+                    "{}:{}:{}: off-palette color {} (nearest {}, ΔE {:.3})",
+                    display, off.line, off.column, off.literal, off.nearest, off.distance
+                );
+            }
+        }
+        if args.check && (report.changes > 0 || !report.off_palette.is_empty()) {
+            failed = true;
+        }
+
+        if report.changes > 0 {
+            if args.dry_run && !json_output {
+                let diff = TextDiff::from_lines(&report.original, &report.rewritten);
+                let (old, new) = if report.path.is_absolute() {
+                    (display.clone(), display.clone())
+                } else {
+                    (format!("a/{}", display), format!("b/{}", display))
+                };
+                print!("{}", diff.unified_diff().header(&old, &new));
+            } else if args.check {
+                if !json_output {
+                    println!("{}: {} colors would change", display, report.changes);
+                }
+            } else if !args.dry_run {
+                if let Err(error) = fs::write(&report.path, &report.rewritten) {
+                    eprintln!("bigcolor: {}: {}", display, error);
+                    failed = true;
+                    continue;
+                }
+                if !json_output {
+                    println!("{}: {} colors rewritten", display, report.changes);
+                }
+            }
+        }
+
+        if json_output && (report.changes > 0 || !report.off_palette.is_empty()) {
+            let off_palette: Vec<Value> = report
+                .off_palette
+                .iter()
+                .map(|off| {
+                    json!({
+                        "line": off.line,
+                        "column": off.column,
+                        "literal": off.literal,
+                        "nearest": off.nearest,
+                        "delta_e": off.distance,
+                    })
+                })
+                .collect();
+            results.push(json!({ "path": display, "changes": report.changes, "off_palette": off_palette }));
+        }
+    }
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap_or_default());
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_keeps_case_and_syntax() {
+        let blue = BigColor::new("#1a6ef5");
+        assert_eq!(render("#1A6EF5", &blue, ColorFormat::HEX), "#1A6EF5");
+        assert_eq!(render("rgb(0 0 0)", &blue, ColorFormat::RGB), "rgb(26 110 245)");
+        assert_eq!(render("rgb(0, 0, 0)", &blue, ColorFormat::RGB), "rgb(26, 110, 245)");
+        let translucent = BigColor::new("rgba(26, 110, 245, 0.5)");
+        assert_eq!(render("rgb(0 0 0 / 1)", &translucent, ColorFormat::RGB), "rgb(26 110 245 / 0.5)");
+        assert_eq!(render("hsl(0, 0%, 0%)", &translucent, ColorFormat::OKLCH), "oklch(57% 0.22 260 / 0.5)");
+    }
+
+    #[test]
+    fn test_rewrites_and_snaps_files() {
+        let dir = std::env::temp_dir().join(format!("bigcolor-rewrite-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let css = dir.join("style.css");
+        fs::write(&css, ".a { color: #1B6FF4; }\n.b { color: rgb(255, 0, 0); }\n").unwrap();
+
+        let palette = Palette::from_colors(None, &[BigColor::new("#1a6ef5")]);
+        let args = RewriteArgs {
+            paths: vec![dir.clone()],
+            to: None,
+            glob: vec!["*.css".to_string()],
+            dry_run: true,
+            check: false,
+            palette: None,
+            threshold: 0.02,
+        };
+        let report = rewrite_file(&css, &args, Some(&palette)).unwrap();
+        assert_eq!(report.changes, 1);
+        assert!(report.rewritten.contains("color: #1A6EF5;"));
+        assert_eq!(report.off_palette.len(), 1);
+        assert_eq!((report.off_palette[0].line, report.off_palette[0].column), (2, 13));
+
+        assert_eq!(files(std::slice::from_ref(&dir), glob_set(&args.glob).unwrap().as_ref()), vec![css]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_keeps_precise_and_wide_gamut_literals() {
+        let dir = std::env::temp_dir().join(format!("bigcolor-rewrite-precise-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let css = dir.join("style.css");
+        let source = ".a { color: oklch(62.8% 0.2577 29.23); }\n\
+                      .b { color: lab(54.29 80.8 69.89); }\n\
+                      .c { color: hsl(210.5, 50.5%, 40.2%); }\n\
+                      .d { color: oklch(70% 0.3 145); }\n";
+        fs::write(&css, source).unwrap();
+
+        let mut args = RewriteArgs {
+            paths: vec![dir.clone()],
+            to: None,
+            glob: Vec::new(),
+            dry_run: false,
+            check: true,
+            palette: None,
+            threshold: 0.02,
+        };
+        let report = rewrite_file(&css, &args, None).unwrap();
+        assert_eq!(report.changes, 0);
+        assert_eq!(report.rewritten, source);
+
+        args.to = Some(Format::Oklch);
+        let report = rewrite_file(&css, &args, None).unwrap();
+        assert_eq!(report.changes, 2);
+        assert!(report.rewritten.contains("oklch(62.8% 0.2577 29.23)"));
+        assert!(report.rewritten.contains("oklch(70% 0.3 145)"));
+        assert!(!report.rewritten.contains("lab(54.29"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
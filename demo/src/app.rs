@@ -1,9 +1,9 @@
 use yew::prelude::*;
 use bigcolor::{BigColor, ColorFormat};
+use bigcolor::scan::convert_colors;
 use web_sys::{HtmlInputElement, HtmlTextAreaElement, HtmlSelectElement, window};
 use wasm_bindgen::JsCast;
 use gloo_timers::callback::Timeout;
-use regex::Regex;
 
 /// Helper function to copy text to clipboard
 fn copy_to_clipboard(text: &str) {
@@ -88,77 +88,7 @@ fn get_color_format_options() -> Vec<(String, ColorFormat)> {
 
 // Function to detect and convert colors in text
 fn convert_colors_in_text(text: &str, target_format: ColorFormat) -> String {
-    // Create patterns for various color formats
-    let color_patterns = vec![
-        // Hex colors
-        r"#([0-9a-fA-F]{3})\b",
-        r"#([0-9a-fA-F]{6})\b",
-        r"#([0-9a-fA-F]{8})\b",
-        // RGB colors
-        r"rgb\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\)",
-        r"rgb\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*\)",
-        r"rgba\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*([01]?\.?\d*)\s*\)",
-        r"rgba\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*([01]?\.?\d*)\s*\)",
-        // HSL colors
-        r"hsl\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*\)",
-        r"hsla\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*([01]?\.?\d*)\s*\)",
-        // Space-separated HSL (like in CSS variables)
-        r"(\d+(?:\.\d+)?)\s+(\d+(?:\.\d+)?)%\s+(\d+(?:\.\d+)?)%",
-        r":\s*(\d+(?:\.\d+)?)\s+(\d+(?:\.\d+)?)%\s+(\d+(?:\.\d+)?)%",
-        // HSV/HSB colors
-        r"hsv\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*\)",
-        r"hsva\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*,\s*([01]?\.?\d*)\s*\)",
-        r"hsb\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*\)",
-        r"hsba\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*,\s*([01]?\.?\d*)\s*\)",
-        // CMYK colors
-        r"cmyk\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*\)",
-        // LAB colors
-        r"lab\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(-?\d+(?:\.\d+)?)\s*,\s*(-?\d+(?:\.\d+)?)\s*\)",
-        // LCH colors
-        r"lch\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)\s*\)",
-        // OKLAB colors
-        r"oklab\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(-?\d+(?:\.\d+)?)\s*,\s*(-?\d+(?:\.\d+)?)\s*\)",
-        // OKLCH colors
-        r"oklch\s*\(\s*(\d+(?:\.\d+)?)%\s*,?\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*\)",
-        r"oklch\s*\(\s*(\d*\.?\d+)\s+(\d*\.?\d+)\s+(\d+(?:\.\d+)?)\s*\)",
-        r"oklch\s*\(\s*(\d+(?:\.\d+)?)%\s+(\d+(?:\.\d+)?)\s+(\d+(?:\.\d+)?)\s*\)",
-    ];
-    
-    let mut result = text.to_string();
-    
-    for pattern in color_patterns {
-        let regex = Regex::new(pattern).unwrap();
-        let mut offset = 0;
-        
-        while let Some(color_match) = regex.find(&result[offset..]) {
-            let start = offset + color_match.start();
-            let end = offset + color_match.end();
-            let color_str = &result[start..end];
-            
-            // Special handling for space-separated HSL
-            let color = if pattern.contains("\\s+(\\d+(?:\\.")  && !pattern.contains("oklch") && !pattern.contains("rgb") && !pattern.contains("hsl\\s*\\(") {
-                // Convert space-separated HSL to standard HSL format
-                let caps = regex.captures(color_str).unwrap();
-                let h = caps.get(1).map_or("0", |m| m.as_str());
-                let s = caps.get(2).map_or("0", |m| m.as_str());
-                let l = caps.get(3).map_or("0", |m| m.as_str());
-                let hsl_str = format!("hsl({}, {}%, {}%)", h, s, l);
-                BigColor::new(&hsl_str)
-            } else {
-                BigColor::new(color_str)
-            };
-            
-            if color.is_valid() {
-                let converted = color.to(target_format);
-                result.replace_range(start..end, &converted);
-                offset = start + converted.len();
-            } else {
-                offset = end;
-            }
-        }
-    }
-    
-    result
+    convert_colors(text, target_format, None)
 }
 
 // Add after the ColorPreview struct
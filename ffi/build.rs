@@ -0,0 +1,25 @@
+// Generates bigcolor.h from the extern "C" API into OUT_DIR. The checked-in
+// include/bigcolor.h is only rewritten when BIGCOLOR_FFI_UPDATE_HEADER is set;
+// a test keeps it in sync with the generated one.
+
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=BIGCOLOR_FFI_UPDATE_HEADER");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("cbindgen.toml");
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => {
+            bindings.write_to_file(out_dir.join("bigcolor.h"));
+            if std::env::var_os("BIGCOLOR_FFI_UPDATE_HEADER").is_some() {
+                bindings.write_to_file(crate_dir.join("include/bigcolor.h"));
+            }
+        }
+        // rustc reports the actual error when the source does not parse
+        Err(error) => println!("cargo:warning=header not generated: {}", error),
+    }
+}
@@ -0,0 +1,175 @@
+/* C API for bigcolor. Colors are plain OKLCH values; strings returned by the
+ * library are owned by the caller and released with bc_string_free. */
+
+#ifndef BIGCOLOR_H
+#define BIGCOLOR_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a fallible call
+typedef enum bc_status {
+  BC_STATUS_OK = 0,
+  // A required pointer argument was null
+  BC_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8
+  BC_STATUS_INVALID_UTF8 = 2,
+  // The input could not be parsed as a color
+  BC_STATUS_INVALID_COLOR = 3,
+  // `BC_FORMAT_NAME` was requested for a color without a CSS name
+  BC_STATUS_UNNAMED = 4,
+  // An enum argument was not one of its declared values
+  BC_STATUS_INVALID_ARGUMENT = 5,
+} bc_status;
+
+// Color formats, as in the Rust `ColorFormat`
+typedef enum bc_format {
+  BC_FORMAT_RGB = 0,
+  BC_FORMAT_PRGB = 1,
+  BC_FORMAT_HEX = 2,
+  BC_FORMAT_HEX3 = 3,
+  BC_FORMAT_HEX6 = 4,
+  BC_FORMAT_HEX8 = 5,
+  BC_FORMAT_HSL = 6,
+  BC_FORMAT_HSV = 7,
+  BC_FORMAT_HSB = 8,
+  BC_FORMAT_LAB = 9,
+  BC_FORMAT_LCH = 10,
+  BC_FORMAT_OKLAB = 11,
+  BC_FORMAT_OKLCH = 12,
+  BC_FORMAT_CMYK = 13,
+  BC_FORMAT_NAME = 14,
+} bc_format;
+
+// WCAG 2 conformance level
+typedef enum bc_level {
+  BC_LEVEL_AA = 0,
+  BC_LEVEL_AAA = 1,
+} bc_level;
+
+// WCAG 2 text size
+typedef enum bc_size {
+  BC_SIZE_SMALL = 0,
+  BC_SIZE_LARGE = 1,
+} bc_size;
+
+// A color as OKLCH lightness (0 to 1), chroma, hue in degrees and alpha.
+// Values outside sRGB are kept.
+typedef struct bc_color {
+  float l;
+  float c;
+  float h;
+  float alpha;
+} bc_color;
+
+// 8-bit sRGB channels with alpha from 0 to 1
+typedef struct bc_rgb {
+  uint8_t r;
+  uint8_t g;
+  uint8_t b;
+  float alpha;
+} bc_rgb;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the bigcolor library version as a static NUL-terminated string
+const char *bc_version(void);
+
+// Parses any color string bigcolor accepts. `format` may be null; when
+// given it receives the format the input was written in.
+//
+// # Safety
+//
+// `input` must be a NUL-terminated string. `out` and `format`, when not
+// null, must point to writable memory.
+enum bc_status bc_parse(const char *input, struct bc_color *out, enum bc_format *format);
+
+// Writes the color in the given `bc_format` to `*out` as a newly allocated
+// string, to be released with `bc_string_free`
+//
+// # Safety
+//
+// `out` must point to writable memory.
+enum bc_status bc_to_string(struct bc_color color, uint32_t format, char **out);
+
+// Releases a string returned by the library. Null is ignored.
+//
+// # Safety
+//
+// `string` must come from this library and must not be used afterwards.
+void bc_string_free(char *string);
+
+// Returns a human-readable description of a `bc_status`
+const char *bc_status_message(uint32_t status);
+
+// Creates a color from 8-bit sRGB channels and alpha
+struct bc_color bc_from_rgb(uint8_t r, uint8_t g, uint8_t b, float alpha);
+
+// Creates a color from HSL with hue in degrees and saturation and
+// lightness from 0 to 1
+struct bc_color bc_from_hsl(float h, float s, float l, float alpha);
+
+// Returns the color as 8-bit sRGB
+struct bc_rgb bc_to_rgb(struct bc_color color);
+
+// Lightens by `amount` percent; NaN uses the default of 10
+struct bc_color bc_lighten(struct bc_color color, float amount);
+
+// Darkens by `amount` percent; NaN uses the default of 10
+struct bc_color bc_darken(struct bc_color color, float amount);
+
+// Brightens by `amount` percent; NaN uses the default of 10
+struct bc_color bc_brighten(struct bc_color color, float amount);
+
+// Saturates by `amount` percent; NaN uses the default of 10
+struct bc_color bc_saturate(struct bc_color color, float amount);
+
+// Desaturates by `amount` percent; NaN uses the default of 10
+struct bc_color bc_desaturate(struct bc_color color, float amount);
+
+// Removes all saturation
+struct bc_color bc_greyscale(struct bc_color color);
+
+// Rotates the hue by `degrees`
+struct bc_color bc_spin(struct bc_color color, float degrees);
+
+// Returns the color with a new alpha
+struct bc_color bc_set_alpha(struct bc_color color, float alpha);
+
+// Mixes two colors; `amount` is the percentage of `b`, 50 when NaN
+struct bc_color bc_mix(struct bc_color a, struct bc_color b, float amount);
+
+// Returns the relative luminance from 0 to 1
+float bc_luminance(struct bc_color color);
+
+// Returns true if the color is perceived as dark
+bool bc_is_dark(struct bc_color color);
+
+// Returns the WCAG 2 contrast ratio, from 1 to 21
+float bc_contrast_ratio(struct bc_color foreground, struct bc_color background);
+
+// Returns the APCA lightness contrast (Lc) of text on a background
+float bc_apca_contrast(struct bc_color foreground, struct bc_color background);
+
+// Returns true if the pair meets the WCAG 2 `bc_level` for the `bc_size`
+// of text, and false for values outside those enums
+bool bc_is_readable(struct bc_color foreground,
+                    struct bc_color background,
+                    uint32_t level,
+                    uint32_t size);
+
+// Returns the perceptual distance (ΔEOK) between two colors
+float bc_delta_e(struct bc_color a, struct bc_color b);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BIGCOLOR_H */
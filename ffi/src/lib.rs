@@ -0,0 +1,497 @@
+// C ABI for bigcolor
+//
+// Colors cross the boundary as `bc_color`, a plain OKLCH struct, so no handle
+// has to be freed. Fallible functions return a `bc_status` and write their
+// result through an out pointer. Strings returned by the library are owned by
+// the caller and must be released with `bc_string_free`.
+
+use std::ffi::{c_char, CStr, CString};
+use std::sync::OnceLock;
+
+use bigcolor::{is_readable, mix, BigColor, ColorFormat, WCAG2Level, WCAG2Params, WCAG2Size};
+
+/// A color as OKLCH lightness (0 to 1), chroma, hue in degrees and alpha.
+/// Values outside sRGB are kept.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BcColor {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+    pub alpha: f32,
+}
+
+/// 8-bit sRGB channels with alpha from 0 to 1
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BcRgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub alpha: f32,
+}
+
+/// Result of a fallible call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BcStatus {
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The input could not be parsed as a color
+    InvalidColor = 3,
+    /// `BC_FORMAT_NAME` was requested for a color without a CSS name
+    Unnamed = 4,
+    /// An enum argument was not one of its declared values
+    InvalidArgument = 5,
+}
+
+/// Color formats, as in the Rust `ColorFormat`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BcFormat {
+    Rgb = 0,
+    Prgb = 1,
+    Hex = 2,
+    Hex3 = 3,
+    Hex6 = 4,
+    Hex8 = 5,
+    Hsl = 6,
+    Hsv = 7,
+    Hsb = 8,
+    Lab = 9,
+    Lch = 10,
+    Oklab = 11,
+    Oklch = 12,
+    Cmyk = 13,
+    Name = 14,
+}
+
+/// WCAG 2 conformance level
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BcLevel {
+    Aa = 0,
+    Aaa = 1,
+}
+
+/// WCAG 2 text size
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BcSize {
+    Small = 0,
+    Large = 1,
+}
+
+// C callers can pass any integer where an enum is expected, and an
+// undeclared value in a Rust enum is undefined behavior, so enum arguments
+// cross the boundary as `u32` and are checked here
+macro_rules! try_from_u32 {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        impl TryFrom<u32> for $name {
+            type Error = BcStatus;
+
+            fn try_from(value: u32) -> Result<Self, BcStatus> {
+                [$($name::$variant),*]
+                    .into_iter()
+                    .find(|variant| *variant as u32 == value)
+                    .ok_or(BcStatus::InvalidArgument)
+            }
+        }
+    };
+}
+
+try_from_u32!(BcStatus { Ok, NullPointer, InvalidUtf8, InvalidColor, Unnamed, InvalidArgument });
+try_from_u32!(BcFormat { Rgb, Prgb, Hex, Hex3, Hex6, Hex8, Hsl, Hsv, Hsb, Lab, Lch, Oklab, Oklch, Cmyk, Name });
+try_from_u32!(BcLevel { Aa, Aaa });
+try_from_u32!(BcSize { Small, Large });
+
+impl From<BcFormat> for ColorFormat {
+    fn from(format: BcFormat) -> Self {
+        match format {
+            BcFormat::Rgb => ColorFormat::RGB,
+            BcFormat::Prgb => ColorFormat::PRGB,
+            BcFormat::Hex => ColorFormat::HEX,
+            BcFormat::Hex3 => ColorFormat::HEX3,
+            BcFormat::Hex6 => ColorFormat::HEX6,
+            BcFormat::Hex8 => ColorFormat::HEX8,
+            BcFormat::Hsl => ColorFormat::HSL,
+            BcFormat::Hsv => ColorFormat::HSV,
+            BcFormat::Hsb => ColorFormat::HSB,
+            BcFormat::Lab => ColorFormat::LAB,
+            BcFormat::Lch => ColorFormat::LCH,
+            BcFormat::Oklab => ColorFormat::OKLAB,
+            BcFormat::Oklch => ColorFormat::OKLCH,
+            BcFormat::Cmyk => ColorFormat::CMYK,
+            BcFormat::Name => ColorFormat::NAME,
+        }
+    }
+}
+
+impl BcFormat {
+    fn from_color_format(format: ColorFormat) -> Option<Self> {
+        Some(match format {
+            ColorFormat::RGB => BcFormat::Rgb,
+            ColorFormat::PRGB => BcFormat::Prgb,
+            ColorFormat::HEX => BcFormat::Hex,
+            ColorFormat::HEX3 => BcFormat::Hex3,
+            ColorFormat::HEX6 => BcFormat::Hex6,
+            ColorFormat::HEX8 => BcFormat::Hex8,
+            ColorFormat::HSL => BcFormat::Hsl,
+            ColorFormat::HSV => BcFormat::Hsv,
+            ColorFormat::HSB => BcFormat::Hsb,
+            ColorFormat::LAB => BcFormat::Lab,
+            ColorFormat::LCH => BcFormat::Lch,
+            ColorFormat::OKLAB => BcFormat::Oklab,
+            ColorFormat::OKLCH => BcFormat::Oklch,
+            ColorFormat::CMYK => BcFormat::Cmyk,
+            ColorFormat::NAME => BcFormat::Name,
+            ColorFormat::INVALID => return None,
+        })
+    }
+}
+
+impl From<&BigColor> for BcColor {
+    fn from(color: &BigColor) -> Self {
+        let oklch = color.to_oklch();
+        BcColor {
+            l: oklch.l,
+            c: oklch.c,
+            h: oklch.h,
+            alpha: oklch.alpha,
+        }
+    }
+}
+
+impl From<BcColor> for BigColor {
+    fn from(color: BcColor) -> Self {
+        BigColor::from_oklch(color.l, color.c, color.h, color.alpha)
+    }
+}
+
+/// Applies an in-place `BigColor` modification to a copy of the color
+fn modified(color: BcColor, modify: impl FnOnce(&mut BigColor)) -> BcColor {
+    let mut color = BigColor::from(color);
+    modify(&mut color);
+    BcColor::from(&color)
+}
+
+fn optional(amount: f32) -> Option<f32> {
+    if amount.is_nan() {
+        None
+    } else {
+        Some(amount)
+    }
+}
+
+/// Returns the bigcolor library version as a static NUL-terminated string
+#[no_mangle]
+pub extern "C" fn bc_version() -> *const c_char {
+    static VERSION: OnceLock<CString> = OnceLock::new();
+    VERSION
+        .get_or_init(|| CString::new(bigcolor::VERSION).unwrap())
+        .as_ptr()
+}
+
+/// Parses any color string bigcolor accepts. `format` may be null; when
+/// given it receives the format the input was written in.
+///
+/// # Safety
+///
+/// `input` must be a NUL-terminated string. `out` and `format`, when not
+/// null, must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn bc_parse(input: *const c_char, out: *mut BcColor, format: *mut BcFormat) -> BcStatus {
+    if input.is_null() || out.is_null() {
+        return BcStatus::NullPointer;
+    }
+    let Ok(input) = CStr::from_ptr(input).to_str() else {
+        return BcStatus::InvalidUtf8;
+    };
+    let color = BigColor::new(input);
+    let Some(parsed_format) = BcFormat::from_color_format(color.get_format()).filter(|_| color.is_valid()) else {
+        return BcStatus::InvalidColor;
+    };
+    *out = BcColor::from(&color);
+    if !format.is_null() {
+        *format = parsed_format;
+    }
+    BcStatus::Ok
+}
+
+/// Writes the color in the given `bc_format` to `*out` as a newly allocated
+/// string, to be released with `bc_string_free`
+///
+/// # Safety
+///
+/// `out` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn bc_to_string(color: BcColor, format: u32, out: *mut *mut c_char) -> BcStatus {
+    if out.is_null() {
+        return BcStatus::NullPointer;
+    }
+    let format = match BcFormat::try_from(format) {
+        Ok(format) => format,
+        Err(status) => return status,
+    };
+    let color = BigColor::from(color);
+    if format == BcFormat::Name && color.to_name().is_none() {
+        return BcStatus::Unnamed;
+    }
+    // Rendered colors never contain NUL bytes
+    *out = CString::new(color.to(format.into())).unwrap_or_default().into_raw();
+    BcStatus::Ok
+}
+
+/// Releases a string returned by the library. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must come from this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn bc_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Returns a human-readable description of a `bc_status`
+#[no_mangle]
+pub extern "C" fn bc_status_message(status: u32) -> *const c_char {
+    let message: &'static str = match BcStatus::try_from(status) {
+        Ok(BcStatus::Ok) => "ok\0",
+        Ok(BcStatus::NullPointer) => "null pointer argument\0",
+        Ok(BcStatus::InvalidUtf8) => "string is not valid UTF-8\0",
+        Ok(BcStatus::InvalidColor) => "invalid color\0",
+        Ok(BcStatus::Unnamed) => "color has no CSS name\0",
+        Ok(BcStatus::InvalidArgument) => "invalid enum argument\0",
+        Err(_) => "unknown status\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Creates a color from 8-bit sRGB channels and alpha
+#[no_mangle]
+pub extern "C" fn bc_from_rgb(r: u8, g: u8, b: u8, alpha: f32) -> BcColor {
+    BcColor::from(&BigColor::from_rgb(r, g, b, alpha))
+}
+
+/// Creates a color from HSL with hue in degrees and saturation and
+/// lightness from 0 to 1
+#[no_mangle]
+pub extern "C" fn bc_from_hsl(h: f32, s: f32, l: f32, alpha: f32) -> BcColor {
+    BcColor::from(&BigColor::from_hsl(h, s, l, alpha))
+}
+
+/// Returns the color as 8-bit sRGB
+#[no_mangle]
+pub extern "C" fn bc_to_rgb(color: BcColor) -> BcRgb {
+    let rgb = BigColor::from(color).to_rgb();
+    BcRgb {
+        r: rgb.r,
+        g: rgb.g,
+        b: rgb.b,
+        alpha: rgb.a,
+    }
+}
+
+/// Lightens by `amount` percent; NaN uses the default of 10
+#[no_mangle]
+pub extern "C" fn bc_lighten(color: BcColor, amount: f32) -> BcColor {
+    modified(color, |c| {
+        c.lighten(optional(amount));
+    })
+}
+
+/// Darkens by `amount` percent; NaN uses the default of 10
+#[no_mangle]
+pub extern "C" fn bc_darken(color: BcColor, amount: f32) -> BcColor {
+    modified(color, |c| {
+        c.darken(optional(amount));
+    })
+}
+
+/// Brightens by `amount` percent; NaN uses the default of 10
+#[no_mangle]
+pub extern "C" fn bc_brighten(color: BcColor, amount: f32) -> BcColor {
+    modified(color, |c| {
+        c.brighten(optional(amount));
+    })
+}
+
+/// Saturates by `amount` percent; NaN uses the default of 10
+#[no_mangle]
+pub extern "C" fn bc_saturate(color: BcColor, amount: f32) -> BcColor {
+    modified(color, |c| {
+        c.saturate(optional(amount));
+    })
+}
+
+/// Desaturates by `amount` percent; NaN uses the default of 10
+#[no_mangle]
+pub extern "C" fn bc_desaturate(color: BcColor, amount: f32) -> BcColor {
+    modified(color, |c| {
+        c.desaturate(optional(amount));
+    })
+}
+
+/// Removes all saturation
+#[no_mangle]
+pub extern "C" fn bc_greyscale(color: BcColor) -> BcColor {
+    modified(color, |c| {
+        c.greyscale();
+    })
+}
+
+/// Rotates the hue by `degrees`
+#[no_mangle]
+pub extern "C" fn bc_spin(color: BcColor, degrees: f32) -> BcColor {
+    modified(color, |c| {
+        c.spin(degrees);
+    })
+}
+
+/// Returns the color with a new alpha
+#[no_mangle]
+pub extern "C" fn bc_set_alpha(color: BcColor, alpha: f32) -> BcColor {
+    modified(color, |c| {
+        c.set_alpha(alpha);
+    })
+}
+
+/// Mixes two colors; `amount` is the percentage of `b`, 50 when NaN
+#[no_mangle]
+pub extern "C" fn bc_mix(a: BcColor, b: BcColor, amount: f32) -> BcColor {
+    BcColor::from(&mix(&a.into(), &b.into(), optional(amount)))
+}
+
+/// Returns the relative luminance from 0 to 1
+#[no_mangle]
+pub extern "C" fn bc_luminance(color: BcColor) -> f32 {
+    BigColor::from(color).get_luminance()
+}
+
+/// Returns true if the color is perceived as dark
+#[no_mangle]
+pub extern "C" fn bc_is_dark(color: BcColor) -> bool {
+    BigColor::from(color).is_dark()
+}
+
+/// Returns the WCAG 2 contrast ratio, from 1 to 21
+#[no_mangle]
+pub extern "C" fn bc_contrast_ratio(foreground: BcColor, background: BcColor) -> f32 {
+    BigColor::from(foreground).get_contrast_ratio(&background.into())
+}
+
+/// Returns the APCA lightness contrast (Lc) of text on a background
+#[no_mangle]
+pub extern "C" fn bc_apca_contrast(foreground: BcColor, background: BcColor) -> f32 {
+    BigColor::from(foreground).get_apca_contrast(&background.into())
+}
+
+/// Returns true if the pair meets the WCAG 2 `bc_level` for the `bc_size`
+/// of text, and false for values outside those enums
+#[no_mangle]
+pub extern "C" fn bc_is_readable(foreground: BcColor, background: BcColor, level: u32, size: u32) -> bool {
+    let (Ok(level), Ok(size)) = (BcLevel::try_from(level), BcSize::try_from(size)) else {
+        return false;
+    };
+    let params = WCAG2Params {
+        level: match level {
+            BcLevel::Aa => WCAG2Level::AA,
+            BcLevel::Aaa => WCAG2Level::AAA,
+        },
+        size: match size {
+            BcSize::Small => WCAG2Size::Small,
+            BcSize::Large => WCAG2Size::Large,
+        },
+    };
+    is_readable(&foreground.into(), &background.into(), Some(params))
+}
+
+/// Returns the perceptual distance (ΔEOK) between two colors
+#[no_mangle]
+pub extern "C" fn bc_delta_e(a: BcColor, b: BcColor) -> f32 {
+    BigColor::from(a).delta_e(&b.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn parse(input: &str) -> (BcStatus, BcColor, BcFormat) {
+        let input = CString::new(input).unwrap();
+        let mut color = BcColor { l: 0.0, c: 0.0, h: 0.0, alpha: 0.0 };
+        let mut format = BcFormat::Rgb;
+        let status = unsafe { bc_parse(input.as_ptr(), &mut color, &mut format) };
+        (status, color, format)
+    }
+
+    fn render(color: BcColor, format: BcFormat) -> Result<String, BcStatus> {
+        let mut out = ptr::null_mut();
+        let status = unsafe { bc_to_string(color, format as u32, &mut out) };
+        if status != BcStatus::Ok {
+            return Err(status);
+        }
+        let text = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+        unsafe { bc_string_free(out) };
+        Ok(text)
+    }
+
+    #[test]
+    fn test_matches_rust_semantics() {
+        for input in ["#1a6ef5", "rgba(255, 0, 0, 0.5)", "oklch(70% 0.1 200)", "hsl(120, 50%, 40%)", "rebeccapurple"] {
+            let (status, color, _) = parse(input);
+            assert_eq!(status, BcStatus::Ok, "{}", input);
+            let rust = BigColor::new(input);
+            for format in [BcFormat::Hex8, BcFormat::Rgb, BcFormat::Hsl, BcFormat::Oklch, BcFormat::Lab, BcFormat::Cmyk] {
+                assert_eq!(render(color, format).unwrap(), rust.to(format.into()), "{} as {:?}", input, format);
+            }
+        }
+        let (_, red, format) = parse("red");
+        assert_eq!(format, BcFormat::Name);
+        assert_eq!(bc_to_rgb(bc_darken(red, 20.0)), {
+            let rgb = BigColor::new("red").darken(Some(20.0)).to_rgb();
+            BcRgb { r: rgb.r, g: rgb.g, b: rgb.b, alpha: rgb.a }
+        });
+        assert_eq!(render(red, BcFormat::Name).unwrap(), "red");
+        assert_eq!(render(bc_from_rgb(1, 2, 3, 1.0), BcFormat::Name), Err(BcStatus::Unnamed));
+    }
+
+    #[test]
+    fn test_errors_and_contrast() {
+        assert_eq!(parse("not a color").0, BcStatus::InvalidColor);
+        let status = unsafe { bc_parse(ptr::null(), ptr::null_mut(), ptr::null_mut()) };
+        assert_eq!(status, BcStatus::NullPointer);
+        let message = unsafe { CStr::from_ptr(bc_status_message(BcStatus::InvalidColor as u32)) };
+        assert_eq!(message.to_str().unwrap(), "invalid color");
+        let message = unsafe { CStr::from_ptr(bc_status_message(99)) };
+        assert_eq!(message.to_str().unwrap(), "unknown status");
+        let version = unsafe { CStr::from_ptr(bc_version()) };
+        assert_eq!(version.to_str().unwrap(), bigcolor::VERSION);
+        let mut out = ptr::null_mut();
+        let status = unsafe { bc_to_string(bc_from_rgb(1, 2, 3, 1.0), 99, &mut out) };
+        assert_eq!(status, BcStatus::InvalidArgument);
+        assert!(out.is_null());
+
+        let grey = bc_from_rgb(0x76, 0x76, 0x76, 1.0);
+        let white = bc_from_rgb(255, 255, 255, 1.0);
+        assert!((bc_contrast_ratio(grey, white) - 4.54).abs() < 0.01);
+        assert!(bc_is_readable(grey, white, BcLevel::Aa as u32, BcSize::Small as u32));
+        assert!(!bc_is_readable(grey, white, BcLevel::Aaa as u32, BcSize::Small as u32));
+        assert!(!bc_is_readable(grey, white, 7, BcSize::Small as u32));
+    }
+
+    #[test]
+    fn test_checked_in_header_is_current() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/bigcolor.h"));
+        let checked_in = include_str!("../include/bigcolor.h");
+        assert!(
+            generated == checked_in,
+            "include/bigcolor.h is out of date; rebuild with BIGCOLOR_FFI_UPDATE_HEADER=1"
+        );
+    }
+}
@@ -0,0 +1,358 @@
+// Color analysis of a document: literals, picker presentations and diagnostics
+
+use lazy_static::lazy_static;
+use lsp_types::{Color, ColorInformation, ColorPresentation, Diagnostic, DiagnosticSeverity, Position, Range, TextEdit};
+use regex::Regex;
+
+use bigcolor::accessibility::ContrastTarget;
+use bigcolor::scan::{scan_source, SourceScan, Syntax};
+use bigcolor::{declared_gamut, BigColor, ColorFormat, Gamut};
+
+/// Converts between byte offsets and LSP positions, which count UTF-16 code units
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { line_starts }
+    }
+
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = text[self.line_starts[line]..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, text: &str, span: std::ops::Range<usize>) -> Range {
+        Range::new(self.position(text, span.start), self.position(text, span.end))
+    }
+
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+        let mut units = 0;
+        for (i, c) in text[start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        text.len()
+    }
+}
+
+/// Settings a client can pass in `initializationOptions`
+#[derive(Debug, Clone, Copy)]
+pub struct AnalysisOptions {
+    /// Contrast declared text and background pairs must reach
+    pub contrast: ContrastTarget,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions {
+            contrast: ContrastTarget::Ratio(4.5),
+        }
+    }
+}
+
+impl AnalysisOptions {
+    /// Reads `{"minimumContrast": 4.5}` or `{"minimumApca": 60}`
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let mut options = AnalysisOptions::default();
+        if let Some(ratio) = value.get("minimumContrast").and_then(|v| v.as_f64()) {
+            options.contrast = ContrastTarget::Ratio(ratio as f32);
+        } else if let Some(lc) = value.get("minimumApca").and_then(|v| v.as_f64()) {
+            options.contrast = ContrastTarget::Apca(lc as f32);
+        }
+        options
+    }
+}
+
+/// Picks the scanner syntax for an LSP language id, or the file extension
+pub fn syntax_for(language_id: &str, uri: &str) -> Syntax {
+    match language_id {
+        "css" | "postcss" => Syntax::Css,
+        "scss" | "sass" => Syntax::Scss,
+        "less" => Syntax::Less,
+        "javascript" | "javascriptreact" | "typescript" | "typescriptreact" => Syntax::JavaScript,
+        _ => Syntax::from_extension(uri.rsplit('.').next().unwrap_or_default()),
+    }
+}
+
+fn to_lsp_color(color: &BigColor) -> Color {
+    let rgb = color.to_rgb();
+    Color {
+        red: rgb.r as f32 / 255.0,
+        green: rgb.g as f32 / 255.0,
+        blue: rgb.b as f32 / 255.0,
+        alpha: rgb.a,
+    }
+}
+
+/// Every color literal of a document with its range
+pub fn document_colors(text: &str, syntax: Syntax) -> Vec<ColorInformation> {
+    let index = LineIndex::new(text);
+    scan_source(text, syntax, None)
+        .matches
+        .iter()
+        .map(|m| ColorInformation {
+            range: index.range(text, m.span.clone()),
+            color: to_lsp_color(&m.color),
+        })
+        .collect()
+}
+
+/// Formats offered in the color picker, after the format of the edited literal
+const PRESENTATION_FORMATS: [ColorFormat; 9] = [
+    ColorFormat::HEX,
+    ColorFormat::RGB,
+    ColorFormat::HSL,
+    ColorFormat::HSV,
+    ColorFormat::LAB,
+    ColorFormat::LCH,
+    ColorFormat::OKLAB,
+    ColorFormat::OKLCH,
+    ColorFormat::CMYK,
+];
+
+/// Renderings of a picked color in every format. `current` is the literal
+/// being edited, whose format is offered first.
+pub fn color_presentations(color: &Color, range: Range, current: Option<&str>) -> Vec<ColorPresentation> {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let picked = BigColor::from_rgb(channel(color.red), channel(color.green), channel(color.blue), color.alpha);
+
+    let mut formats: Vec<ColorFormat> = PRESENTATION_FORMATS.to_vec();
+    if picked.to_name().is_some() {
+        formats.push(ColorFormat::NAME);
+    }
+    if let Some(format) = current.map(BigColor::new).filter(BigColor::is_valid).map(|c| c.get_format()) {
+        let format = match format {
+            ColorFormat::HEX3 | ColorFormat::HEX6 | ColorFormat::HEX8 => ColorFormat::HEX,
+            ColorFormat::NAME if picked.to_name().is_none() => ColorFormat::HEX,
+            other => other,
+        };
+        formats.retain(|f| *f != format);
+        formats.insert(0, format);
+    }
+
+    formats
+        .into_iter()
+        .map(|format| {
+            let format = if format == ColorFormat::HEX && picked.get_alpha() < 1.0 { ColorFormat::HEX8 } else { format };
+            let label = picked.to(format);
+            ColorPresentation {
+                text_edit: Some(TextEdit::new(range, label.clone())),
+                label,
+                additional_text_edits: None,
+            }
+        })
+        .collect()
+}
+
+lazy_static! {
+    static ref COLOR_FUNCTION: Regex =
+        Regex::new(r"(?i)\b(?:rgba?|hsla?|hsva?|hwb|lab|lch|oklab|oklch|cmyk)\([^()]*\)").unwrap();
+    static ref HEX_VALUE: Regex = Regex::new(r"#[0-9a-fA-F]+\b").unwrap();
+    static ref BLOCK: Regex = Regex::new(r"\{([^{}]*)\}").unwrap();
+    static ref PAIR_PROPERTY: Regex =
+        Regex::new(r"(?i)(?:^|[;{\s])(color|background-color|background)\s*:\s*([^;}]*)").unwrap();
+    static ref VAR_REFERENCE: Regex = Regex::new(r"var\(\s*(--[\w-]+)").unwrap();
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some("bigcolor".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Flags invalid colors, out-of-gamut values and declared text and background
+/// pairs below the contrast target
+pub fn diagnostics(text: &str, syntax: Syntax, options: &AnalysisOptions) -> Vec<Diagnostic> {
+    let index = LineIndex::new(text);
+    let scan = scan_source(text, syntax, None);
+    let overlaps = |start: usize, end: usize| scan.matches.iter().any(|m| m.span.start < end && start < m.span.end);
+    let mut diagnostics = Vec::new();
+
+    // Color functions with literal arguments the parser rejects. Calls with
+    // variables or identifiers as arguments are left alone.
+    for found in COLOR_FUNCTION.find_iter(text) {
+        let arguments = &found.as_str()[found.as_str().find('(').unwrap_or(0)..];
+        let literal_arguments = arguments
+            .chars()
+            .all(|c| c.is_ascii_digit() || " \t.,%/+-()".contains(c) || c.is_ascii_alphabetic() && "degnoe".contains(c));
+        if literal_arguments && !overlaps(found.start(), found.end()) && !BigColor::new(found.as_str()).is_valid() {
+            let range = index.range(text, found.range());
+            diagnostics.push(diagnostic(range, DiagnosticSeverity::ERROR, format!("invalid color {}", found.as_str())));
+        }
+    }
+
+    // Hex values of the wrong length in value position
+    for found in HEX_VALUE.find_iter(text) {
+        let digits = found.as_str().len() - 1;
+        let next = text[found.end()..].trim_start_matches([' ', '\t']).chars().next();
+        let in_value = matches!(next, None | Some(';' | ',' | ')' | '"' | '\'' | '`' | '\n' | '\r' | '}'));
+        if !matches!(digits, 3 | 4 | 6 | 8) && in_value && !overlaps(found.start(), found.end()) {
+            let range = index.range(text, found.range());
+            let message = format!("invalid hex color {}: expected 3, 4, 6 or 8 digits", found.as_str());
+            diagnostics.push(diagnostic(range, DiagnosticSeverity::ERROR, message));
+        }
+    }
+
+    // Literals whose declared channels sRGB cannot show
+    for m in &scan.matches {
+        let literal = m.as_str(text);
+        let gamut = match declared_gamut(literal) {
+            Some(Gamut::DisplayP3) => "inside Display P3",
+            Some(Gamut::Wide) => "outside Display P3 too",
+            Some(Gamut::Srgb) | None => continue,
+        };
+        let message = format!(
+            "{} is outside the sRGB gamut ({}) and is shown as {} on sRGB displays",
+            literal,
+            gamut,
+            m.color.to_hex_string(false)
+        );
+        diagnostics.push(diagnostic(index.range(text, m.span.clone()), DiagnosticSeverity::WARNING, message));
+    }
+
+    contrast_diagnostics(text, &scan, &index, options, &mut diagnostics);
+    diagnostics
+}
+
+/// Returns the color of a declaration value: its first literal, or the
+/// custom property it refers to
+fn value_color(scan: &SourceScan, value_start: usize, value_end: usize, value: &str) -> Option<(BigColor, usize, usize)> {
+    if let Some(m) = scan.matches.iter().find(|m| m.span.start >= value_start && m.span.end <= value_end) {
+        return Some((m.color.clone(), m.span.start, m.span.end));
+    }
+    let name = VAR_REFERENCE.captures(value)?;
+    Some((scan.variable(&name[1])?.clone(), value_start, value_end))
+}
+
+fn contrast_diagnostics(
+    text: &str,
+    scan: &SourceScan,
+    index: &LineIndex,
+    options: &AnalysisOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let target = options.contrast;
+    let describe = |foreground: &BigColor, background: &BigColor| match target {
+        ContrastTarget::Apca(lc) => format!(
+            "APCA Lc {:.1} of {} on {} is below {}",
+            foreground.get_apca_contrast(background).abs(),
+            foreground.to_hex_string(false),
+            background.to_hex_string(false),
+            lc
+        ),
+        ContrastTarget::Ratio(ratio) => format!(
+            "contrast {:.2}:1 of {} on {} is below {}:1",
+            foreground.get_contrast_ratio(background),
+            foreground.to_hex_string(false),
+            background.to_hex_string(false),
+            ratio
+        ),
+        ContrastTarget::Wcag2(params) => format!(
+            "contrast {:.2}:1 of {} on {} fails WCAG {:?}",
+            foreground.get_contrast_ratio(background),
+            foreground.to_hex_string(false),
+            background.to_hex_string(false),
+            params.level
+        ),
+    };
+
+    // `color` and `background` declared in the same rule
+    for block in BLOCK.captures_iter(text) {
+        let body = block.get(1).expect("group 1");
+        let mut foreground = None;
+        let mut background = None;
+        for declaration in PAIR_PROPERTY.captures_iter(body.as_str()) {
+            let value = declaration.get(2).expect("group 2");
+            let (start, end) = (body.start() + value.start(), body.start() + value.end());
+            let Some(color) = value_color(scan, start, end, value.as_str()) else { continue };
+            if declaration[1].eq_ignore_ascii_case("color") {
+                foreground = Some(color);
+            } else {
+                background = Some(color);
+            }
+        }
+        if let (Some((fg, start, end)), Some((bg, _, _))) = (foreground, background) {
+            if !target.is_met(&fg, &bg) {
+                let range = index.range(text, start..end);
+                diagnostics.push(diagnostic(range, DiagnosticSeverity::WARNING, describe(&fg, &bg)));
+            }
+        }
+    }
+
+    // Token pairs such as `--primary` and `--on-primary`
+    for definition in &scan.definitions {
+        let Some(foreground) = &definition.color else { continue };
+        let partner = ["--on-", "$on-", "@on-"]
+            .iter()
+            .find_map(|prefix| definition.name.strip_prefix(prefix).map(|rest| format!("{}{}", &prefix[..prefix.len() - 3], rest)));
+        let Some(background) = partner.as_deref().and_then(|name| scan.variable(name)) else { continue };
+        if !target.is_met(foreground, background) {
+            let range = index.range(text, definition.span.clone());
+            diagnostics.push(diagnostic(range, DiagnosticSeverity::WARNING, describe(foreground, background)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_count_utf16_units() {
+        let text = "a { content: \"😀\"; color: #fff; }\n.b { color: #f00 }";
+        let index = LineIndex::new(text);
+        let offset = text.find("#fff").unwrap();
+        let position = index.position(text, offset);
+        assert_eq!(position, Position::new(0, 26));
+        assert_eq!(index.offset(text, position), offset);
+
+        let colors = document_colors(text, Syntax::Css);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[1].range.start, Position::new(1, 12));
+        assert_eq!(colors[1].color.red, 1.0);
+    }
+
+    #[test]
+    fn test_presentations_offer_current_format_first() {
+        let red = Color { red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0 };
+        let range = Range::new(Position::new(0, 0), Position::new(0, 4));
+        let labels: Vec<String> =
+            color_presentations(&red, range, Some("oklch(50% 0.1 20)")).into_iter().map(|p| p.label).collect();
+        assert!(labels[0].starts_with("oklch("));
+        assert!(labels.contains(&"#ff0000".to_string()));
+        assert!(labels.contains(&"red".to_string()));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let css = concat!(
+            ":root { --primary: #1a6ef5; --on-primary: #3b82f6; }\n",
+            ".a { color: #777; background: var(--surface, #fff); background-color: #888; }\n",
+            ".b { color: oklch(70% 0.35 145); border-color: #12345; outline-color: rgb(300, 0); }\n",
+        );
+        let messages: Vec<String> = diagnostics(css, Syntax::Css, &AnalysisOptions::default())
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(messages.len(), 5, "{:#?}", messages);
+        assert!(messages.iter().any(|m| m.starts_with("invalid color rgb(300, 0)")));
+        assert!(messages.iter().any(|m| m.starts_with("invalid hex color #12345")));
+        assert!(messages.iter().any(|m| m.contains("outside the sRGB gamut")));
+        assert!(messages.iter().any(|m| m.contains("#777777 on #888888")));
+        assert!(messages.iter().any(|m| m.contains("#3b82f6 on #1a6ef5")));
+    }
+}
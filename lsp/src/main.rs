@@ -0,0 +1,24 @@
+// Language server for color literals, speaking LSP over stdio
+
+mod analysis;
+mod server;
+
+use lsp_server::Connection;
+use lsp_types::{ColorProviderCapability, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind};
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        color_provider: Some(ColorProviderCapability::Simple(true)),
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        ..Default::default()
+    };
+    let initialize = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let options = server::options_from_initialize(&initialize);
+
+    server::Server::new(options).run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
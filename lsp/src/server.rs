@@ -0,0 +1,197 @@
+// Request and notification handling for the language server
+
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{ColorPresentationRequest, DocumentColor, Request as _};
+use lsp_types::{
+    ColorPresentationParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentColorParams, PublishDiagnosticsParams, Uri,
+};
+use serde_json::Value;
+
+use bigcolor::scan::Syntax;
+
+use crate::analysis::{color_presentations, diagnostics, document_colors, syntax_for, AnalysisOptions, LineIndex};
+
+struct Document {
+    text: String,
+    syntax: Syntax,
+}
+
+/// Open documents and the analysis settings of the session
+pub struct Server {
+    documents: HashMap<String, Document>,
+    options: AnalysisOptions,
+}
+
+impl Server {
+    pub fn new(options: AnalysisOptions) -> Self {
+        Server {
+            documents: HashMap::new(),
+            options,
+        }
+    }
+
+    /// Serves requests until the client shuts the connection down
+    pub fn run(&mut self, connection: &Connection) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    connection.sender.send(Message::Response(self.handle_request(request)))?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(published) = self.handle_notification(notification) {
+                        connection.sender.send(Message::Notification(published))?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let result = match request.method.as_str() {
+            DocumentColor::METHOD => serde_json::from_value::<DocumentColorParams>(request.params).map(|params| {
+                let colors = self
+                    .documents
+                    .get(params.text_document.uri.as_str())
+                    .map(|document| document_colors(&document.text, document.syntax))
+                    .unwrap_or_default();
+                serde_json::to_value(colors)
+            }),
+            ColorPresentationRequest::METHOD => {
+                serde_json::from_value::<ColorPresentationParams>(request.params).map(|params| {
+                    let current = self.documents.get(params.text_document.uri.as_str()).map(|document| {
+                        let index = LineIndex::new(&document.text);
+                        let start = index.offset(&document.text, params.range.start);
+                        let end = index.offset(&document.text, params.range.end).max(start);
+                        &document.text[start..end]
+                    });
+                    serde_json::to_value(color_presentations(&params.color, params.range, current))
+                })
+            }
+            method => {
+                let message = format!("unsupported request {}", method);
+                return Response::new_err(request.id, ErrorCode::MethodNotFound as i32, message);
+            }
+        };
+        match result {
+            Ok(Ok(value)) => Response::new_ok(request.id, value),
+            Ok(Err(error)) | Err(error) => {
+                Response::new_err(request.id, ErrorCode::InvalidParams as i32, error.to_string())
+            }
+        }
+    }
+
+    /// Updates the document store and returns the diagnostics to publish
+    fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                let document = params.text_document;
+                let syntax = syntax_for(&document.language_id, document.uri.as_str());
+                self.documents
+                    .insert(document.uri.as_str().to_string(), Document { text: document.text, syntax });
+                document.uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                // Full synchronization: the last change holds the whole text
+                let text = params.content_changes.into_iter().last()?.text;
+                self.documents.get_mut(params.text_document.uri.as_str())?.text = text;
+                params.text_document.uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                self.documents.remove(params.text_document.uri.as_str());
+                return Some(publish(params.text_document.uri, Vec::new()));
+            }
+            _ => return None,
+        };
+        let document = self.documents.get(uri.as_str())?;
+        Some(publish(uri, diagnostics(&document.text, document.syntax, &self.options)))
+    }
+}
+
+fn publish(uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+    let params = PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    };
+    Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+}
+
+/// Reads the analysis settings from the client's `initializationOptions`
+pub fn options_from_initialize(params: &Value) -> AnalysisOptions {
+    params
+        .get("initializationOptions")
+        .map(AnalysisOptions::from_json)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_rpc_session() {
+        let (server_side, client) = Connection::memory();
+        let worker = std::thread::spawn(move || Server::new(AnalysisOptions::default()).run(&server_side));
+        let uri = "file:///styles/site.css";
+
+        client
+            .sender
+            .send(Message::Notification(Notification::new(
+                DidOpenTextDocument::METHOD.to_string(),
+                json!({ "textDocument": {
+                    "uri": uri,
+                    "languageId": "css",
+                    "version": 1,
+                    "text": ".a { color: #777; background: #888; }\n.b { border-color: #12345; }\n",
+                }}),
+            )))
+            .unwrap();
+        let Message::Notification(published) = client.receiver.recv().unwrap() else { panic!("expected diagnostics") };
+        assert_eq!(published.method, PublishDiagnostics::METHOD);
+        assert_eq!(published.params["diagnostics"].as_array().unwrap().len(), 2);
+
+        let request = Request::new(RequestId::from(1), DocumentColor::METHOD.to_string(), json!({ "textDocument": { "uri": uri } }));
+        client.sender.send(Message::Request(request)).unwrap();
+        let Message::Response(response) = client.receiver.recv().unwrap() else { panic!("expected a response") };
+        let colors = response.result.unwrap();
+        assert_eq!(colors.as_array().unwrap().len(), 2);
+        assert_eq!(colors[0]["range"]["start"], json!({ "line": 0, "character": 12 }));
+
+        let request = Request::new(
+            RequestId::from(2),
+            ColorPresentationRequest::METHOD.to_string(),
+            json!({
+                "textDocument": { "uri": uri },
+                "color": { "red": 0.0, "green": 0.0, "blue": 1.0, "alpha": 1.0 },
+                "range": colors[0]["range"],
+            }),
+        );
+        client.sender.send(Message::Request(request)).unwrap();
+        let Message::Response(response) = client.receiver.recv().unwrap() else { panic!("expected a response") };
+        let presentations = response.result.unwrap();
+        assert_eq!(presentations[0]["label"], "#0000ff");
+        assert_eq!(presentations[0]["textEdit"]["newText"], "#0000ff");
+
+        let shutdown = Request::new(RequestId::from(3), "shutdown".to_string(), Value::Null);
+        client.sender.send(Message::Request(shutdown)).unwrap();
+        client.receiver.recv().unwrap();
+        client.sender.send(Message::Notification(Notification::new("exit".to_string(), Value::Null))).unwrap();
+        worker.join().unwrap().unwrap();
+    }
+}
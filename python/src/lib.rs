@@ -0,0 +1,684 @@
+// Python bindings for bigcolor
+//
+// Colors are immutable on the Python side: modifications return a new
+// `BigColor` instead of changing the receiver.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use bigcolor::accessibility::{ensure_contrast, ContrastTarget};
+use bigcolor::color_space::{lab_to_lch, oklab_to_oklch, Lab, OKLab};
+use bigcolor::cvd::{check_palette, separate_palette, CvdCheckArgs, CvdType};
+use bigcolor::{
+    harmonize_palette, is_readable, mix, most_readable, readability, BigColor, ColorFormat, MostReadableArgs,
+    WCAG2Level, WCAG2Params, WCAG2Size,
+};
+use numpy::ndarray::{Array2, ArrayView2};
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Format names accepted by `to_string` and reported by `format`
+const FORMATS: [(&str, ColorFormat); 15] = [
+    ("rgb", ColorFormat::RGB),
+    ("prgb", ColorFormat::PRGB),
+    ("hex", ColorFormat::HEX),
+    ("hex3", ColorFormat::HEX3),
+    ("hex6", ColorFormat::HEX6),
+    ("hex8", ColorFormat::HEX8),
+    ("hsl", ColorFormat::HSL),
+    ("hsv", ColorFormat::HSV),
+    ("hsb", ColorFormat::HSB),
+    ("lab", ColorFormat::LAB),
+    ("lch", ColorFormat::LCH),
+    ("oklab", ColorFormat::OKLAB),
+    ("oklch", ColorFormat::OKLCH),
+    ("cmyk", ColorFormat::CMYK),
+    ("name", ColorFormat::NAME),
+];
+
+fn parse_format(name: &str) -> PyResult<ColorFormat> {
+    FORMATS
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, format)| *format)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown color format '{}'", name)))
+}
+
+fn parse_cvd(name: &str) -> PyResult<CvdType> {
+    match name.to_ascii_lowercase().as_str() {
+        "protanopia" => Ok(CvdType::Protanopia),
+        "deuteranopia" => Ok(CvdType::Deuteranopia),
+        "tritanopia" => Ok(CvdType::Tritanopia),
+        "achromatopsia" => Ok(CvdType::Achromatopsia),
+        _ => Err(PyValueError::new_err(format!("unknown color vision deficiency '{}'", name))),
+    }
+}
+
+fn wcag2_params(level: &str, size: &str) -> PyResult<WCAG2Params> {
+    let level = match level.to_ascii_uppercase().as_str() {
+        "AA" => WCAG2Level::AA,
+        "AAA" => WCAG2Level::AAA,
+        _ => return Err(PyValueError::new_err(format!("unknown WCAG level '{}'", level))),
+    };
+    let size = match size.to_ascii_lowercase().as_str() {
+        "small" => WCAG2Size::Small,
+        "large" => WCAG2Size::Large,
+        _ => return Err(PyValueError::new_err(format!("unknown text size '{}'", size))),
+    };
+    Ok(WCAG2Params { level, size })
+}
+
+/// A parsed color. Accepts every string the Rust crate parses, or another
+/// `BigColor`.
+#[pyclass(name = "BigColor", module = "bigcolor", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct PyBigColor {
+    inner: BigColor,
+}
+
+impl From<BigColor> for PyBigColor {
+    fn from(inner: BigColor) -> Self {
+        PyBigColor { inner }
+    }
+}
+
+fn wrap(colors: Vec<BigColor>) -> Vec<PyBigColor> {
+    colors.into_iter().map(PyBigColor::from).collect()
+}
+
+fn unwrap(colors: &[PyRef<'_, PyBigColor>]) -> Vec<BigColor> {
+    colors.iter().map(|color| color.inner.clone()).collect()
+}
+
+impl PyBigColor {
+    /// Applies an in-place modification to a copy of the color
+    fn modified(&self, modify: impl FnOnce(&mut BigColor)) -> PyBigColor {
+        let mut color = self.inner.clone();
+        modify(&mut color);
+        color.into()
+    }
+}
+
+#[pymethods]
+impl PyBigColor {
+    #[new]
+    fn new(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(color) = value.cast::<PyBigColor>() {
+            return Ok(color.get().clone());
+        }
+        let input: String = value.extract()?;
+        let color = BigColor::new(&input);
+        if !color.is_valid() {
+            return Err(PyValueError::new_err(format!("invalid color '{}'", input)));
+        }
+        Ok(color.into())
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (r, g, b, alpha = 1.0))]
+    fn from_rgb(r: u8, g: u8, b: u8, alpha: f32) -> Self {
+        BigColor::from_rgb(r, g, b, alpha).into()
+    }
+
+    /// Hue in degrees, saturation and lightness from 0 to 1
+    #[staticmethod]
+    #[pyo3(signature = (h, s, l, alpha = 1.0))]
+    fn from_hsl(h: f32, s: f32, l: f32, alpha: f32) -> Self {
+        BigColor::from_hsl(h, s, l, alpha).into()
+    }
+
+    /// Hue in degrees, saturation and value from 0 to 1
+    #[staticmethod]
+    #[pyo3(signature = (h, s, v, alpha = 1.0))]
+    fn from_hsv(h: f32, s: f32, v: f32, alpha: f32) -> Self {
+        BigColor::from_hsv(h, s, v, alpha).into()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (l, c, h, alpha = 1.0))]
+    fn from_lch(l: f32, c: f32, h: f32, alpha: f32) -> Self {
+        BigColor::from_lch(l, c, h, alpha).into()
+    }
+
+    /// Lightness from 0 to 1, chroma and hue in degrees. Values outside
+    /// sRGB are kept.
+    #[staticmethod]
+    #[pyo3(signature = (l, c, h, alpha = 1.0))]
+    fn from_oklch(l: f32, c: f32, h: f32, alpha: f32) -> Self {
+        BigColor::from_oklch(l, c, h, alpha).into()
+    }
+
+    #[getter]
+    fn alpha(&self) -> f32 {
+        self.inner.get_alpha()
+    }
+
+    /// Format the color was written in, such as `"hex"` or `"oklch"`
+    #[getter]
+    fn format(&self) -> &'static str {
+        let format = self.inner.get_format();
+        FORMATS.iter().find(|(_, f)| *f == format).map_or("invalid", |(name, _)| name)
+    }
+
+    #[getter]
+    fn original_input(&self) -> &str {
+        self.inner.get_original_input()
+    }
+
+    #[getter]
+    fn name(&self) -> Option<&'static str> {
+        self.inner.to_name()
+    }
+
+    /// WCAG 2 relative luminance from 0 to 1
+    #[getter]
+    fn luminance(&self) -> f32 {
+        self.inner.get_luminance()
+    }
+
+    #[getter]
+    fn brightness(&self) -> f32 {
+        self.inner.get_brightness()
+    }
+
+    #[getter]
+    fn is_dark(&self) -> bool {
+        self.inner.is_dark()
+    }
+
+    #[getter]
+    fn is_light(&self) -> bool {
+        self.inner.is_light()
+    }
+
+    /// Writes the color in a named format, or in the format it was parsed from
+    #[pyo3(signature = (format = None))]
+    fn to_string(&self, format: Option<&str>) -> PyResult<String> {
+        Ok(self.inner.to_string(format.map(parse_format).transpose()?))
+    }
+
+    #[pyo3(signature = (short = false))]
+    fn to_hex(&self, short: bool) -> String {
+        self.inner.to_hex_string(short)
+    }
+
+    #[pyo3(signature = (short = false))]
+    fn to_hex8(&self, short: bool) -> String {
+        self.inner.to_hex8_string(short)
+    }
+
+    /// `(r, g, b, alpha)` with 8-bit channels
+    fn to_rgb(&self) -> (u8, u8, u8, f32) {
+        let rgb = self.inner.to_rgb();
+        (rgb.r, rgb.g, rgb.b, rgb.a)
+    }
+
+    /// `(h, s, l, alpha)` with hue in degrees
+    fn to_hsl(&self) -> (f32, f32, f32, f32) {
+        let hsl = self.inner.to_hsl();
+        (hsl.h, hsl.s, hsl.l, hsl.a)
+    }
+
+    /// `(h, s, v, alpha)` with hue in degrees
+    fn to_hsv(&self) -> (f32, f32, f32, f32) {
+        let hsv = self.inner.to_hsv();
+        (hsv.h, hsv.s, hsv.v, hsv.a)
+    }
+
+    fn to_lab(&self) -> (f32, f32, f32, f32) {
+        let lab = self.inner.to_lab();
+        (lab.l, lab.a, lab.b, lab.alpha)
+    }
+
+    fn to_lch(&self) -> (f32, f32, f32, f32) {
+        let lch = self.inner.to_lch();
+        (lch.l, lch.c, lch.h, lch.alpha)
+    }
+
+    fn to_oklab(&self) -> (f32, f32, f32, f32) {
+        let oklab = self.inner.to_oklab();
+        (oklab.l, oklab.a, oklab.b, oklab.alpha)
+    }
+
+    fn to_oklch(&self) -> (f32, f32, f32, f32) {
+        let oklch = self.inner.to_oklch();
+        (oklch.l, oklch.c, oklch.h, oklch.alpha)
+    }
+
+    fn with_alpha(&self, alpha: f32) -> Self {
+        self.modified(|c| {
+            c.set_alpha(alpha);
+        })
+    }
+
+    #[pyo3(signature = (amount = None))]
+    fn lighten(&self, amount: Option<f32>) -> Self {
+        self.modified(|c| {
+            c.lighten(amount);
+        })
+    }
+
+    #[pyo3(signature = (amount = None))]
+    fn brighten(&self, amount: Option<f32>) -> Self {
+        self.modified(|c| {
+            c.brighten(amount);
+        })
+    }
+
+    #[pyo3(signature = (amount = None))]
+    fn darken(&self, amount: Option<f32>) -> Self {
+        self.modified(|c| {
+            c.darken(amount);
+        })
+    }
+
+    #[pyo3(signature = (amount = None))]
+    fn saturate(&self, amount: Option<f32>) -> Self {
+        self.modified(|c| {
+            c.saturate(amount);
+        })
+    }
+
+    #[pyo3(signature = (amount = None))]
+    fn desaturate(&self, amount: Option<f32>) -> Self {
+        self.modified(|c| {
+            c.desaturate(amount);
+        })
+    }
+
+    fn greyscale(&self) -> Self {
+        self.modified(|c| {
+            c.greyscale();
+        })
+    }
+
+    fn spin(&self, degrees: f32) -> Self {
+        self.modified(|c| {
+            c.spin(degrees);
+        })
+    }
+
+    fn harmonize(&self, source: &PyBigColor, max_degrees: f32) -> Self {
+        self.inner.harmonize(&source.inner, max_degrees).into()
+    }
+
+    #[pyo3(signature = (results = None, slices = None))]
+    fn analogous(&self, results: Option<usize>, slices: Option<usize>) -> Vec<Self> {
+        wrap(self.inner.analogous(results, slices))
+    }
+
+    #[pyo3(signature = (results = None))]
+    fn monochromatic(&self, results: Option<usize>) -> Vec<Self> {
+        wrap(self.inner.monochromatic(results))
+    }
+
+    fn complement(&self) -> Self {
+        self.inner.complement().into()
+    }
+
+    fn split_complement(&self) -> Vec<Self> {
+        wrap(self.inner.split_complement())
+    }
+
+    fn triad(&self) -> Vec<Self> {
+        wrap(self.inner.triad())
+    }
+
+    fn tetrad(&self) -> Vec<Self> {
+        wrap(self.inner.tetrad())
+    }
+
+    fn polyad(&self, number: usize) -> Vec<Self> {
+        wrap(self.inner.polyad(number))
+    }
+
+    /// WCAG 2 contrast ratio against another color, from 1 to 21
+    fn contrast_ratio(&self, other: &PyBigColor) -> f32 {
+        self.inner.get_contrast_ratio(&other.inner)
+    }
+
+    /// APCA lightness contrast (Lc) of this color as text on the background
+    fn apca_contrast(&self, background: &PyBigColor) -> f32 {
+        self.inner.get_apca_contrast(&background.inner)
+    }
+
+    /// Perceptual distance (ΔEOK)
+    fn delta_e(&self, other: &PyBigColor) -> f32 {
+        self.inner.delta_e(&other.inner)
+    }
+
+    fn contrast_color(&self, intensity: f32) -> Self {
+        self.inner.get_contrast_color(intensity).into()
+    }
+
+    #[pyo3(signature = (cvd, severity = None))]
+    fn simulate_cvd(&self, cvd: &str, severity: Option<f32>) -> PyResult<Self> {
+        Ok(self.inner.simulate_cvd(parse_cvd(cvd)?, severity).into())
+    }
+
+    #[pyo3(signature = (cvd, strength = None))]
+    fn daltonize(&self, cvd: &str, strength: Option<f32>) -> PyResult<Self> {
+        Ok(self.inner.daltonize(parse_cvd(cvd)?, strength).into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BigColor('{}')", self.inner)
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string(None)
+    }
+
+    fn __hash__(&self) -> u64 {
+        let oklch = self.inner.to_oklch();
+        let mut hasher = DefaultHasher::new();
+        [oklch.l, oklch.c, oklch.h, oklch.alpha].map(f32::to_bits).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Mixes two colors; `amount` is the percentage of the second, 50 by default
+#[pyfunction(name = "mix")]
+#[pyo3(signature = (color1, color2, amount = None))]
+fn py_mix(color1: &PyBigColor, color2: &PyBigColor, amount: Option<f32>) -> PyBigColor {
+    mix(&color1.inner, &color2.inner, amount).into()
+}
+
+/// WCAG 2 contrast ratio between two colors
+#[pyfunction(name = "readability")]
+fn py_readability(color1: &PyBigColor, color2: &PyBigColor) -> f32 {
+    readability(&color1.inner, &color2.inner)
+}
+
+#[pyfunction(name = "is_readable")]
+#[pyo3(signature = (color1, color2, level = "AA", size = "small"))]
+fn py_is_readable(color1: &PyBigColor, color2: &PyBigColor, level: &str, size: &str) -> PyResult<bool> {
+    Ok(is_readable(&color1.inner, &color2.inner, Some(wcag2_params(level, size)?)))
+}
+
+/// The color of `colors` with the most contrast on `base`, falling back to
+/// black or white when requested and none is readable
+#[pyfunction(name = "most_readable")]
+#[pyo3(signature = (base, colors, include_fallback_colors = false, level = "AA", size = "small"))]
+fn py_most_readable(
+    base: &PyBigColor,
+    colors: Vec<PyRef<'_, PyBigColor>>,
+    include_fallback_colors: bool,
+    level: &str,
+    size: &str,
+) -> PyResult<PyBigColor> {
+    let args = MostReadableArgs {
+        include_fallback_colors,
+        wcag2: wcag2_params(level, size)?,
+    };
+    Ok(most_readable(&base.inner, &unwrap(&colors), Some(args)).into())
+}
+
+/// Adjusts the lightness of `color` until it reaches a WCAG 2 `ratio` or an
+/// APCA `apca` Lc on the background; WCAG AA for small text by default
+#[pyfunction(name = "ensure_contrast")]
+#[pyo3(signature = (color, background, ratio = None, apca = None))]
+fn py_ensure_contrast(color: &PyBigColor, background: &PyBigColor, ratio: Option<f32>, apca: Option<f32>) -> PyBigColor {
+    let target = match (ratio, apca) {
+        (Some(ratio), _) => ContrastTarget::Ratio(ratio),
+        (None, Some(lc)) => ContrastTarget::Apca(lc),
+        (None, None) => ContrastTarget::default(),
+    };
+    ensure_contrast(&color.inner, &background.inner, target).into()
+}
+
+#[pyfunction(name = "harmonize_palette")]
+fn py_harmonize_palette(colors: Vec<PyRef<'_, PyBigColor>>, source: &PyBigColor, max_degrees: f32) -> Vec<PyBigColor> {
+    wrap(harmonize_palette(&unwrap(&colors), &source.inner, max_degrees))
+}
+
+/// Pairs of palette entries that become indistinguishable under a color
+/// vision deficiency, as dicts with `first`, `second`, `min_distance` and
+/// `conflicts`. An empty list means the palette is colorblind-safe.
+#[pyfunction(name = "check_palette")]
+#[pyo3(signature = (colors, threshold = 0.1, severity = 1.0))]
+fn py_check_palette<'py>(
+    py: Python<'py>,
+    colors: Vec<PyRef<'_, PyBigColor>>,
+    threshold: f32,
+    severity: f32,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let report = check_palette(&unwrap(&colors), Some(CvdCheckArgs { threshold, severity }));
+    report
+        .conflicts()
+        .map(|pair| {
+            let dict = PyDict::new(py);
+            dict.set_item("first", pair.first)?;
+            dict.set_item("second", pair.second)?;
+            dict.set_item("min_distance", pair.min_distance())?;
+            let names: Vec<String> = pair.conflicts.iter().map(|cvd| format!("{:?}", cvd).to_lowercase()).collect();
+            dict.set_item("conflicts", names)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// Nudges palette entries apart until every pair is colorblind-safe
+#[pyfunction(name = "separate_palette")]
+#[pyo3(signature = (colors, threshold = 0.1, severity = 1.0))]
+fn py_separate_palette(colors: Vec<PyRef<'_, PyBigColor>>, threshold: f32, severity: f32) -> Vec<PyBigColor> {
+    wrap(separate_palette(&unwrap(&colors), Some(CvdCheckArgs { threshold, severity })))
+}
+
+/// Color spaces of the array functions and the channels of their rows
+const SPACES: [&str; 7] = ["rgb", "hsl", "hsv", "lab", "lch", "oklab", "oklch"];
+
+fn check_space(space: &str) -> PyResult<&str> {
+    SPACES
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(space))
+        .copied()
+        .ok_or_else(|| PyValueError::new_err(format!("unknown color space '{}', expected one of {:?}", space, SPACES)))
+}
+
+/// Builds a color from one row of channels in a space. RGB channels are 0 to
+/// 255, saturation, value and HSL lightness 0 to 1, hue in degrees.
+fn color_from_row(space: &str, row: [f32; 4]) -> BigColor {
+    let [x, y, z, alpha] = row;
+    match space {
+        "rgb" => {
+            let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+            BigColor::from_rgb(channel(x), channel(y), channel(z), alpha)
+        }
+        "hsl" => BigColor::from_hsl(x, y, z, alpha),
+        "hsv" => BigColor::from_hsv(x, y, z, alpha),
+        "lab" => {
+            let lch = lab_to_lch(Lab { l: x, a: y, b: z, alpha });
+            BigColor::from_lch(lch.l, lch.c, lch.h, alpha)
+        }
+        "lch" => BigColor::from_lch(x, y, z, alpha),
+        "oklab" => {
+            let oklch = oklab_to_oklch(OKLab { l: x, a: y, b: z, alpha });
+            BigColor::from_oklch(oklch.l, oklch.c, oklch.h, alpha)
+        }
+        _ => BigColor::from_oklch(x, y, z, alpha),
+    }
+}
+
+fn color_to_row(space: &str, color: &BigColor) -> [f32; 4] {
+    match space {
+        "rgb" => {
+            let rgb = color.to_rgb();
+            [rgb.r as f32, rgb.g as f32, rgb.b as f32, rgb.a]
+        }
+        "hsl" => {
+            let hsl = color.to_hsl();
+            [hsl.h, hsl.s, hsl.l, hsl.a]
+        }
+        "hsv" => {
+            let hsv = color.to_hsv();
+            [hsv.h, hsv.s, hsv.v, hsv.a]
+        }
+        "lab" => {
+            let lab = color.to_lab();
+            [lab.l, lab.a, lab.b, lab.alpha]
+        }
+        "lch" => {
+            let lch = color.to_lch();
+            [lch.l, lch.c, lch.h, lch.alpha]
+        }
+        "oklab" => {
+            let oklab = color.to_oklab();
+            [oklab.l, oklab.a, oklab.b, oklab.alpha]
+        }
+        _ => {
+            let oklch = color.to_oklch();
+            [oklch.l, oklch.c, oklch.h, oklch.alpha]
+        }
+    }
+}
+
+/// Converts rows of 3 or 4 channels between spaces. A missing alpha is 1.
+fn convert_rows(values: ArrayView2<'_, f32>, source: &str, target: &str) -> PyResult<Array2<f32>> {
+    let columns = values.ncols();
+    if columns != 3 && columns != 4 {
+        return Err(PyValueError::new_err(format!("expected 3 or 4 columns, got {}", columns)));
+    }
+    let mut out = Array2::zeros((values.nrows(), columns));
+    for (row, mut converted) in values.rows().into_iter().zip(out.rows_mut()) {
+        let alpha = if columns == 4 { row[3] } else { 1.0 };
+        let color = color_from_row(source, [row[0], row[1], row[2], alpha]);
+        for (slot, value) in converted.iter_mut().zip(color_to_row(target, &color)) {
+            *slot = value;
+        }
+    }
+    Ok(out)
+}
+
+/// Converts an `(n, 3)` or `(n, 4)` array of colors from one space to
+/// another with the same math as `BigColor`. Integer and float64 arrays, or
+/// nested lists, are cast to float32 first.
+#[pyfunction]
+fn convert_array<'py>(
+    py: Python<'py>,
+    values: &Bound<'py, PyAny>,
+    source: &str,
+    target: &str,
+) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    let (source, target) = (check_space(source)?, check_space(target)?);
+    let values = py.import("numpy")?.call_method1("asarray", (values, "float32"))?;
+    let values = values.extract::<PyReadonlyArray2<'py, f32>>()?;
+    Ok(convert_rows(values.as_array(), source, target)?.into_pyarray(py))
+}
+
+/// Parses color strings into an `(n, 4)` float array in the target space
+#[pyfunction]
+#[pyo3(signature = (colors, target = "oklch"))]
+fn parse_array<'py>(py: Python<'py>, colors: Vec<String>, target: &str) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    let target = check_space(target)?;
+    let mut out = Array2::zeros((colors.len(), 4));
+    for (i, input) in colors.iter().enumerate() {
+        let color = BigColor::new(input);
+        if !color.is_valid() {
+            return Err(PyValueError::new_err(format!("invalid color '{}' at index {}", input, i)));
+        }
+        for (slot, value) in out.row_mut(i).iter_mut().zip(color_to_row(target, &color)) {
+            *slot = value;
+        }
+    }
+    Ok(out.into_pyarray(py))
+}
+
+#[pymodule]
+#[pyo3(name = "bigcolor")]
+fn bigcolor_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBigColor>()?;
+    m.add_function(wrap_pyfunction!(py_mix, m)?)?;
+    m.add_function(wrap_pyfunction!(py_readability, m)?)?;
+    m.add_function(wrap_pyfunction!(py_is_readable, m)?)?;
+    m.add_function(wrap_pyfunction!(py_most_readable, m)?)?;
+    m.add_function(wrap_pyfunction!(py_ensure_contrast, m)?)?;
+    m.add_function(wrap_pyfunction!(py_harmonize_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(py_check_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(py_separate_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_array, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_array, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::array;
+
+    /// Runs Python code against a freshly built `bigcolor` module
+    fn run_python(code: &std::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "bigcolor")?;
+            bigcolor_py(&module)?;
+            py.import("sys")?.getattr("modules")?.set_item("bigcolor", &module)?;
+            py.run(code, None, None)
+        })
+        .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    #[test]
+    fn test_python_api() {
+        run_python(
+            cr##"
+from bigcolor import BigColor, mix, readability, most_readable, is_readable, check_palette
+
+blue = BigColor("#1a6ef5")
+assert repr(blue) == "BigColor('#1a6ef5')", repr(blue)
+assert blue.format == "hex" and blue.to_rgb() == (26, 110, 245, 1.0)
+assert blue.lighten(10) != blue and blue == BigColor("rgb(26, 110, 245)")
+assert len({blue, BigColor("#1a6ef5")}) == 1
+assert BigColor("red").name == "red" and BigColor("red").to_string("hsl") == "hsl(0, 100%, 50%)"
+assert mix(BigColor("#000"), BigColor("#fff")).to_hex() == "#7f7f7f"
+assert abs(readability(BigColor("#767676"), BigColor("white")) - 4.54) < 0.01
+assert is_readable(BigColor("#767676"), BigColor("white")) and not is_readable(BigColor("#767676"), BigColor("white"), "AAA")
+assert most_readable(BigColor("#ff0088"), [BigColor("#2e0c3a"), BigColor("#ffffff")]).to_hex() == "#2e0c3a"
+assert check_palette([BigColor("#ff0000"), BigColor("#00ff00")]) == []
+assert "protanopia" in check_palette([BigColor("#ff0000"), BigColor("#fe0000")])[0]["conflicts"]
+
+try:
+    BigColor("not a color")
+    raise AssertionError("expected ValueError")
+except ValueError as error:
+    assert "not a color" in str(error)
+"##,
+        );
+    }
+
+    #[test]
+    fn test_convert_array_casts_numpy_dtypes() {
+        // NumPy is only needed by the array functions, so skip without it
+        Python::initialize();
+        if Python::attach(|py| py.import("numpy").is_err()) {
+            eprintln!("numpy is not installed; skipping");
+            return;
+        }
+        run_python(
+            cr##"
+import numpy as np
+from bigcolor import BigColor, convert_array
+
+expected = BigColor("#ff0000").to_oklch()[:3]
+for values in (np.array([[255, 0, 0]]), np.array([[255.0, 0.0, 0.0]], dtype=np.float64), [[255, 0, 0]]):
+    oklch = convert_array(values, "rgb", "oklch")
+    assert oklch.dtype == np.float32 and oklch.shape == (1, 3)
+    assert np.allclose(oklch[0], expected, atol=1e-4), oklch
+"##,
+        );
+    }
+
+    #[test]
+    fn test_array_conversion_matches_big_color() {
+        let rows = array![[26.0f32, 110.0, 245.0], [255.0, 0.0, 0.0]];
+        let oklch = convert_rows(rows.view(), "rgb", "oklch").unwrap();
+        let expected = BigColor::new("#1a6ef5").to_oklch();
+        assert_eq!(oklch.row(0).to_vec(), vec![expected.l, expected.c, expected.h]);
+
+        let back = convert_rows(oklch.view(), "oklch", "rgb").unwrap();
+        assert_eq!(back, rows);
+        assert!(convert_rows(array![[1.0f32, 2.0]].view(), "rgb", "hsl").is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::BigColor;
+use crate::color_space::*;
+use crate::{is_readable, readability, BigColor, WCAG2Params};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
 
 /// Determines whether a color is considered "light" (and should have dark text on it)
 /// or "dark" (and should have light text on it)
@@ -55,13 +58,7 @@ pub fn get_contrast_color(color: &BigColor, intensity: f32) -> BigColor {
 /// - 7:1 is the enhanced minimum for normal text (AAA)
 /// - 4.5:1 is the enhanced minimum for large text (AAA)
 pub fn get_contrast_ratio(color1: &BigColor, color2: &BigColor) -> f32 {
-    // Calculate relative luminance for both colors
-    let l1 = color1.get_luminance();
-    let l2 = color2.get_luminance();
-    
-    // Calculate contrast ratio
-    let (lighter, darker) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
-    (lighter + 0.05) / (darker + 0.05)
+    color1.color().contrast_ratio(color2.color())
 }
 
 /// Calculate the relative luminance of a color
@@ -80,4 +77,98 @@ pub fn to_linear(component: f32) -> f32 {
     } else {
         ((component + 0.055) / 1.055).powf(2.4)
     }
-} 
\ No newline at end of file
+} 
+
+/// Returns the APCA lightness contrast (Lc) of text on a background
+///
+/// Implements APCA-W3 0.0.98G. The result is roughly in the range -108 to 106:
+/// positive for dark text on a light background, negative for light text on a
+/// dark background. As a rule of thumb |Lc| 75 suits body text, 60 suits larger
+/// text and 45 suits headlines.
+pub fn get_apca_contrast(text: &BigColor, background: &BigColor) -> f32 {
+    text.color().apca_contrast(background.color())
+}
+
+/// A minimum contrast a foreground color must reach against its background
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContrastTarget {
+    /// A WCAG 2 level and text size
+    Wcag2(WCAG2Params),
+    /// A minimum WCAG 2 contrast ratio
+    Ratio(f32),
+    /// A minimum absolute APCA lightness contrast (Lc)
+    Apca(f32),
+}
+
+impl Default for ContrastTarget {
+    fn default() -> Self {
+        ContrastTarget::Wcag2(WCAG2Params::default())
+    }
+}
+
+impl ContrastTarget {
+    /// Measures the contrast of a pair with this target's metric:
+    /// the WCAG 2 ratio, or the absolute APCA Lc
+    pub fn measure(&self, foreground: &BigColor, background: &BigColor) -> f32 {
+        match self {
+            ContrastTarget::Wcag2(_) | ContrastTarget::Ratio(_) => readability(foreground, background),
+            ContrastTarget::Apca(_) => get_apca_contrast(foreground, background).abs(),
+        }
+    }
+
+    /// Returns true if the foreground meets this target on the background
+    pub fn is_met(&self, foreground: &BigColor, background: &BigColor) -> bool {
+        match self {
+            ContrastTarget::Wcag2(params) => is_readable(foreground, background, Some(*params)),
+            ContrastTarget::Ratio(ratio) => readability(foreground, background) >= *ratio,
+            ContrastTarget::Apca(lc) => get_apca_contrast(foreground, background).abs() >= *lc,
+        }
+    }
+}
+
+/// Adjusts the lightness of a color until it meets a contrast target against a background
+///
+/// Hue is kept and chroma is reduced only as needed to stay in sRGB. The color moves
+/// toward whichever of black or white contrasts more with the background; if even that
+/// extreme falls short, the extreme is returned.
+pub fn ensure_contrast(color: &BigColor, background: &BigColor, target: ContrastTarget) -> BigColor {
+    if target.is_met(color, background) {
+        return color.clone();
+    }
+
+    let source = color.to_oklch();
+    let with_lightness = |l: f32| {
+        let mapped = gamut_map_oklch(OKLCH { l, ..source });
+        BigColor::from_oklch(mapped.l, mapped.c, mapped.h, mapped.alpha)
+    };
+
+    let black = BigColor::new("#000");
+    let white = BigColor::new("#fff");
+    let extreme_l = if target.measure(&black, background) >= target.measure(&white, background) {
+        0.0
+    } else {
+        1.0
+    };
+
+    let extreme = with_lightness(extreme_l);
+    if !target.is_met(&extreme, background) {
+        return extreme;
+    }
+
+    let mut near = source.l;
+    let mut far = extreme_l;
+    let mut best = extreme;
+    for _ in 0..20 {
+        let mid = (near + far) / 2.0;
+        let candidate = with_lightness(mid);
+        if target.is_met(&candidate, background) {
+            far = mid;
+            best = candidate;
+        } else {
+            near = mid;
+        }
+    }
+
+    best
+}
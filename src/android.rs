@@ -0,0 +1,241 @@
+// Android color resources (res/values/colors.xml)
+
+use std::fmt;
+
+use crate::code::{parse_hex_digits, ByteOrder};
+use crate::swatches::{xml_attribute, xml_escape, xml_unescape};
+use crate::theme::Theme;
+use crate::BigColor;
+
+/// Errors from reading a colors.xml file
+#[derive(Debug, Clone, PartialEq)]
+pub enum AndroidError {
+    /// A color element whose value is not a color or a reference
+    InvalidColor { name: String, value: String },
+    /// An `@color/...` reference to a color the file does not define
+    UnknownReference { name: String, reference: String },
+    /// References that point back at themselves, in the order followed
+    ReferenceCycle(Vec<String>),
+}
+
+impl fmt::Display for AndroidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AndroidError::InvalidColor { name, value } => write!(f, "color {} has invalid value {}", name, value),
+            AndroidError::UnknownReference { name, reference } => {
+                write!(f, "color {} references unknown color {}", name, reference)
+            }
+            AndroidError::ReferenceCycle(chain) => write!(f, "reference cycle: {}", chain.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for AndroidError {}
+
+/// Parses an Android `#RGB`, `#ARGB`, `#RRGGBB` or `#AARRGGBB` value
+pub fn parse_android_hex(value: &str) -> Option<BigColor> {
+    parse_hex_digits(value.trim().strip_prefix('#')?, ByteOrder::Argb)
+}
+
+/// Writes a color the way Android Studio does, `#RRGGBB` or `#AARRGGBB`
+pub fn to_android_hex(color: &BigColor) -> String {
+    let rgb = color.to_rgb();
+    if rgb.a < 1.0 {
+        format!("#{:02X}{:02X}{:02X}{:02X}", (rgb.a * 255.0).round() as u8, rgb.r, rgb.g, rgb.b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}", rgb.r, rgb.g, rgb.b)
+    }
+}
+
+/// Turns a token name into a valid resource name, `brand-500` to `brand_500`
+pub fn resource_name(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let out = out.trim_end_matches('_');
+    if out.starts_with(|c: char| c.is_ascii_digit()) || out.is_empty() {
+        format!("color_{}", out)
+    } else {
+        out.to_string()
+    }
+}
+
+/// Removes `<!-- ... -->` comments so commented-out colors are ignored
+fn strip_comments(xml: &str) -> String {
+    let mut out = String::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("-->") {
+            Some(end) => &rest[start + end + 3..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Follows `@color/` references until a literal value
+fn resolve(raw: &[(String, String)], name: &str, chain: &mut Vec<String>) -> Result<BigColor, AndroidError> {
+    if chain.iter().any(|seen| seen == name) {
+        chain.push(name.to_string());
+        return Err(AndroidError::ReferenceCycle(chain.clone()));
+    }
+    chain.push(name.to_string());
+
+    let value = &raw.iter().find(|(n, _)| n == name).expect("caller checks the name").1;
+    if let Some(reference) = value.strip_prefix("@android:color/") {
+        return match reference {
+            "white" => Ok(BigColor::new("#ffffff")),
+            "black" => Ok(BigColor::new("#000000")),
+            "transparent" => Ok(BigColor::new("#00000000")),
+            _ => Err(AndroidError::UnknownReference { name: name.to_string(), reference: value.clone() }),
+        };
+    }
+    if let Some(reference) = value.strip_prefix("@color/") {
+        if !raw.iter().any(|(n, _)| n == reference) {
+            return Err(AndroidError::UnknownReference { name: name.to_string(), reference: value.clone() });
+        }
+        return resolve(raw, reference, chain);
+    }
+    parse_android_hex(value).ok_or_else(|| AndroidError::InvalidColor { name: name.to_string(), value: value.clone() })
+}
+
+/// Reads the `<color>` resources of a colors.xml file in document order,
+/// resolving `@color/` and `@android:color/` references
+pub fn read_colors_xml(xml: &str) -> Result<Vec<(String, BigColor)>, AndroidError> {
+    let xml = strip_comments(xml);
+    let mut raw = Vec::new();
+    for (position, _) in xml.match_indices("<color") {
+        let after = &xml[position + "<color".len()..];
+        if !after.starts_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+        let Some(tag_end) = after.find('>') else { break };
+        // A self-closing `<color name="a"/>` has an empty value
+        let (attributes, self_closing) = match after[..tag_end].strip_suffix('/') {
+            Some(attributes) => (attributes, true),
+            None => (&after[..tag_end], false),
+        };
+        let Some(name) = xml_attribute(attributes, "name") else { continue };
+        let body = &after[tag_end + 1..];
+        let value = if self_closing { "" } else { body.find("</color>").map_or("", |end| &body[..end]) };
+        raw.push((name, xml_unescape(value.trim())));
+    }
+
+    raw.iter()
+        .map(|(name, _)| Ok((name.clone(), resolve(&raw, name, &mut Vec::new())?)))
+        .collect()
+}
+
+/// Writes a colors.xml file, converting names with [`resource_name`]
+pub fn write_colors_xml(tokens: &[(String, BigColor)]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n");
+    for (name, color) in tokens {
+        out.push_str(&format!(
+            "    <color name=\"{}\">{}</color>\n",
+            xml_escape(&resource_name(name)),
+            to_android_hex(color)
+        ));
+    }
+    out.push_str("</resources>\n");
+    out
+}
+
+/// Day colors from `values/colors.xml` with night overrides from
+/// `values-night/colors.xml`
+#[derive(Debug, Clone, Default)]
+pub struct AndroidColors {
+    pub day: Vec<(String, BigColor)>,
+    pub night: Vec<(String, BigColor)>,
+}
+
+impl AndroidColors {
+    /// Reads the day file and, when present, the night file
+    pub fn read(day_xml: &str, night_xml: Option<&str>) -> Result<Self, AndroidError> {
+        Ok(AndroidColors {
+            day: read_colors_xml(day_xml)?,
+            night: night_xml.map(read_colors_xml).transpose()?.unwrap_or_default(),
+        })
+    }
+
+    /// Uses a theme's light and dark tokens as the day and night colors
+    pub fn from_theme(theme: &Theme) -> Self {
+        let tokens = |colors: &crate::theme::ThemeColors| {
+            colors
+                .tokens()
+                .into_iter()
+                .map(|(name, color)| (resource_name(name), color.clone()))
+                .collect()
+        };
+        AndroidColors { day: tokens(&theme.light), night: tokens(&theme.dark) }
+    }
+
+    /// Returns a color for the day or night configuration. Night falls back
+    /// to the day color like Android's resource lookup.
+    pub fn color(&self, name: &str, night: bool) -> Option<&BigColor> {
+        let find = |colors: &[(String, BigColor)]| colors.iter().position(|(n, _)| n == name);
+        let night_color = find(&self.night).filter(|_| night).map(|i| &self.night[i].1);
+        night_color.or_else(|| find(&self.day).map(|i| &self.day[i].1))
+    }
+
+    /// Returns the files to write under `res/`, leaving out an empty night file
+    pub fn files(&self) -> Vec<(&'static str, String)> {
+        let mut files = vec![("values/colors.xml", write_colors_xml(&self.day))];
+        if !self.night.is_empty() {
+            files.push(("values-night/colors.xml", write_colors_xml(&self.night)));
+        }
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_references_and_short_hex() {
+        let xml = r##"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <!-- <color name="old">#123</color> -->
+    <color name="brand">#1A6EF5</color>
+    <color name="scrim">#8000</color>
+    <color name="accent">@color/brand</color>
+    <color name="surface">@android:color/white</color>
+</resources>"##;
+        let colors = read_colors_xml(xml).unwrap();
+        let names: Vec<&str> = colors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["brand", "scrim", "accent", "surface"]);
+        assert_eq!(colors[1].1.to_rgb().a, 136.0 / 255.0);
+        assert_eq!(colors[2].1.to_hex_string(false), "#1a6ef5");
+
+        let cycle = read_colors_xml(r#"<color name="a">@color/b</color><color name="b">@color/a</color>"#);
+        assert_eq!(cycle.unwrap_err(), AndroidError::ReferenceCycle(vec!["a".into(), "b".into(), "a".into()]));
+
+        let namespaced = read_colors_xml(r#"<color tools:name="x" title='name="y"' name="brand">#fff</color>"#);
+        assert_eq!(namespaced.unwrap()[0].0, "brand");
+
+        let empty = read_colors_xml(r#"<color name="a"/><color name="b">#fff</color>"#);
+        assert_eq!(empty.unwrap_err(), AndroidError::InvalidColor { name: "a".into(), value: String::new() });
+    }
+
+    #[test]
+    fn test_day_night_round_trip() {
+        let colors = AndroidColors {
+            day: vec![("brand-500".to_string(), BigColor::new("#1a6ef5"))],
+            night: vec![("brand-500".to_string(), BigColor::new("rgba(91, 155, 255, 0.5)"))],
+        };
+        let files = colors.files();
+        assert_eq!(files[1].0, "values-night/colors.xml");
+        assert!(files[1].1.contains("<color name=\"brand_500\">#805B9BFF</color>"));
+
+        let read = AndroidColors::read(&files[0].1, Some(&files[1].1)).unwrap();
+        assert_eq!(read.color("brand_500", false).unwrap().to_hex_string(false), "#1a6ef5");
+        assert_eq!(read.color("brand_500", true).unwrap().to_hex_string(false), "#5b9bff");
+    }
+}
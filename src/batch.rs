@@ -0,0 +1,451 @@
+// Slice conversions for images and large datasets
+//
+// Colors are converted in fixed-size chunks laid out as structure of arrays,
+// one plane per channel, so every step is a plain loop over `f32` slices the
+// compiler can vectorize. 8-bit sRGB is decoded and encoded through lookup
+// tables instead of `powf`.
+
+use alloc::vec::Vec;
+use crate::color::Color;
+use crate::color_space::*;
+use crate::matrix_utils::*;
+use core::f32::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+
+/// Colors converted per chunk
+const CHUNK: usize = 64;
+
+/// A color space for [`convert_slice`]. Alpha is the fourth component in
+/// every space and is passed through unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+    /// Gamma-encoded sRGB, 0 to 1
+    Srgb,
+    /// Linear-light sRGB, 0 to 1
+    LinearSrgb,
+    XyzD65,
+    /// CIE Lab with a D50 white point
+    Lab,
+    /// CIE LCH with a D50 white point, hue in degrees
+    Lch,
+    OkLab,
+    /// OKLCH, hue in degrees
+    OkLch,
+}
+
+/// OKLab colors stored as one plane per channel
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OkLabPlanes {
+    pub l: Vec<f32>,
+    pub a: Vec<f32>,
+    pub b: Vec<f32>,
+    pub alpha: Vec<f32>,
+}
+
+impl OkLabPlanes {
+    /// Returns the number of colors
+    pub fn len(&self) -> usize {
+        self.l.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.l.is_empty()
+    }
+}
+
+/// Decodes an 8-bit sRGB channel to linear light through a lookup table
+pub fn srgb8_to_linear(value: u8) -> f32 {
+    SRGB8_TO_LINEAR[value as usize]
+}
+
+/// Encodes linear light as a rounded 8-bit sRGB channel by searching a
+/// table of step thresholds. Values outside 0 to 1 are clipped.
+pub fn linear_to_srgb8(linear: f32) -> u8 {
+    SRGB8_THRESHOLDS.partition_point(|&threshold| threshold <= linear) as u8
+}
+
+/// Converts colors between color spaces, writing the results to `out`
+///
+/// # Panics
+///
+/// Panics if `input` and `out` have different lengths.
+pub fn convert_slice(input: &[[f32; 4]], from: ColorSpace, to: ColorSpace, out: &mut [[f32; 4]]) {
+    assert_eq!(input.len(), out.len(), "convert_slice needs one output color per input color");
+
+    let mut planes = Planes::new();
+    for (colors, results) in input.chunks(CHUNK).zip(out.chunks_mut(CHUNK)) {
+        let n = colors.len();
+        for (i, color) in colors.iter().enumerate() {
+            planes.x[i] = color[0];
+            planes.y[i] = color[1];
+            planes.z[i] = color[2];
+        }
+        if from != to {
+            planes.decode(from, n);
+            planes.encode(to, n);
+        }
+        for (i, result) in results.iter_mut().enumerate() {
+            *result = [planes.x[i], planes.y[i], planes.z[i], colors[i][3]];
+        }
+    }
+}
+
+/// Converts a buffer of RGBA8 pixels to OKLCH colors, writing them to `out`.
+/// A trailing partial pixel is ignored.
+///
+/// # Panics
+///
+/// Panics if `out` does not hold one color per pixel.
+pub fn srgb8_to_oklch_batch(pixels: &[u8], out: &mut [Color]) {
+    assert_eq!(pixels.len() / 4, out.len(), "srgb8_to_oklch_batch needs one output color per pixel");
+
+    let mut planes = Planes::new();
+    for (chunk, results) in pixels.chunks(4 * CHUNK).zip(out.chunks_mut(CHUNK)) {
+        let n = planes.load_rgba8(chunk);
+        planes.encode(ColorSpace::OkLch, n);
+        for (i, result) in results.iter_mut().enumerate() {
+            *result = Color::new(planes.x[i], planes.y[i], planes.z[i], chunk[i * 4 + 3] as f32 / 255.0);
+        }
+    }
+}
+
+/// Converts a buffer of RGBA8 pixels to OKLab planes. A trailing partial
+/// pixel is ignored.
+pub fn rgba8_to_oklab_planes(pixels: &[u8]) -> OkLabPlanes {
+    let count = pixels.len() / 4;
+    let mut result = OkLabPlanes {
+        l: Vec::with_capacity(count),
+        a: Vec::with_capacity(count),
+        b: Vec::with_capacity(count),
+        alpha: Vec::with_capacity(count),
+    };
+
+    let mut planes = Planes::new();
+    for chunk in pixels.chunks(4 * CHUNK) {
+        let n = planes.load_rgba8(chunk);
+        planes.encode(ColorSpace::OkLab, n);
+        result.l.extend_from_slice(&planes.x[..n]);
+        result.a.extend_from_slice(&planes.y[..n]);
+        result.b.extend_from_slice(&planes.z[..n]);
+        result.alpha.extend(chunk.chunks_exact(4).map(|pixel| pixel[3] as f32 / 255.0));
+    }
+    result
+}
+
+/// Writes OKLab planes back to a buffer of RGBA8 pixels, clipping colors
+/// outside sRGB
+///
+/// # Panics
+///
+/// Panics if the planes have different lengths or `pixels` does not hold
+/// exactly one pixel per color.
+pub fn oklab_planes_to_rgba8(planes: &OkLabPlanes, pixels: &mut [u8]) {
+    let count = planes.len();
+    assert!(
+        planes.a.len() == count && planes.b.len() == count && planes.alpha.len() == count,
+        "OKLab planes must have the same length"
+    );
+    assert_eq!(pixels.len(), count * 4, "oklab_planes_to_rgba8 needs one pixel per color");
+
+    let mut chunk_planes = Planes::new();
+    for (start, chunk) in (0..count).step_by(CHUNK).zip(pixels.chunks_mut(4 * CHUNK)) {
+        let n = (count - start).min(CHUNK);
+        chunk_planes.x[..n].copy_from_slice(&planes.l[start..start + n]);
+        chunk_planes.y[..n].copy_from_slice(&planes.a[start..start + n]);
+        chunk_planes.z[..n].copy_from_slice(&planes.b[start..start + n]);
+        chunk_planes.decode(ColorSpace::OkLab, n);
+        chunk_planes.encode(ColorSpace::LinearSrgb, n);
+        for (i, pixel) in chunk.chunks_exact_mut(4).enumerate() {
+            pixel[0] = linear_to_srgb8(chunk_planes.x[i]);
+            pixel[1] = linear_to_srgb8(chunk_planes.y[i]);
+            pixel[2] = linear_to_srgb8(chunk_planes.z[i]);
+            pixel[3] = (clamp_01(planes.alpha[start + i]) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// One chunk of colors as three channel planes
+struct Planes {
+    x: [f32; CHUNK],
+    y: [f32; CHUNK],
+    z: [f32; CHUNK],
+}
+
+impl Planes {
+    fn new() -> Self {
+        Planes { x: [0.0; CHUNK], y: [0.0; CHUNK], z: [0.0; CHUNK] }
+    }
+
+    /// Loads up to a chunk of RGBA8 pixels as linear sRGB and returns the count
+    fn load_rgba8(&mut self, pixels: &[u8]) -> usize {
+        let mut n = 0;
+        for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+            self.x[i] = srgb8_to_linear(pixel[0]);
+            self.y[i] = srgb8_to_linear(pixel[1]);
+            self.z[i] = srgb8_to_linear(pixel[2]);
+            n = i + 1;
+        }
+        self.multiply(LINEAR_SRGB_TO_XYZ_M, n);
+        n
+    }
+
+    /// Converts the first `n` colors from `space` to XYZ D65
+    fn decode(&mut self, space: ColorSpace, n: usize) {
+        match space {
+            ColorSpace::Srgb => {
+                self.map(srgb_to_linear, n);
+                self.multiply(LINEAR_SRGB_TO_XYZ_M, n);
+            }
+            ColorSpace::LinearSrgb => self.multiply(LINEAR_SRGB_TO_XYZ_M, n),
+            ColorSpace::XyzD65 => {}
+            ColorSpace::Lab => self.lab_to_xyz(n),
+            ColorSpace::Lch => {
+                self.polar_to_lab(n);
+                self.lab_to_xyz(n);
+            }
+            ColorSpace::OkLab => self.oklab_to_xyz(n),
+            ColorSpace::OkLch => {
+                self.polar_to_lab(n);
+                self.oklab_to_xyz(n);
+            }
+        }
+    }
+
+    /// Converts the first `n` colors from XYZ D65 to `space`
+    fn encode(&mut self, space: ColorSpace, n: usize) {
+        match space {
+            ColorSpace::Srgb => {
+                self.multiply(XYZ_TO_LINEAR_SRGB_M, n);
+                self.map(linear_to_srgb, n);
+            }
+            ColorSpace::LinearSrgb => self.multiply(XYZ_TO_LINEAR_SRGB_M, n),
+            ColorSpace::XyzD65 => {}
+            ColorSpace::Lab => self.xyz_to_lab(n),
+            ColorSpace::Lch => {
+                self.xyz_to_lab(n);
+                self.lab_to_polar(n);
+            }
+            ColorSpace::OkLab => self.xyz_to_oklab(n),
+            ColorSpace::OkLch => {
+                self.xyz_to_oklab(n);
+                self.lab_to_polar(n);
+            }
+        }
+    }
+
+    fn multiply(&mut self, m: Matrix3x3, n: usize) {
+        let (xs, ys, zs) = (&mut self.x[..n], &mut self.y[..n], &mut self.z[..n]);
+        for ((x, y), z) in xs.iter_mut().zip(ys.iter_mut()).zip(zs.iter_mut()) {
+            [*x, *y, *z] = multiply_v3_m3x3([*x, *y, *z], m);
+        }
+    }
+
+    fn map(&mut self, f: impl Fn(f32) -> f32, n: usize) {
+        for plane in [&mut self.x, &mut self.y, &mut self.z] {
+            for value in &mut plane[..n] {
+                *value = f(*value);
+            }
+        }
+    }
+
+    fn xyz_to_oklab(&mut self, n: usize) {
+        self.multiply(XYZ_TO_LMS_M, n);
+        self.map(|v| v.cbrt(), n);
+        self.multiply(LMS_TO_LAB_M, n);
+    }
+
+    fn oklab_to_xyz(&mut self, n: usize) {
+        self.multiply(LAB_TO_LMS_M, n);
+        self.map(|v| v.powi(3), n);
+        self.multiply(LMS_TO_XYZ_M, n);
+    }
+
+    fn xyz_to_lab(&mut self, n: usize) {
+        self.multiply(D65_TO_D50_M, n);
+        let (xs, ys, zs) = (&mut self.x[..n], &mut self.y[..n], &mut self.z[..n]);
+        let f = |value: f32| if value > EPSILON { value.cbrt() } else { (KAPPA * value + 16.0) / 116.0 };
+        for ((x, y), z) in xs.iter_mut().zip(ys.iter_mut()).zip(zs.iter_mut()) {
+            let (fx, fy, fz) = (f(*x / WHITE_D50[0]), f(*y / WHITE_D50[1]), f(*z / WHITE_D50[2]));
+            [*x, *y, *z] = [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)];
+        }
+    }
+
+    fn lab_to_xyz(&mut self, n: usize) {
+        let (xs, ys, zs) = (&mut self.x[..n], &mut self.y[..n], &mut self.z[..n]);
+        let f_inv = |f: f32| if f > EPSILON3 { f.powi(3) } else { (116.0 * f - 16.0) / KAPPA };
+        for ((x, y), z) in xs.iter_mut().zip(ys.iter_mut()).zip(zs.iter_mut()) {
+            let l = *x;
+            let f1 = (l + 16.0) / 116.0;
+            let f0 = *y / 500.0 + f1;
+            let f2 = f1 - *z / 200.0;
+            let y_rel = if l > 8.0 { f1.powi(3) } else { l / KAPPA };
+            [*x, *y, *z] = [f_inv(f0) * WHITE_D50[0], y_rel * WHITE_D50[1], f_inv(f2) * WHITE_D50[2]];
+        }
+        self.multiply(D50_TO_D65_M, n);
+    }
+
+    /// Lightness, a, b to lightness, chroma, hue in degrees
+    fn lab_to_polar(&mut self, n: usize) {
+        let (ys, zs) = (&mut self.y[..n], &mut self.z[..n]);
+        for (y, z) in ys.iter_mut().zip(zs.iter_mut()) {
+            let (a, b) = (*y, *z);
+            *y = (a * a + b * b).sqrt();
+            // Hue is undefined for achromatic colors and reported as 0
+            *z = if a.abs() < 1e-10 && b.abs() < 1e-10 { 0.0 } else { constrain_angle(b.atan2(a) * 180.0 / PI) };
+        }
+    }
+
+    /// Lightness, chroma, hue in degrees to lightness, a, b
+    fn polar_to_lab(&mut self, n: usize) {
+        let (ys, zs) = (&mut self.y[..n], &mut self.z[..n]);
+        for (y, z) in ys.iter_mut().zip(zs.iter_mut()) {
+            let (c, h) = (*y, *z * PI / 180.0);
+            [*y, *z] = [c * h.cos(), c * h.sin()];
+        }
+    }
+}
+
+// Generated from `srgb_to_linear`; the tests check both tables against it.
+
+/// `srgb_to_linear(i / 255)` for every 8-bit value
+static SRGB8_TO_LINEAR: [f32; 256] = [
+    0.0, 3.03527e-4, 6.07054e-4, 9.10581e-4, 1.214108e-3, 1.517635e-3, 1.821162e-3, 2.1246888e-3,
+    2.428216e-3, 2.7317428e-3, 3.03527e-3, 3.3465358e-3, 3.6765074e-3, 4.024717e-3, 4.391442e-3, 4.7769533e-3,
+    5.1815165e-3, 5.6053917e-3, 6.048833e-3, 6.5120906e-3, 6.99541e-3, 7.499032e-3, 8.023193e-3, 8.568126e-3,
+    9.134059e-3, 9.721218e-3, 1.0329823e-2, 1.0960094e-2, 1.1612245e-2, 1.2286488e-2, 1.29830325e-2, 1.3702083e-2,
+    1.4443844e-2, 1.5208514e-2, 1.5996294e-2, 1.6807375e-2, 1.7641954e-2, 1.850022e-2, 1.9382361e-2, 2.0288562e-2,
+    2.121901e-2, 2.2173885e-2, 2.3153367e-2, 2.4157632e-2, 2.518686e-2, 2.6241222e-2, 2.7320892e-2, 2.842604e-2,
+    2.9556835e-2, 3.0713445e-2, 3.1896032e-2, 3.3104766e-2, 3.4339808e-2, 3.5601314e-2, 3.688945e-2, 3.8204372e-2,
+    3.9546236e-2, 4.09152e-2, 4.231141e-2, 4.373503e-2, 4.5186203e-2, 4.6665087e-2, 4.8171826e-2, 4.9706567e-2,
+    5.1269457e-2, 5.2860647e-2, 5.4480277e-2, 5.612849e-2, 5.780543e-2, 5.9511237e-2, 6.1246052e-2, 6.3010015e-2,
+    6.4803265e-2, 6.662594e-2, 6.847817e-2, 7.0360094e-2, 7.227185e-2, 7.421357e-2, 7.618538e-2, 7.818742e-2,
+    8.021982e-2, 8.228271e-2, 8.437621e-2, 8.650046e-2, 8.865558e-2, 9.084171e-2, 9.3058966e-2, 9.530747e-2,
+    9.758735e-2, 9.9898726e-2, 1.0224173e-1, 1.04616486e-1, 1.07023105e-1, 1.0946171e-1, 1.1193243e-1, 1.14435375e-1,
+    1.16970666e-1, 1.1953843e-1, 1.22138776e-1, 1.2477182e-1, 1.2743768e-1, 1.3013647e-1, 1.3286832e-1, 1.3563333e-1,
+    1.3843161e-1, 1.4126329e-1, 1.4412847e-1, 1.4702727e-1, 1.4995979e-1, 1.5292615e-1, 1.5592647e-1, 1.5896083e-1,
+    1.6202937e-1, 1.651322e-1, 1.682694e-1, 1.7144111e-1, 1.746474e-1, 1.7788842e-1, 1.8116425e-1, 1.8447499e-1,
+    1.8782078e-1, 1.9120169e-1, 1.9461784e-1, 1.9806932e-1, 2.0155625e-1, 2.0507874e-1, 2.0863687e-1, 2.1223076e-1,
+    2.158605e-1, 2.195262e-1, 2.2322796e-1, 2.2696587e-1, 2.3074006e-1, 2.3455058e-1, 2.3839757e-1, 2.4228112e-1,
+    2.4620132e-1, 2.5015828e-1, 2.541521e-1, 2.5818285e-1, 2.6225066e-1, 2.663556e-1, 2.704978e-1, 2.746773e-1,
+    2.7889428e-1, 2.8314874e-1, 2.8744084e-1, 2.9177064e-1, 2.9613826e-1, 3.0054379e-1, 3.049873e-1, 3.0946892e-1,
+    3.1398872e-1, 3.1854677e-1, 3.231432e-1, 3.277781e-1, 3.3245152e-1, 3.3716363e-1, 3.4191442e-1, 3.4670407e-1,
+    3.515326e-1, 3.5640013e-1, 3.613068e-1, 3.662526e-1, 3.712377e-1, 3.7626213e-1, 3.8132602e-1, 3.8642943e-1,
+    3.9157248e-1, 3.9675522e-1, 4.0197778e-1, 4.072402e-1, 4.125426e-1, 4.1788507e-1, 4.2326766e-1, 4.286905e-1,
+    4.3415365e-1, 4.3965718e-1, 4.452012e-1, 4.507858e-1, 4.5641103e-1, 4.62077e-1, 4.677838e-1, 4.7353148e-1,
+    4.7932017e-1, 4.8514995e-1, 4.9102086e-1, 4.9693298e-1, 5.028865e-1, 5.0888133e-1, 5.149177e-1, 5.2099556e-1,
+    5.271151e-1, 5.332764e-1, 5.394795e-1, 5.4572445e-1, 5.5201143e-1, 5.583404e-1, 5.647115e-1, 5.7112485e-1,
+    5.7758045e-1, 5.8407843e-1, 5.9061885e-1, 5.9720176e-1, 6.0382736e-1, 6.1049557e-1, 6.172066e-1, 6.239604e-1,
+    6.3075715e-1, 6.3759685e-1, 6.444797e-1, 6.5140563e-1, 6.5837485e-1, 6.653873e-1, 6.7244315e-1, 6.795425e-1,
+    6.866853e-1, 6.9387174e-1, 7.011019e-1, 7.0837575e-1, 7.156935e-1, 7.230551e-1, 7.3046076e-1, 7.379104e-1,
+    7.454042e-1, 7.529422e-1, 7.605245e-1, 7.6815116e-1, 7.758222e-1, 7.835378e-1, 7.912979e-1, 7.991027e-1,
+    8.0695224e-1, 8.148466e-1, 8.2278574e-1, 8.307699e-1, 8.38799e-1, 8.468732e-1, 8.549926e-1, 8.631572e-1,
+    8.713671e-1, 8.796224e-1, 8.879231e-1, 8.962694e-1, 9.046612e-1, 9.1309863e-1, 9.2158186e-1, 9.301109e-1,
+    9.386857e-1, 9.473065e-1, 9.559733e-1, 9.646863e-1, 9.734453e-1, 9.822506e-1, 9.911021e-1, 1e0,
+];
+
+/// Linear values where the rounded 8-bit sRGB encoding steps from `i` to `i + 1`
+static SRGB8_THRESHOLDS: [f32; 255] = [
+    1.517635e-4, 4.552905e-4, 7.588175e-4, 1.0623444e-3, 1.3658714e-3, 1.6693984e-3, 1.9729254e-3, 2.2764525e-3,
+    2.5799794e-3, 2.8835062e-3, 3.1883009e-3, 3.5092593e-3, 3.848315e-3, 4.205748e-3, 4.581833e-3, 4.9768374e-3,
+    5.391024e-3, 5.8246506e-3, 6.2779696e-3, 6.7512277e-3, 7.2446684e-3, 7.7585303e-3, 8.2930485e-3, 8.848453e-3,
+    9.4249705e-3, 1.0022826e-2, 1.0642237e-2, 1.1283421e-2, 1.19465925e-2, 1.263196e-2, 1.3339732e-2, 1.4070112e-2,
+    1.4823303e-2, 1.5599503e-2, 1.639891e-2, 1.7221715e-2, 1.8068114e-2, 1.8938294e-2, 1.9832443e-2, 2.0750744e-2,
+    2.1693382e-2, 2.2660539e-2, 2.365239e-2, 2.4669115e-2, 2.5710888e-2, 2.6777882e-2, 2.787027e-2, 2.898822e-2,
+    3.0131903e-2, 3.130148e-2, 3.2497123e-2, 3.371899e-2, 3.4967244e-2, 3.6242045e-2, 3.7543554e-2, 3.8871925e-2,
+    4.022732e-2, 4.1609887e-2, 4.3019786e-2, 4.4457164e-2, 4.592217e-2, 4.7414962e-2, 4.8935685e-2, 5.0484486e-2,
+    5.2061506e-2, 5.3666897e-2, 5.5300802e-2, 5.696336e-2, 5.8654718e-2, 6.0375012e-2, 6.2124383e-2, 6.3902974e-2,
+    6.571092e-2, 6.754835e-2, 6.941541e-2, 7.1312234e-2, 7.3238954e-2, 7.519571e-2, 7.718261e-2, 7.919982e-2,
+    8.124744e-2, 8.3325624e-2, 8.543449e-2, 8.7574154e-2, 8.974477e-2, 9.194644e-2, 9.41793e-2, 9.6443474e-2,
+    9.8739095e-2, 1.0106627e-1, 1.0342513e-1, 1.05815805e-1, 1.082384e-1, 1.10693045e-1, 1.1317986e-1, 1.1569897e-1,
+    1.1825048e-1, 1.2083452e-1, 1.234512e-1, 1.2610064e-1, 1.2878296e-1, 1.3149826e-1, 1.3424668e-1, 1.370283e-1,
+    1.3984327e-1, 1.4269169e-1, 1.4557366e-1, 1.4848931e-1, 1.5143873e-1, 1.5442206e-1, 1.5743938e-1, 1.6049083e-1,
+    1.635765e-1, 1.6669649e-1, 1.6985093e-1, 1.7303991e-1, 1.7626357e-1, 1.7952198e-1, 1.8281525e-1, 1.861435e-1,
+    1.8950683e-1, 1.9290535e-1, 1.9633915e-1, 1.9980834e-1, 2.0331304e-1, 2.0685335e-1, 2.1042934e-1, 2.1404114e-1,
+    2.1768884e-1, 2.2137256e-1, 2.250924e-1, 2.2884843e-1, 2.3264076e-1, 2.364695e-1, 2.4033478e-1, 2.4423663e-1,
+    2.481752e-1, 2.5215057e-1, 2.5616285e-1, 2.6021212e-1, 2.6429847e-1, 2.6842204e-1, 2.725829e-1, 2.767811e-1,
+    2.810168e-1, 2.852901e-1, 2.8960103e-1, 2.9394972e-1, 2.983363e-1, 3.027608e-1, 3.0722335e-1, 3.1172404e-1,
+    3.1626296e-1, 3.2084018e-1, 3.2545584e-1, 3.3010998e-1, 3.3480275e-1, 3.3953416e-1, 3.4430438e-1, 3.4911346e-1,
+    3.539615e-1, 3.5884857e-1, 3.6377478e-1, 3.6874023e-1, 3.7374496e-1, 3.7878913e-1, 3.8387278e-1, 3.88996e-1,
+    3.941589e-1, 3.9936152e-1, 4.0460402e-1, 4.0988642e-1, 4.1520882e-1, 4.2057136e-1, 4.2597404e-1, 4.3141702e-1,
+    4.3690035e-1, 4.4242412e-1, 4.4798842e-1, 4.535933e-1, 4.5923892e-1, 4.649253e-1, 4.7065252e-1, 4.764207e-1,
+    4.8222992e-1, 4.8808023e-1, 4.9397177e-1, 4.9990454e-1, 5.058787e-1, 5.118943e-1, 5.179514e-1, 5.240501e-1,
+    5.301905e-1, 5.363727e-1, 5.4259676e-1, 5.488627e-1, 5.5517066e-1, 5.615207e-1, 5.679129e-1, 5.743473e-1,
+    5.8082414e-1, 5.8734334e-1, 5.93905e-1, 6.005092e-1, 6.071561e-1, 6.138457e-1, 6.205781e-1, 6.2735337e-1,
+    6.341716e-1, 6.410329e-1, 6.4793724e-1, 6.548848e-1, 6.6187567e-1, 6.689098e-1, 6.7598736e-1, 6.8310845e-1,
+    6.902731e-1, 6.9748133e-1, 7.047334e-1, 7.1202916e-1, 7.193688e-1, 7.2675246e-1, 7.3418003e-1, 7.416518e-1,
+    7.491677e-1, 7.567278e-1, 7.643323e-1, 7.719811e-1, 7.796744e-1, 7.874123e-1, 7.9519475e-1, 8.030219e-1,
+    8.1089383e-1, 8.188105e-1, 8.267722e-1, 8.347788e-1, 8.428305e-1, 8.509273e-1, 8.590692e-1, 8.672565e-1,
+    8.7548906e-1, 8.8376707e-1, 8.9209056e-1, 9.004596e-1, 9.088742e-1, 9.1733456e-1, 9.258406e-1, 9.343926e-1,
+    9.4299036e-1, 9.5163417e-1, 9.6032405e-1, 9.6906e-1, 9.7784215e-1, 9.8667055e-1, 9.9554527e-1,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_tables_match_the_transfer_functions() {
+        for value in 0..=255u8 {
+            let linear = srgb_to_linear(value as f32 / 255.0);
+            assert!((srgb8_to_linear(value) - linear).abs() < 1e-6);
+            assert_eq!(linear_to_srgb8(linear), value);
+        }
+        for i in 0..=1000 {
+            let linear = i as f32 / 1000.0;
+            let expected = (linear_to_srgb(linear) * 255.0).round() as i32;
+            assert!((linear_to_srgb8(linear) as i32 - expected).abs() <= 1);
+        }
+        assert_eq!(linear_to_srgb8(-0.5), 0);
+        assert_eq!(linear_to_srgb8(1.5), 255);
+    }
+
+    #[test]
+    fn test_convert_slice_matches_single_color_conversions() {
+        let colors: Vec<[f32; 4]> = (0..150)
+            .map(|i| [(i % 7) as f32 / 6.0, (i % 11) as f32 / 10.0, (i % 13) as f32 / 12.0, 0.5])
+            .collect();
+        let mut oklch = vec![[0.0; 4]; colors.len()];
+        convert_slice(&colors, ColorSpace::Srgb, ColorSpace::OkLch, &mut oklch);
+        let mut lab = vec![[0.0; 4]; colors.len()];
+        convert_slice(&colors, ColorSpace::Srgb, ColorSpace::Lab, &mut lab);
+
+        for ((color, oklch), lab) in colors.iter().zip(&oklch).zip(&lab) {
+            let linear = [color[0], color[1], color[2]].map(srgb_to_linear);
+            let xyz = linear_srgb_to_xyz_d65(linear, color[3]);
+            let expected = oklab_to_oklch(xyz_d65_to_oklab(xyz));
+            assert!((oklch[0] - expected.l).abs() < 1e-5 && (oklch[1] - expected.c).abs() < 1e-5);
+            if expected.c > 1e-3 {
+                assert!((oklch[2] - expected.h).abs() < 1e-2);
+            }
+            assert_eq!(oklch[3], 0.5);
+            let expected = xyz_d50_to_lab(xyz_d65_to_xyz_d50(xyz));
+            assert!((lab[0] - expected.l).abs() < 1e-3 && (lab[1] - expected.a).abs() < 1e-3);
+        }
+
+        let mut back = vec![[0.0; 4]; colors.len()];
+        convert_slice(&lab, ColorSpace::Lab, ColorSpace::Srgb, &mut back);
+        for (color, back) in colors.iter().zip(&back) {
+            assert!(color.iter().zip(back).all(|(a, b)| (a - b).abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn test_rgba8_buffers() {
+        let pixels: Vec<u8> = (0..=255u8).flat_map(|v| [v, 255 - v, v / 2, 200]).collect();
+        let mut colors = vec![Color::default(); 256];
+        srgb8_to_oklch_batch(&pixels, &mut colors);
+        for (pixel, color) in pixels.chunks_exact(4).zip(&colors) {
+            let expected = Color::from_rgb(pixel[0], pixel[1], pixel[2], 200.0 / 255.0);
+            assert!((color.l - expected.l).abs() < 1e-5 && (color.c - expected.c).abs() < 1e-5);
+            assert_eq!(color.alpha, expected.alpha);
+        }
+
+        let planes = rgba8_to_oklab_planes(&pixels);
+        assert_eq!(planes.len(), 256);
+        let mut round_trip = vec![0; pixels.len()];
+        oklab_planes_to_rgba8(&planes, &mut round_trip);
+        assert_eq!(round_trip, pixels);
+    }
+}
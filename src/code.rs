@@ -0,0 +1,405 @@
+// Color literals from programming languages and platform APIs
+
+use crate::color_space::srgb_to_linear;
+use crate::BigColor;
+
+/// Order of the channels in packed 32-bit integers and 8-digit hex
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ByteOrder {
+    /// `0xRRGGBBAA`, as in CSS `#RRGGBBAA`
+    Rgba,
+    /// `0xAARRGGBB`, as used by Android, Flutter, Compose and .NET
+    Argb,
+}
+
+/// A numeric argument as written in code
+#[derive(Debug, Clone, Copy)]
+struct Number {
+    value: f64,
+    /// Written with a decimal point or a float suffix such as `0.5f`
+    is_float: bool,
+}
+
+/// Parses an integer or float literal, ignoring type suffixes like `u`, `L` or `f`
+fn parse_number(token: &str) -> Option<Number> {
+    let token = token.trim();
+    if let Some(hex) = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+        let hex = hex.trim_end_matches(['u', 'U', 'l', 'L']);
+        let value = u64::from_str_radix(hex, 16).ok()?;
+        return Some(Number { value: value as f64, is_float: false });
+    }
+
+    let trimmed = token.trim_end_matches(['f', 'F', 'd', 'D', 'u', 'U', 'l', 'L']);
+    let is_float = trimmed.contains('.') || trimmed.len() < token.len() && token.ends_with(['f', 'F', 'd', 'D']);
+    let value = trimmed.parse::<f64>().ok()?;
+    Some(Number { value, is_float })
+}
+
+/// Splits a packed 32-bit color into RGBA bytes
+fn unpack(value: u32, byte_order: ByteOrder) -> (u8, u8, u8, u8) {
+    let [b0, b1, b2, b3] = value.to_be_bytes();
+    match byte_order {
+        ByteOrder::Rgba => (b0, b1, b2, b3),
+        ByteOrder::Argb => (b1, b2, b3, b0),
+    }
+}
+
+/// Builds a color from RGBA bytes
+fn from_bytes((r, g, b, a): (u8, u8, u8, u8)) -> BigColor {
+    BigColor::from_rgb(r, g, b, a as f32 / 255.0)
+}
+
+/// Parses hex digits as a color. Six digits are opaque RGB, eight digits
+/// follow the byte order. Three and four digits are shorthand for the same.
+pub(crate) fn parse_hex_digits(digits: &str, byte_order: ByteOrder) -> Option<BigColor> {
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded: String = match digits.len() {
+        3 | 4 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => digits.to_string(),
+        _ => return None,
+    };
+
+    let value = u32::from_str_radix(&expanded, 16).ok()?;
+    if expanded.len() == 6 {
+        return Some(from_bytes(unpack((value << 8) | 0xff, ByteOrder::Rgba)));
+    }
+    Some(from_bytes(unpack(value, byte_order)))
+}
+
+/// Parses an integer literal such as `0xFF1A6EF5`
+fn parse_hex_int(token: &str, byte_order: ByteOrder) -> Option<BigColor> {
+    let token = token.trim();
+    let digits = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X"))?;
+    let digits = digits.trim_end_matches(['u', 'U', 'l', 'L']);
+    match digits.len() {
+        6 | 8 => parse_hex_digits(digits, byte_order),
+        _ => None,
+    }
+}
+
+/// Converts a channel written either as an integer from 0 to 255 or as a
+/// float from 0.0 to 1.0
+fn channel(number: Number) -> u8 {
+    if number.is_float {
+        (number.value * 255.0).round().clamp(0.0, 255.0) as u8
+    } else {
+        number.value.round().clamp(0.0, 255.0) as u8
+    }
+}
+
+/// Converts an alpha written either as an integer from 0 to 255 or as a
+/// float from 0.0 to 1.0
+fn alpha(number: Number) -> f32 {
+    if number.is_float {
+        number.value.clamp(0.0, 1.0) as f32
+    } else {
+        (number.value.clamp(0.0, 255.0) / 255.0) as f32
+    }
+}
+
+/// Treats every number as a float, for APIs whose channels are always 0.0 to 1.0
+fn as_float(number: Number) -> Number {
+    Number { is_float: true, ..number }
+}
+
+/// Parses arguments labelled `red:`/`green:`/`blue:` (Swift) or `red =` (Kotlin),
+/// with optional `alpha` or `opacity`, or `white:` and `alpha:` for greys
+fn parse_labelled(args: &[&str]) -> Option<BigColor> {
+    let mut red = None;
+    let mut green = None;
+    let mut blue = None;
+    let mut white = None;
+    let mut opacity = None;
+
+    for arg in args {
+        let (label, value) = arg.split_once(':').or_else(|| arg.split_once('='))?;
+        let value = parse_number(value)?;
+        match label.trim() {
+            "red" => red = Some(value),
+            "green" => green = Some(value),
+            "blue" => blue = Some(value),
+            "white" => white = Some(value),
+            "alpha" | "opacity" => opacity = Some(value),
+            _ => return None,
+        }
+    }
+
+    // Labelled channels are floats unless every channel is written as an integer
+    // above 1, as in Compose `Color(red = 26, green = 110, blue = 245)`
+    let (r, g, b) = match (red, green, blue, white) {
+        (Some(r), Some(g), Some(b), None) => (r, g, b),
+        (None, None, None, Some(w)) => (as_float(w), as_float(w), as_float(w)),
+        _ => return None,
+    };
+    let integers = [r, g, b].iter().all(|n| !n.is_float) && [r, g, b].iter().any(|n| n.value > 1.0);
+    let fix = |n: Number| if integers { n } else { as_float(n) };
+    let a = opacity.map_or(1.0, |n| alpha(if integers { n } else { as_float(n) }));
+
+    Some(BigColor::from_rgb(channel(fix(r)), channel(fix(g)), channel(fix(b)), a))
+}
+
+/// Parses the argument list of a call, given the callee name in lowercase
+fn parse_call(callee: &str, args: &[&str]) -> Option<BigColor> {
+    if args.iter().any(|arg| arg.contains(':') || arg.contains('=')) {
+        return parse_labelled(args);
+    }
+
+    // A single packed integer. The constructors that take one are all ARGB.
+    if let [arg] = args {
+        if let Some(stripped) = arg.strip_prefix('"').and_then(|a| a.strip_suffix('"')) {
+            return (callee == "color.parsecolor")
+                .then(|| parse_hex_digits(stripped.strip_prefix('#')?, ByteOrder::Argb))
+                .flatten();
+        }
+        let value = parse_number(arg)?;
+        // Signed `@ColorInt`s such as -15044875 wrap to their ARGB bits
+        let packed = (!value.is_float && value.value.fract() == 0.0)
+            .then_some(value.value)
+            .filter(|v| (i32::MIN as f64..=u32::MAX as f64).contains(v))?;
+        return match callee {
+            "color" | "color.fromargb" | "color.valueof" => {
+                Some(from_bytes(unpack(packed as i64 as u32, ByteOrder::Argb)))
+            }
+            _ => None,
+        };
+    }
+
+    let numbers = args.iter().map(|arg| parse_number(arg)).collect::<Option<Vec<_>>>()?;
+    match (callee, numbers.as_slice()) {
+        // Flutter, .NET and Android: alpha first
+        ("color.fromargb" | "color.argb", &[a, r, g, b]) => {
+            Some(BigColor::from_rgb(channel(r), channel(g), channel(b), alpha(a)))
+        }
+        // Flutter: integer channels with a float opacity
+        ("color.fromrgbo", &[r, g, b, o]) => {
+            Some(BigColor::from_rgb(channel(r), channel(g), channel(b), alpha(as_float(o))))
+        }
+        ("color.fromargb" | "color.fromrgb" | "color.rgb" | "color", &[r, g, b]) => {
+            Some(BigColor::from_rgb(channel(r), channel(g), channel(b), 1.0))
+        }
+        // Compose and Android: alpha last
+        ("color" | "color.valueof", &[r, g, b, a]) => {
+            Some(BigColor::from_rgb(channel(r), channel(g), channel(b), alpha(a)))
+        }
+        _ => None,
+    }
+}
+
+/// Parses a color literal written in code
+///
+/// Understands packed integers (`0xFF1A6EF5`), 8-digit hex (`#AARRGGBB` in
+/// Android resources), Flutter and Compose `Color(0xFF1A6EF5)`,
+/// `Color.fromARGB`, `Color.fromRGBO`, .NET `Color.FromArgb`, Android
+/// `Color.argb`/`Color.rgb`/`Color.parseColor`, and Swift/SwiftUI/Compose
+/// constructors with labelled `red`, `green`, `blue` and `alpha`/`opacity`.
+///
+/// `byte_order` decides how bare integers and 8- or 4-digit hex are read.
+/// Constructors whose API defines an order, such as `Color(0xFF1A6EF5)`,
+/// always use it.
+pub fn parse_code_literal(input: &str, byte_order: ByteOrder) -> Option<BigColor> {
+    let input = input.trim().trim_end_matches(';').trim_end();
+
+    if let Some(digits) = input.strip_prefix('#') {
+        return parse_hex_digits(digits, byte_order);
+    }
+    if input.starts_with("0x") || input.starts_with("0X") {
+        return parse_hex_int(input, byte_order);
+    }
+
+    let open = input.find('(')?;
+    let inner = input[open + 1..].strip_suffix(')')?;
+    let callee: String = input[..open]
+        .trim()
+        .trim_start_matches("new ")
+        .trim_start_matches("const ")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    // Platform prefixes share the same constructors
+    let callee = match callee.as_str() {
+        "uicolor" | "nscolor" | "cgcolor" | "swiftui.color" => "color",
+        "system.drawing.color.fromargb" | "colors.fromargb" => "color.fromargb",
+        "android.graphics.color.argb" => "color.argb",
+        "android.graphics.color.rgb" => "color.rgb",
+        "android.graphics.color.parsecolor" => "color.parsecolor",
+        other => other,
+    };
+
+    let args: Vec<&str> = inner.split(',').map(str::trim).filter(|arg| !arg.is_empty()).collect();
+    parse_call(callee, &args)
+}
+
+/// Code literal syntaxes a color can be written as
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CodeFormat {
+    /// Kotlin / Jetpack Compose `Color(0xFF1A6EF5)`
+    Compose,
+    /// SwiftUI `Color(red: 0.102, green: 0.431, blue: 0.961, opacity: 1.0)`
+    SwiftUI,
+    /// Flutter `Color(0xFF1A6EF5)`
+    Flutter,
+    /// .NET `Color.FromArgb(255, 26, 110, 245)`
+    CSharp,
+    /// Android resource value `#FF1A6EF5`
+    AndroidXml,
+    /// WGSL `vec4<f32>(...)`
+    Wgsl,
+    /// GLSL `vec4(...)`
+    Glsl,
+    /// HLSL `float4(...)`
+    Hlsl,
+}
+
+impl CodeFormat {
+    /// Returns true for shader formats, which accept linear-light values
+    pub fn is_shader(self) -> bool {
+        matches!(self, CodeFormat::Wgsl | CodeFormat::Glsl | CodeFormat::Hlsl)
+    }
+}
+
+/// Options for writing code literals
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CodeOptions {
+    /// Write shader channels as linear-light values instead of sRGB-encoded ones.
+    /// Alpha is never linearized. Ignored by non-shader formats.
+    pub linear: bool,
+    /// Maximum number of decimals for float channels
+    pub precision: usize,
+}
+
+impl Default for CodeOptions {
+    fn default() -> Self {
+        CodeOptions {
+            linear: false,
+            precision: 3,
+        }
+    }
+}
+
+/// Writes a float with at most `precision` decimals, always keeping a decimal point
+fn format_float(value: f32, precision: usize) -> String {
+    let fixed = format!("{:.*}", precision.max(1), value);
+    let trimmed = fixed.trim_end_matches('0');
+    if trimmed.ends_with('.') {
+        format!("{}0", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Writes a color as a code literal for another platform or a shader
+pub fn to_code(color: &BigColor, format: CodeFormat, options: Option<CodeOptions>) -> String {
+    let options = options.unwrap_or_default();
+    let rgb = color.to_rgb();
+    let alpha_byte = (rgb.a.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let argb = format!("{:02X}{:02X}{:02X}{:02X}", alpha_byte, rgb.r, rgb.g, rgb.b);
+
+    let float = |value: f32| format_float(value, options.precision);
+    let channels = [rgb.r, rgb.g, rgb.b].map(|c| {
+        let encoded = c as f32 / 255.0;
+        if options.linear && format.is_shader() {
+            float(srgb_to_linear(encoded))
+        } else {
+            float(encoded)
+        }
+    });
+    let [r, g, b] = &channels;
+    let a = float(rgb.a);
+
+    match format {
+        CodeFormat::Compose | CodeFormat::Flutter => format!("Color(0x{})", argb),
+        CodeFormat::SwiftUI => format!("Color(red: {}, green: {}, blue: {}, opacity: {})", r, g, b, a),
+        CodeFormat::CSharp => format!("Color.FromArgb({}, {}, {}, {})", alpha_byte, rgb.r, rgb.g, rgb.b),
+        CodeFormat::AndroidXml => format!("#{}", argb),
+        CodeFormat::Wgsl => format!("vec4<f32>({}, {}, {}, {})", r, g, b, a),
+        CodeFormat::Glsl => format!("vec4({}, {}, {}, {})", r, g, b, a),
+        CodeFormat::Hlsl => format!("float4({}, {}, {}, {})", r, g, b, a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(input: &str, byte_order: ByteOrder) -> String {
+        let color = parse_code_literal(input, byte_order).expect(input);
+        color.to_hex8_string(false)
+    }
+
+    #[test]
+    fn test_parses_platform_constructors() {
+        let cases = [
+            "0xFF1A6EF5",
+            "Color(0xFF1A6EF5)",
+            "Color(0xFF1A6EF5u)",
+            "Color.fromARGB(255, 26, 110, 245)",
+            "Color.fromRGBO(26, 110, 245, 1.0)",
+            "Color.FromArgb(255, 26, 110, 245)",
+            "Color.FromArgb(0xFF1A6EF5)",
+            "Color.argb(255, 26, 110, 245)",
+            "Color.parseColor(\"#FF1A6EF5\")",
+            "#FF1A6EF5",
+            "Color(red: 0.102, green: 0.431, blue: 0.961, opacity: 1)",
+            "UIColor(red: 0.102, green: 0.431, blue: 0.961, alpha: 1)",
+            "Color(red = 26, green = 110, blue = 245)",
+        ];
+        for case in cases {
+            assert_eq!(hex(case, ByteOrder::Argb), "#1a6ef5ff", "{}", case);
+        }
+    }
+
+    #[test]
+    fn test_byte_order_applies_to_bare_values() {
+        assert_eq!(hex("0x1A6EF580", ByteOrder::Rgba), "#1a6ef580");
+        assert_eq!(hex("0x801A6EF5", ByteOrder::Argb), "#1a6ef580");
+        assert_eq!(hex("#801A6EF5", ByteOrder::Argb), "#1a6ef580");
+        // Constructors with a defined order ignore the setting
+        assert_eq!(hex("Color(0x801A6EF5)", ByteOrder::Rgba), "#1a6ef580");
+
+        // CSS still parses through the fallback
+        assert!(BigColor::from_code("rgb(26, 110, 245)", ByteOrder::Argb).is_valid());
+        assert!(parse_code_literal("Color.fromARGB(255, 26)", ByteOrder::Argb).is_none());
+
+        // Android logs and decompiled code print colors as signed ints
+        assert_eq!(hex("Color(-15044875)", ByteOrder::Argb), "#1a6ef5ff");
+        assert_eq!(hex("Color.valueOf(-16777216)", ByteOrder::Argb), "#000000ff");
+        assert!(parse_code_literal("Color(-2147483649)", ByteOrder::Argb).is_none());
+        assert!(parse_code_literal("Color(0x1FF1A6EF5)", ByteOrder::Argb).is_none());
+    }
+
+    #[test]
+    fn test_writes_platform_and_shader_literals() {
+        let color = BigColor::new("#1a6ef5");
+        assert_eq!(to_code(&color, CodeFormat::Compose, None), "Color(0xFF1A6EF5)");
+        assert_eq!(to_code(&color, CodeFormat::CSharp, None), "Color.FromArgb(255, 26, 110, 245)");
+        assert_eq!(to_code(&color, CodeFormat::AndroidXml, None), "#FF1A6EF5");
+        assert_eq!(
+            to_code(&color, CodeFormat::SwiftUI, None),
+            "Color(red: 0.102, green: 0.431, blue: 0.961, opacity: 1.0)"
+        );
+        assert_eq!(to_code(&color, CodeFormat::Glsl, None), "vec4(0.102, 0.431, 0.961, 1.0)");
+
+        let linear = CodeOptions { linear: true, ..Default::default() };
+        assert_eq!(to_code(&color, CodeFormat::Wgsl, Some(linear)), "vec4<f32>(0.01, 0.156, 0.913, 1.0)");
+
+        // Every platform literal parses back to the same color
+        let platforms = [
+            CodeFormat::Compose,
+            CodeFormat::SwiftUI,
+            CodeFormat::Flutter,
+            CodeFormat::CSharp,
+            CodeFormat::AndroidXml,
+        ];
+        for format in platforms {
+            let parsed = parse_code_literal(&to_code(&color, format, None), ByteOrder::Argb).unwrap();
+            assert_eq!(parsed.to_hex_string(false), "#1a6ef5", "{:?}", format);
+        }
+    }
+}
@@ -0,0 +1,404 @@
+// Copy color value that the color operations are built on
+
+use crate::color_space::*;
+use crate::matrix_utils::rotate_hue_toward;
+use crate::parse::{hex_to_name, input_to_rgb};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+
+/// A color as OKLCH lightness (0 to 1), chroma, hue in degrees and alpha.
+///
+/// `Color` is 16 bytes and `Copy`: every operation takes and returns values,
+/// so nothing allocates. [`BigColor`](crate::BigColor) wraps a `Color` together
+/// with the input and format it was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+    pub alpha: f32,
+}
+
+impl Default for Color {
+    /// Opaque black
+    fn default() -> Self {
+        Color::new(0.0, 0.0, 0.0, 1.0)
+    }
+}
+
+impl From<OKLCH> for Color {
+    fn from(oklch: OKLCH) -> Self {
+        Color::new(oklch.l, oklch.c, oklch.h, oklch.alpha)
+    }
+}
+
+impl From<Color> for OKLCH {
+    fn from(color: Color) -> Self {
+        color.to_oklch()
+    }
+}
+
+impl Color {
+    /// Creates a color from OKLCH values. Values outside sRGB are kept.
+    pub const fn new(l: f32, c: f32, h: f32, alpha: f32) -> Self {
+        Color { l, c, h, alpha }
+    }
+
+    /// Creates a color from 8-bit sRGB channels
+    pub fn from_rgb(r: u8, g: u8, b: u8, alpha: f32) -> Self {
+        rgb_to_oklch(r, g, b, bound_alpha(alpha)).into()
+    }
+
+    /// Parses any input [`BigColor::new`](crate::BigColor::new) accepts,
+    /// without keeping the input
+    pub fn parse(input: &str) -> Option<Self> {
+        let rgb = input_to_rgb(input);
+        rgb.ok.then(|| rgb_to_oklch(rgb.r, rgb.g, rgb.b, rgb.a).into())
+    }
+
+    pub fn to_oklch(self) -> OKLCH {
+        OKLCH {
+            l: self.l,
+            c: self.c,
+            h: self.h,
+            alpha: self.alpha,
+        }
+    }
+
+    pub fn to_oklab(self) -> OKLab {
+        oklch_to_oklab(self.to_oklch())
+    }
+
+    /// Converts to 8-bit sRGB, clipping colors outside the gamut
+    pub fn to_rgb(self) -> RGB {
+        let (r, g, b, a) = oklch_to_rgb(self.to_oklch());
+        RGB { r, g, b, a }
+    }
+
+    pub fn to_hsv(self) -> HSV {
+        let rgb = self.to_rgb();
+        let hsv = rgb_to_hsv(rgb.r, rgb.g, rgb.b);
+        HSV {
+            h: hsv.h * 360.0,
+            s: hsv.s,
+            v: hsv.v,
+            a: self.alpha,
+        }
+    }
+
+    pub fn to_hsl(self) -> HSL {
+        let rgb = self.to_rgb();
+
+        let r_norm = rgb.r as f32 / 255.0;
+        let g_norm = rgb.g as f32 / 255.0;
+        let b_norm = rgb.b as f32 / 255.0;
+
+        let max = r_norm.max(g_norm).max(b_norm);
+        let min = r_norm.min(g_norm).min(b_norm);
+        let mut h = 0.0;
+        let mut s = 0.0;
+        let l = (max + min) / 2.0;
+
+        if max != min {
+            let d = max - min;
+            s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+
+            if max == r_norm {
+                h = (g_norm - b_norm) / d + (if g_norm < b_norm { 6.0 } else { 0.0 });
+            } else if max == g_norm {
+                h = (b_norm - r_norm) / d + 2.0;
+            } else if max == b_norm {
+                h = (r_norm - g_norm) / d + 4.0;
+            }
+
+            h /= 6.0;
+        }
+
+        HSL {
+            h: h * 360.0,
+            s,
+            l,
+            a: self.alpha,
+        }
+    }
+
+    pub fn to_lch(self) -> LCH {
+        let rgb = self.to_rgb();
+        rgb_to_lch(rgb.r, rgb.g, rgb.b, self.alpha)
+    }
+
+    pub fn to_lab(self) -> Lab {
+        let rgb = self.to_rgb();
+        let xyz_d65 = rgb_to_xyz_d65(rgb.r, rgb.g, rgb.b, self.alpha);
+        xyz_d50_to_lab(xyz_d65_to_xyz_d50(xyz_d65))
+    }
+
+    pub fn to_cmyk(self) -> CMYK {
+        let rgb = self.to_rgb();
+        rgb_to_cmyk(rgb.r, rgb.g, rgb.b, rgb.a)
+    }
+
+    /// Returns the CSS color name, if the color has one
+    pub fn to_name(self) -> Option<&'static str> {
+        if self.alpha == 0.0 {
+            Some("transparent")
+        } else if self.alpha < 1.0 {
+            None
+        } else {
+            let rgb = self.to_rgb();
+            hex_to_name(&rgb_to_hex(rgb.r, rgb.g, rgb.b, true))
+        }
+    }
+
+    /// Returns true if the OKLCH lightness is below one half
+    pub fn is_dark(self) -> bool {
+        self.l < 0.5
+    }
+
+    pub fn is_light(self) -> bool {
+        !self.is_dark()
+    }
+
+    /// Returns the OKLCH lightness scaled to 0 to 255
+    pub fn brightness(self) -> f32 {
+        self.l * 255.0
+    }
+
+    /// Returns the WCAG 2 relative luminance, from 0 to 1
+    pub fn luminance(self) -> f32 {
+        let rgb = self.to_rgb();
+        let linear = |channel: u8| {
+            let srgb = channel as f32 / 255.0;
+            if srgb <= 0.03928 {
+                srgb / 12.92
+            } else {
+                ((srgb + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(rgb.r) + 0.7152 * linear(rgb.g) + 0.0722 * linear(rgb.b)
+    }
+
+    /// Returns the WCAG 2 contrast ratio, from 1 to 21
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let (l1, l2) = (self.luminance(), other.luminance());
+        let (lighter, darker) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Returns the APCA lightness contrast (Lc) of this color as text on
+    /// the background
+    pub fn apca_contrast(self, background: Color) -> f32 {
+        let y_text = self.apca_luminance();
+        let y_background = background.apca_luminance();
+
+        if (y_background - y_text).abs() < 0.0005 {
+            return 0.0;
+        }
+
+        let lc = if y_background > y_text {
+            // Dark text on a light background
+            let sapc = (y_background.powf(0.56) - y_text.powf(0.57)) * 1.14;
+            if sapc < 0.1 { 0.0 } else { sapc - 0.027 }
+        } else {
+            // Light text on a dark background
+            let sapc = (y_background.powf(0.65) - y_text.powf(0.62)) * 1.14;
+            if sapc > -0.1 { 0.0 } else { sapc + 0.027 }
+        };
+
+        lc * 100.0
+    }
+
+    /// Estimated screen luminance used by APCA, with the soft black clamp applied
+    fn apca_luminance(self) -> f32 {
+        let rgb = self.to_rgb();
+        let channel = |c: u8| (c as f32 / 255.0).powf(2.4);
+        let y = 0.2126729 * channel(rgb.r) + 0.7151522 * channel(rgb.g) + 0.0721750 * channel(rgb.b);
+
+        if y < 0.022 {
+            y + (0.022 - y).powf(1.414)
+        } else {
+            y
+        }
+    }
+
+    /// Returns the perceptual distance (ΔEOK), ignoring alpha
+    pub fn delta_e(self, other: Color) -> f32 {
+        delta_e_ok(self.to_oklab(), other.to_oklab())
+    }
+
+    /// Returns the color with a new alpha; values outside 0 to 1 become 1
+    pub fn with_alpha(self, alpha: f32) -> Self {
+        Color { alpha: bound_alpha(alpha), ..self }
+    }
+
+    /// Raises the lightness by `amount` percent
+    pub fn lighten(self, amount: f32) -> Self {
+        Color { l: (self.l + amount / 100.0).clamp(0.0, 1.0), ..self }
+    }
+
+    /// Lowers the lightness by `amount` percent
+    pub fn darken(self, amount: f32) -> Self {
+        Color { l: (self.l - amount / 100.0).clamp(0.0, 1.0), ..self }
+    }
+
+    /// Adds `amount` percent of 255 to every sRGB channel
+    pub fn brighten(self, amount: f32) -> Self {
+        let rgb = self.to_rgb();
+        let channel = |c: u8| (c as f32 + 255.0 * (amount / 100.0)).round().clamp(0.0, 255.0) as u8;
+        rgb_to_oklch(channel(rgb.r), channel(rgb.g), channel(rgb.b), rgb.a).into()
+    }
+
+    /// Raises the chroma by `amount` hundredths
+    pub fn saturate(self, amount: f32) -> Self {
+        Color { c: self.c + amount / 100.0, ..self }
+    }
+
+    /// Lowers the chroma by `amount` hundredths, down to grey
+    pub fn desaturate(self, amount: f32) -> Self {
+        Color { c: (self.c - amount / 100.0).max(0.0), ..self }
+    }
+
+    pub fn greyscale(self) -> Self {
+        Color { c: 0.0, ..self }
+    }
+
+    /// Rotates the hue by `degrees`
+    pub fn spin(self, degrees: f32) -> Self {
+        let mut h = (self.h + degrees) % 360.0;
+        if h < 0.0 {
+            h += 360.0;
+        }
+        Color { h, ..self }
+    }
+
+    /// Rotates the hue toward the source's hue by half the difference, at
+    /// most `max_degrees`. Achromatic colors and sources are left unchanged.
+    pub fn harmonize(self, source: Color, max_degrees: f32) -> Self {
+        const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+        if self.c < ACHROMATIC_CHROMA || source.c < ACHROMATIC_CHROMA {
+            return self;
+        }
+        Color { h: rotate_hue_toward(self.h, source.h, max_degrees), ..self }
+    }
+
+    /// Mixes with another color in sRGB; `amount` is the percentage of `other`
+    pub fn mix(self, other: Color, amount: f32) -> Self {
+        let (rgb1, rgb2) = (self.to_rgb(), other.to_rgb());
+        let p = amount / 100.0;
+        let channel = |a: u8, b: u8| ((b as f32 - a as f32) * p + a as f32) as u8;
+        let alpha = (rgb2.a - rgb1.a) * p + rgb1.a;
+        Color::from_rgb(channel(rgb1.r, rgb2.r), channel(rgb1.g, rgb2.g), channel(rgb1.b, rgb2.b), alpha)
+    }
+
+    pub fn complement(self) -> Self {
+        Color { h: (self.h + 180.0) % 360.0, ..self }
+    }
+
+    /// The color followed by `results - 1` neighbours `360 / slices` degrees apart
+    pub fn analogous(self, results: usize, slices: usize) -> impl Iterator<Item = Color> {
+        let part = 360.0 / slices as f32;
+        let start = (self.h - ((part * results as f32) / 2.0) + 720.0) % 360.0;
+        let neighbours = (1..results).scan(start, move |h, _| {
+            *h = (*h + part) % 360.0;
+            Some(Color { h: *h, ..self })
+        });
+        core::iter::once(self).chain(neighbours)
+    }
+
+    /// `results` colors with the same hue and chroma and lightness rising from 0
+    pub fn monochromatic(self, results: usize) -> impl Iterator<Item = Color> {
+        let step = 1.0 / results as f32;
+        (0..results).scan(0.0f32, move |l, _| {
+            let color = Color { l: l.min(1.0), ..self };
+            *l += step;
+            Some(color)
+        })
+    }
+
+    pub fn split_complement(self) -> [Color; 3] {
+        [self, Color { h: (self.h + 72.0) % 360.0, ..self }, Color { h: (self.h + 216.0) % 360.0, ..self }]
+    }
+
+    pub fn triad(self) -> [Color; 3] {
+        let [a, b, c, ..] = self.polyad_array::<3>();
+        [a, b, c]
+    }
+
+    pub fn tetrad(self) -> [Color; 4] {
+        self.polyad_array::<4>()
+    }
+
+    fn polyad_array<const N: usize>(self) -> [Color; N] {
+        let step = 360.0 / N as f32;
+        core::array::from_fn(|i| if i == 0 { self } else { Color { h: (self.h + i as f32 * step) % 360.0, ..self } })
+    }
+
+    /// The color followed by `number - 1` colors evenly spaced around the hue circle
+    ///
+    /// # Panics
+    ///
+    /// Panics if `number` is zero.
+    pub fn polyad(self, number: usize) -> impl Iterator<Item = Color> {
+        assert!(number > 0, "Argument to polyad must be a positive number");
+        let step = 360.0 / number as f32;
+        (0..number).map(move |i| if i == 0 { self } else { Color { h: (self.h + i as f32 * step) % 360.0, ..self } })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BigColor;
+
+    #[test]
+    fn test_color_is_a_small_copy_value() {
+        assert_eq!(std::mem::size_of::<Color>(), 16);
+        let blue = Color::parse("#1a6ef5").unwrap();
+        let lighter = blue.lighten(10.0);
+        assert_ne!(blue, lighter);
+        assert_eq!(BigColor::from(lighter).to_hex_string(false), BigColor::new("#1a6ef5").lighten(None).to_hex_string(false));
+        assert_eq!(Color::parse("nope"), None);
+    }
+
+    #[test]
+    fn test_operations_match_big_color() {
+        let big = BigColor::new("#c0392b");
+        let color = big.color();
+        let hexes = |colors: &[Color]| colors.iter().map(|c| BigColor::from(*c).to_hex_string(false)).collect::<Vec<_>>();
+        let big_hexes = |colors: Vec<BigColor>| colors.iter().map(|c| c.to_hex_string(false)).collect::<Vec<_>>();
+
+        assert_eq!(hexes(&color.analogous(6, 30).collect::<Vec<_>>()), big_hexes(big.analogous(None, None)));
+        assert_eq!(hexes(&color.monochromatic(5).collect::<Vec<_>>()), big_hexes(big.monochromatic(Some(5))));
+        assert_eq!(hexes(&color.triad()), big_hexes(big.triad()));
+        assert_eq!(hexes(&color.split_complement()), big_hexes(big.split_complement()));
+        assert_eq!(color.polyad(5).count(), 5);
+        assert_eq!(color.contrast_ratio(Color::from_rgb(255, 255, 255, 1.0)), big.get_contrast_ratio(&BigColor::new("white")));
+        assert_eq!(color.mix(Color::default(), 50.0).to_rgb().r, crate::mix(&big, &BigColor::new("black"), None).to_rgb().r);
+    }
+
+    #[test]
+    fn test_harmonize_rotates_toward_source() {
+        let hue = |color: Color| (color.h * 1000.0).round() / 1000.0;
+        let source = Color::new(0.6, 0.15, 100.0, 1.0);
+
+        // Half the difference, capped at max_degrees, keeping l, c and alpha
+        let near = Color::new(0.5, 0.1, 80.0, 0.5).harmonize(source, 15.0);
+        assert_eq!((hue(near), near.l, near.c, near.alpha), (90.0, 0.5, 0.1, 0.5));
+        assert_eq!(hue(Color::new(0.5, 0.1, 20.0, 1.0).harmonize(source, 15.0)), 35.0);
+        assert_eq!(hue(Color::new(0.5, 0.1, 160.0, 1.0).harmonize(source, 15.0)), 145.0);
+
+        // The shorter way around crosses 0°/360°
+        let red = Color::new(0.6, 0.15, 10.0, 1.0);
+        assert_eq!(hue(Color::new(0.5, 0.1, 350.0, 1.0).harmonize(red, 15.0)), 0.0);
+        assert_eq!(hue(Color::new(0.5, 0.1, 20.0, 1.0).harmonize(Color::new(0.6, 0.15, 340.0, 1.0), 15.0)), 5.0);
+
+        // Achromatic colors and sources are left unchanged
+        let grey = Color::new(0.5, 0.0, 80.0, 1.0);
+        assert_eq!(grey.harmonize(source, 15.0), grey);
+        let tinted = Color::new(0.5, 0.1, 20.0, 1.0);
+        assert_eq!(tinted.harmonize(Color::new(0.6, 0.0, 100.0, 1.0), 15.0), tinted);
+    }
+}
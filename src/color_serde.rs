@@ -0,0 +1,150 @@
+// Serde representations for BigColor
+//
+// `BigColor` serializes as a CSS string by default. The `css`, `hex` and
+// `channels` modules can be used with `#[serde(with = "...")]` to pick a
+// representation per field.
+
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::color_space::RGB;
+use crate::{BigColor, ColorFormat};
+
+/// Visitor that parses a string through the regular parser
+struct ColorStringVisitor {
+    expecting: &'static str,
+    accept: fn(&BigColor) -> bool,
+}
+
+impl Visitor<'_> for ColorStringVisitor {
+    type Value = BigColor;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<BigColor, E> {
+        let color = BigColor::new(value.trim());
+        if color.is_valid() && (self.accept)(&color) {
+            Ok(color)
+        } else {
+            Err(E::invalid_value(Unexpected::Str(value), &self))
+        }
+    }
+}
+
+impl Serialize for BigColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        css::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        css::deserialize(deserializer)
+    }
+}
+
+/// A CSS color string in the color's own format, such as `"oklch(0.6 0.2 260)"`.
+/// Any input the parser accepts deserializes.
+pub mod css {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &BigColor, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_string(None))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigColor, D::Error> {
+        deserializer.deserialize_str(ColorStringVisitor {
+            expecting: "a CSS color string",
+            accept: |_| true,
+        })
+    }
+}
+
+/// A hex string, `"#1a6ef5"`, or `"#1a6ef580"` when the color is translucent
+pub mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &BigColor, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex = if color.get_alpha() < 1.0 {
+            color.to_hex8_string(false)
+        } else {
+            color.to_hex_string(false)
+        };
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigColor, D::Error> {
+        deserializer.deserialize_str(ColorStringVisitor {
+            expecting: "a hex color such as \"#1a6ef5\"",
+            accept: |color| {
+                matches!(
+                    color.get_format(),
+                    ColorFormat::HEX | ColorFormat::HEX3 | ColorFormat::HEX6 | ColorFormat::HEX8
+                )
+            },
+        })
+    }
+}
+
+/// A struct of 8-bit channels with a 0.0 to 1.0 alpha: `{"r": 26, "g": 110, "b": 245, "a": 1.0}`
+pub mod channels {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &BigColor, serializer: S) -> Result<S::Ok, S::Error> {
+        color.to_rgb().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigColor, D::Error> {
+        let rgb = RGB::deserialize(deserializer)?;
+        if !(0.0..=1.0).contains(&rgb.a) {
+            return Err(de::Error::invalid_value(
+                Unexpected::Float(rgb.a as f64),
+                &"an alpha between 0.0 and 1.0",
+            ));
+        }
+        Ok(BigColor::from_rgb(rgb.r, rgb.g, rgb.b, rgb.a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Palette {
+        accent: BigColor,
+        #[serde(with = "hex")]
+        border: BigColor,
+        #[serde(with = "channels")]
+        overlay: BigColor,
+    }
+
+    #[test]
+    fn test_representations_round_trip() {
+        let json = r##"{"accent":"rgb(26, 110, 245)","border":"#333","overlay":{"r":0,"g":0,"b":0,"a":0.5}}"##;
+        let palette: Palette = serde_json::from_str(json).unwrap();
+        assert_eq!(palette.accent.to_hex_string(false), "#1a6ef5");
+        assert_eq!(palette.overlay.get_alpha(), 0.5);
+
+        let written = serde_json::to_string(&palette).unwrap();
+        assert_eq!(
+            written,
+            r##"{"accent":"rgb(26, 110, 245)","border":"#333333","overlay":{"r":0,"g":0,"b":0,"a":0.5}}"##
+        );
+    }
+
+    #[test]
+    fn test_invalid_colors_are_reported() {
+        let error = serde_json::from_str::<BigColor>(r#""not-a-color""#).unwrap_err();
+        assert!(error.to_string().contains("expected a CSS color string"));
+
+        let json = r##"{"accent":"red","border":"red","overlay":{"r":0,"g":0,"b":0,"a":1.0}}"##;
+        let error = serde_json::from_str::<Palette>(json).err().unwrap();
+        assert!(error.to_string().contains("expected a hex color"));
+    }
+}
@@ -1,11 +1,16 @@
 // Color space conversion functions
 // Ported from tinycolor.js
 
+use alloc::string::{String, ToString};
+use alloc::{format, vec};
 use crate::matrix_utils::*;
-use std::f32::consts::PI;
+use core::f32::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
 
 /// RGB color
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RGB {
     pub r: u8,
     pub g: u8,
@@ -15,6 +20,7 @@ pub struct RGB {
 
 /// HSL color
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HSL {
     pub h: f32,
     pub s: f32,
@@ -24,6 +30,7 @@ pub struct HSL {
 
 /// HSV color
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HSV {
     pub h: f32,
     pub s: f32,
@@ -33,6 +40,7 @@ pub struct HSV {
 
 /// Percentage RGB color
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PercentageRGB {
     pub r: f32,
     pub g: f32,
@@ -42,6 +50,7 @@ pub struct PercentageRGB {
 
 /// XYZ D65 color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XyzD65 {
     pub x: f32,
     pub y: f32,
@@ -51,6 +60,7 @@ pub struct XyzD65 {
 
 /// XYZ D50 color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XyzD50 {
     pub x: f32,
     pub y: f32,
@@ -60,6 +70,7 @@ pub struct XyzD50 {
 
 /// Lab color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lab {
     pub l: f32, // Lightness: 0-100
     pub a: f32, // a axis: -125 to 125
@@ -69,6 +80,7 @@ pub struct Lab {
 
 /// LCH color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LCH {
     pub l: f32, // Lightness: 0-100
     pub c: f32, // Chroma: 0-150+
@@ -78,6 +90,7 @@ pub struct LCH {
 
 /// OKLab color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OKLab {
     pub l: f32, // Lightness: 0-1
     pub a: f32, // a axis: -0.4 to 0.4
@@ -87,6 +100,7 @@ pub struct OKLab {
 
 /// OKLCH color space
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OKLCH {
     pub l: f32, // Lightness: 0-1
     pub c: f32, // Chroma: 0-0.4+
@@ -96,6 +110,7 @@ pub struct OKLCH {
 
 /// CMYK color model
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CMYK {
     pub c: f32, // Cyan: 0-100%
     pub m: f32, // Magenta: 0-100%
@@ -362,24 +377,21 @@ pub fn rgba_to_argb_hex(r: u8, g: u8, b: u8, a: f32) -> String {
 }
 
 // Constants for Lab conversions
-const EPSILON: f32 = 216.0 / 24389.0; // 6^3/29^3 == (24/116)^3
-const EPSILON3: f32 = 24.0 / 116.0;
-const KAPPA: f32 = 24389.0 / 27.0; // 29^3/3^3
+pub(crate) const EPSILON: f32 = 216.0 / 24389.0; // 6^3/29^3 == (24/116)^3
+pub(crate) const EPSILON3: f32 = 24.0 / 116.0;
+pub(crate) const KAPPA: f32 = 24389.0 / 27.0; // 29^3/3^3
 
 /// Convert RGB to XYZ D65
 pub fn rgb_to_xyz_d65(r: u8, g: u8, b: u8, a: f32) -> XyzD65 {
     // sRGB to linear RGB
-    let r_linear = srgb_to_linear(r as f32 / 255.0);
-    let g_linear = srgb_to_linear(g as f32 / 255.0);
-    let b_linear = srgb_to_linear(b as f32 / 255.0);
+    let linear = [r, g, b].map(|c| srgb_to_linear(c as f32 / 255.0));
+    linear_srgb_to_xyz_d65(linear, a)
+}
 
-    // Linear RGB to XYZ D65
+/// Convert linear-light sRGB to XYZ D65
+pub fn linear_srgb_to_xyz_d65(rgb: [f32; 3], a: f32) -> XyzD65 {
     // sRGB uses D65 as reference white
-    let xyz = [
-        0.4124564 * r_linear + 0.3575761 * g_linear + 0.1804375 * b_linear,
-        0.2126729 * r_linear + 0.7151522 * g_linear + 0.0721750 * b_linear,
-        0.0193339 * r_linear + 0.1191920 * g_linear + 0.9503041 * b_linear,
-    ];
+    let xyz = multiply_v3_m3x3(rgb, LINEAR_SRGB_TO_XYZ_M);
 
     XyzD65 {
         x: xyz[0],
@@ -389,13 +401,34 @@ pub fn rgb_to_xyz_d65(r: u8, g: u8, b: u8, a: f32) -> XyzD65 {
     }
 }
 
+/// Convert linear-light Display P3 to XYZ D65
+pub fn linear_display_p3_to_xyz_d65(rgb: [f32; 3], a: f32) -> XyzD65 {
+    let [r, g, b] = rgb;
+    XyzD65 {
+        x: 0.4865709 * r + 0.2656677 * g + 0.1982173 * b,
+        y: 0.2289746 * r + 0.6917385 * g + 0.0792869 * b,
+        z: 0.0451134 * g + 1.0439444 * b,
+        a,
+    }
+}
+
+/// Convert XYZ D65 to linear-light Display P3 without clipping
+pub fn xyz_d65_to_linear_display_p3(xyz: XyzD65) -> [f32; 3] {
+    [
+        2.4934969 * xyz.x - 0.9313836 * xyz.y - 0.4027108 * xyz.z,
+        -0.8294890 * xyz.x + 1.7626641 * xyz.y + 0.0236247 * xyz.z,
+        0.0358458 * xyz.x - 0.0761724 * xyz.y + 0.9568845 * xyz.z,
+    ]
+}
+
+/// Convert XYZ D65 to linear-light sRGB without clipping
+pub fn xyz_d65_to_linear_srgb(xyz: XyzD65) -> [f32; 3] {
+    multiply_v3_m3x3([xyz.x, xyz.y, xyz.z], XYZ_TO_LINEAR_SRGB_M)
+}
+
 /// Convert XYZ D65 to RGB
 pub fn xyz_d65_to_rgb(xyz: XyzD65) -> (u8, u8, u8, f32) {
-    let rgb_linear = [
-        3.2404542 * xyz.x - 1.5371385 * xyz.y - 0.4985314 * xyz.z,
-        -0.9692660 * xyz.x + 1.8760108 * xyz.y + 0.0415560 * xyz.z,
-        0.0556434 * xyz.x - 0.2040259 * xyz.y + 1.0572252 * xyz.z,
-    ];
+    let rgb_linear = xyz_d65_to_linear_srgb(xyz);
 
     // Linear RGB to sRGB
     let r = (linear_to_srgb(rgb_linear[0]) * 255.0).round() as u8;
@@ -611,7 +644,7 @@ pub fn oklab_to_rgb(oklab: OKLab) -> (u8, u8, u8, f32) {
 }
 
 /// Convert sRGB to linear RGB
-fn srgb_to_linear(srgb: f32) -> f32 {
+pub fn srgb_to_linear(srgb: f32) -> f32 {
     if srgb <= 0.04045 {
         srgb / 12.92
     } else {
@@ -620,7 +653,7 @@ fn srgb_to_linear(srgb: f32) -> f32 {
 }
 
 /// Convert linear RGB to sRGB
-fn linear_to_srgb(linear: f32) -> f32 {
+pub fn linear_to_srgb(linear: f32) -> f32 {
     if linear <= 0.0031308 {
         linear * 12.92
     } else {
@@ -628,6 +661,12 @@ fn linear_to_srgb(linear: f32) -> f32 {
     }
 }
 
+/// Euclidean distance between two OKLab colors (ΔEOK)
+/// Alpha is ignored. A difference of about 0.02 is just noticeable
+pub fn delta_e_ok(a: OKLab, b: OKLab) -> f32 {
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
 /// Convert RGB to OKLCH
 pub fn rgb_to_oklch(r: u8, g: u8, b: u8, a: f32) -> OKLCH {
     let xyz_d65 = rgb_to_xyz_d65(r, g, b, a);
@@ -642,6 +681,47 @@ pub fn oklch_to_rgb(oklch: OKLCH) -> (u8, u8, u8, f32) {
     xyz_d65_to_rgb(xyz_d65)
 }
 
+/// Convert OKLCH to linear-light sRGB without clipping
+/// Components outside [0, 1] mean the color is out of the sRGB gamut
+pub fn oklch_to_linear_srgb(oklch: OKLCH) -> [f32; 3] {
+    xyz_d65_to_linear_srgb(oklab_to_xyz_d65(oklch_to_oklab(oklch)))
+}
+
+/// Returns true if an OKLCH color can be shown in sRGB without clipping
+pub fn is_oklch_in_srgb_gamut(oklch: OKLCH) -> bool {
+    const TOLERANCE: f32 = 1e-4;
+    oklch_to_linear_srgb(oklch)
+        .iter()
+        .all(|&v| (-TOLERANCE..=1.0 + TOLERANCE).contains(&v))
+}
+
+/// Maps an OKLCH color into the sRGB gamut by reducing chroma
+/// Lightness and hue are preserved
+pub fn gamut_map_oklch(oklch: OKLCH) -> OKLCH {
+    let mut mapped = OKLCH {
+        l: clamp_01(oklch.l),
+        ..oklch
+    };
+    if is_oklch_in_srgb_gamut(mapped) {
+        return mapped;
+    }
+
+    // Binary search for the largest chroma that still fits
+    let mut low = 0.0;
+    let mut high = mapped.c;
+    for _ in 0..24 {
+        let mid = (low + high) / 2.0;
+        mapped.c = mid;
+        if is_oklch_in_srgb_gamut(mapped) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    mapped.c = low;
+    mapped
+}
+
 /// Convert RGB to LCH
 pub fn rgb_to_lch(r: u8, g: u8, b: u8, a: f32) -> LCH {
     let xyz_d65 = rgb_to_xyz_d65(r, g, b, a);
@@ -695,7 +775,7 @@ pub fn clamp_01(val: f32) -> f32 {
 
 /// Need to handle 1.0 as 100%, since once it is a number, there is no difference between it and 1
 fn is_one_point_zero(n: f32) -> bool {
-    n.abs() - 1.0 < std::f32::EPSILON
+    n.abs() - 1.0 < core::f32::EPSILON
 }
 
 /// Check to see if the value is a percentage (between 0-100)
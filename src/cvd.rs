@@ -0,0 +1,400 @@
+// Color vision deficiency (CVD) simulation, daltonization and palette checks
+// Simulation matrices from Machado, Oliveira & Fernandes (2009)
+
+use alloc::vec::Vec;
+use crate::batch::{linear_to_srgb8, srgb8_to_linear};
+use crate::color_space::*;
+use crate::matrix_utils::*;
+use crate::BigColor;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+
+/// Types of color vision deficiency
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CvdType {
+    /// Missing L (red) cones
+    Protanopia,
+    /// Missing M (green) cones
+    Deuteranopia,
+    /// Missing S (blue) cones
+    Tritanopia,
+    /// No color perception at all
+    Achromatopsia,
+}
+
+impl CvdType {
+    /// Every supported deficiency, in a stable order
+    pub const ALL: [CvdType; 4] = [
+        CvdType::Protanopia,
+        CvdType::Deuteranopia,
+        CvdType::Tritanopia,
+        CvdType::Achromatopsia,
+    ];
+}
+
+const PROTANOPIA_M: Matrix3x3 = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+
+const DEUTERANOPIA_M: Matrix3x3 = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+const TRITANOPIA_M: Matrix3x3 = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+const ACHROMATOPSIA_M: Matrix3x3 = [
+    [0.2126, 0.7152, 0.0722],
+    [0.2126, 0.7152, 0.0722],
+    [0.2126, 0.7152, 0.0722],
+];
+
+/// Returns the linear-light RGB simulation matrix for a deficiency,
+/// blended with the identity matrix by `severity` (0.0 to 1.0)
+pub(crate) fn simulation_matrix(cvd: CvdType, severity: f32) -> Matrix3x3 {
+    let full = match cvd {
+        CvdType::Protanopia => PROTANOPIA_M,
+        CvdType::Deuteranopia => DEUTERANOPIA_M,
+        CvdType::Tritanopia => TRITANOPIA_M,
+        CvdType::Achromatopsia => ACHROMATOPSIA_M,
+    };
+    let severity = clamp_01(severity);
+
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            *value = identity + (full[i][j] - identity) * severity;
+        }
+    }
+    m
+}
+
+/// Converts an sRGB color to linear-light RGB in [0, 1]
+pub(crate) fn to_linear_rgb(color: &BigColor) -> Vector3 {
+    let rgb = color.to_rgb();
+    [
+        srgb_to_linear(rgb.r as f32 / 255.0),
+        srgb_to_linear(rgb.g as f32 / 255.0),
+        srgb_to_linear(rgb.b as f32 / 255.0),
+    ]
+}
+
+/// Converts linear-light RGB back to a BigColor, clipping to the sRGB gamut
+pub(crate) fn from_linear_rgb(linear: Vector3, alpha: f32) -> BigColor {
+    let to_u8 = |v: f32| (linear_to_srgb(clamp_01(v)) * 255.0).round() as u8;
+    BigColor::from_rgb(to_u8(linear[0]), to_u8(linear[1]), to_u8(linear[2]), alpha)
+}
+
+/// Simulates how a color is perceived with the given deficiency
+///
+/// `severity` ranges from 0.0 (normal vision) to 1.0 (full dichromacy)
+/// and defaults to 1.0
+pub fn simulate_cvd(color: &BigColor, cvd: CvdType, severity: Option<f32>) -> BigColor {
+    let m = simulation_matrix(cvd, severity.unwrap_or(1.0));
+    let simulated = multiply_v3_m3x3(to_linear_rgb(color), m);
+    from_linear_rgb(simulated, color.get_alpha())
+}
+
+/// Error redistribution matrices: the information a viewer cannot see is
+/// shifted into the channels they still perceive
+const SHIFT_RED_GREEN_M: Matrix3x3 = [
+    [0.0, 0.0, 0.0],
+    [0.7, 1.0, 0.0],
+    [0.7, 0.0, 1.0],
+];
+
+const SHIFT_BLUE_YELLOW_M: Matrix3x3 = [
+    [1.0, 0.0, 0.7],
+    [0.0, 1.0, 0.7],
+    [0.0, 0.0, 0.0],
+];
+
+/// Returns the linear-light RGB daltonization matrix for a deficiency
+///
+/// Daltonizing is linear: `c + S * (c - M * c)` collapses to `(I + S * (I - M)) * c`,
+/// where `M` simulates the deficiency and `S` redistributes the lost signal.
+fn daltonize_matrix(cvd: CvdType, strength: f32) -> Matrix3x3 {
+    let shift = match cvd {
+        CvdType::Protanopia | CvdType::Deuteranopia => SHIFT_RED_GREEN_M,
+        CvdType::Tritanopia => SHIFT_BLUE_YELLOW_M,
+        // Nothing can be recovered without any color perception
+        CvdType::Achromatopsia => [[0.0; 3]; 3],
+    };
+    let sim = simulation_matrix(cvd, 1.0);
+
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            let lost: f32 = (0..3)
+                .map(|k| {
+                    let identity = if k == j { 1.0 } else { 0.0 };
+                    shift[i][k] * (identity - sim[k][j])
+                })
+                .sum();
+            let identity = if i == j { 1.0 } else { 0.0 };
+            *value = identity + lost * strength;
+        }
+    }
+    m
+}
+
+/// Corrects a color so viewers with the given deficiency can better tell it
+/// apart from its neighbours
+///
+/// `strength` scales the correction and defaults to 1.0
+pub fn daltonize(color: &BigColor, cvd: CvdType, strength: Option<f32>) -> BigColor {
+    let m = daltonize_matrix(cvd, strength.unwrap_or(1.0));
+    let corrected = multiply_v3_m3x3(to_linear_rgb(color), m);
+    from_linear_rgb(corrected, color.get_alpha())
+}
+
+/// Daltonizes a buffer of RGBA8 pixels in place
+///
+/// The alpha channel is left untouched and a trailing partial pixel is ignored.
+pub fn daltonize_rgba8(pixels: &mut [u8], cvd: CvdType, strength: Option<f32>) {
+    let m = daltonize_matrix(cvd, strength.unwrap_or(1.0));
+    transform_rgba8(pixels, m);
+}
+
+/// Simulates a deficiency over a buffer of RGBA8 pixels in place
+pub fn simulate_cvd_rgba8(pixels: &mut [u8], cvd: CvdType, severity: Option<f32>) {
+    let m = simulation_matrix(cvd, severity.unwrap_or(1.0));
+    transform_rgba8(pixels, m);
+}
+
+/// Applies a linear-light RGB matrix to every pixel of an RGBA8 buffer
+fn transform_rgba8(pixels: &mut [u8], m: Matrix3x3) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let linear = [
+            srgb8_to_linear(pixel[0]),
+            srgb8_to_linear(pixel[1]),
+            srgb8_to_linear(pixel[2]),
+        ];
+        let out = multiply_v3_m3x3(linear, m);
+        pixel[0] = linear_to_srgb8(out[0]);
+        pixel[1] = linear_to_srgb8(out[1]);
+        pixel[2] = linear_to_srgb8(out[2]);
+    }
+}
+
+/// Arguments for the colorblind-safety palette check
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct CvdCheckArgs {
+    /// Minimum ΔEOK two colors must keep under every simulation
+    pub threshold: f32,
+    /// Simulation severity, from 0.0 to 1.0
+    pub severity: f32,
+}
+
+impl Default for CvdCheckArgs {
+    fn default() -> Self {
+        CvdCheckArgs {
+            threshold: 0.1,
+            severity: 1.0,
+        }
+    }
+}
+
+/// Perceptual distances between two palette entries
+#[derive(Debug, Clone)]
+pub struct CvdPairReport {
+    /// Index of the first color in the palette
+    pub first: usize,
+    /// Index of the second color in the palette
+    pub second: usize,
+    /// ΔEOK with normal color vision
+    pub normal: f32,
+    /// ΔEOK under each simulated deficiency
+    pub distances: Vec<(CvdType, f32)>,
+    /// Deficiencies under which the pair falls below the threshold
+    pub conflicts: Vec<CvdType>,
+}
+
+impl CvdPairReport {
+    /// Returns the distance under the given deficiency
+    pub fn distance(&self, cvd: CvdType) -> f32 {
+        self.distances
+            .iter()
+            .find(|(kind, _)| *kind == cvd)
+            .map_or(self.normal, |(_, distance)| *distance)
+    }
+
+    /// Returns the smallest distance across every simulation
+    pub fn min_distance(&self) -> f32 {
+        self.distances
+            .iter()
+            .map(|(_, distance)| *distance)
+            .fold(self.normal, f32::min)
+    }
+
+    /// Returns true if the pair stays distinguishable under every simulation
+    pub fn is_safe(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Result of checking a palette for colorblind safety
+#[derive(Debug, Clone)]
+pub struct CvdPaletteReport {
+    /// Threshold the palette was checked against
+    pub threshold: f32,
+    /// One report per unordered pair of colors
+    pub pairs: Vec<CvdPairReport>,
+}
+
+impl CvdPaletteReport {
+    /// Returns the pairs that collapse under at least one simulation
+    pub fn conflicts(&self) -> impl Iterator<Item = &CvdPairReport> {
+        self.pairs.iter().filter(|pair| !pair.is_safe())
+    }
+
+    /// Returns true if every pair stays distinguishable
+    pub fn is_safe(&self) -> bool {
+        self.pairs.iter().all(CvdPairReport::is_safe)
+    }
+}
+
+/// Checks every pair of colors in a palette for distinguishability under
+/// each CVD simulation
+pub fn check_palette(colors: &[BigColor], args: Option<CvdCheckArgs>) -> CvdPaletteReport {
+    let args = args.unwrap_or_default();
+
+    // Simulate each color once per deficiency
+    let simulated: Vec<Vec<OKLab>> = colors
+        .iter()
+        .map(|color| {
+            CvdType::ALL
+                .iter()
+                .map(|&cvd| simulate_cvd(color, cvd, Some(args.severity)).to_oklab())
+                .collect()
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for i in 0..colors.len() {
+        for j in (i + 1)..colors.len() {
+            let normal = delta_e_ok(colors[i].to_oklab(), colors[j].to_oklab());
+            let distances: Vec<(CvdType, f32)> = CvdType::ALL
+                .iter()
+                .enumerate()
+                .map(|(k, &cvd)| (cvd, delta_e_ok(simulated[i][k], simulated[j][k])))
+                .collect();
+            let conflicts = distances
+                .iter()
+                .filter(|(_, distance)| *distance < args.threshold)
+                .map(|(cvd, _)| *cvd)
+                .collect();
+
+            pairs.push(CvdPairReport {
+                first: i,
+                second: j,
+                normal,
+                distances,
+                conflicts,
+            });
+        }
+    }
+
+    CvdPaletteReport {
+        threshold: args.threshold,
+        pairs,
+    }
+}
+
+/// Proposes a palette whose conflicting pairs are pushed apart in lightness
+///
+/// Hue and chroma are kept; only OKLCH lightness changes. Pairs that cannot be
+/// separated within the lightness range are left at the best found position.
+pub fn separate_palette(colors: &[BigColor], args: Option<CvdCheckArgs>) -> Vec<BigColor> {
+    const STEP: f32 = 0.02;
+    const MAX_ROUNDS: usize = 50;
+
+    let mut result: Vec<BigColor> = colors.to_vec();
+
+    for _ in 0..MAX_ROUNDS {
+        let report = check_palette(&result, args);
+        if report.is_safe() {
+            break;
+        }
+
+        for pair in report.conflicts() {
+            let (lighter, darker) = if result[pair.first].color.l >= result[pair.second].color.l {
+                (pair.first, pair.second)
+            } else {
+                (pair.second, pair.first)
+            };
+            result[lighter].color.l = (result[lighter].color.l + STEP).min(1.0);
+            result[darker].color.l = (result[darker].color.l - STEP).max(0.0);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achromatopsia_is_grey() {
+        let simulated = simulate_cvd(&BigColor::new("#1a6ef5"), CvdType::Achromatopsia, None);
+        let rgb = simulated.to_rgb();
+        assert_eq!(rgb.r, rgb.g);
+        assert_eq!(rgb.g, rgb.b);
+    }
+
+    #[test]
+    fn test_zero_severity_is_identity() {
+        let color = BigColor::new("#1a6ef5");
+        let simulated = simulate_cvd(&color, CvdType::Deuteranopia, Some(0.0));
+        assert_eq!(simulated.to_hex_string(false), color.to_hex_string(false));
+    }
+
+    #[test]
+    fn test_daltonize_buffer_matches_single_color() {
+        let color = BigColor::new("#d62728");
+        let expected = daltonize(&color, CvdType::Deuteranopia, None).to_rgb();
+
+        let mut pixels = [0xd6, 0x27, 0x28, 0x80];
+        daltonize_rgba8(&mut pixels, CvdType::Deuteranopia, None);
+        assert_eq!(pixels, [expected.r, expected.g, expected.b, 0x80]);
+    }
+
+    #[test]
+    fn test_daltonize_separates_confusable_colors() {
+        let (red, green) = (BigColor::new("#d62728"), BigColor::new("#2ca02c"));
+        let seen = |a: &BigColor, b: &BigColor| {
+            let simulate = |c: &BigColor| simulate_cvd(c, CvdType::Deuteranopia, None);
+            simulate(a).delta_e(&simulate(b))
+        };
+        let before = seen(&red, &green);
+        let after = seen(
+            &daltonize(&red, CvdType::Deuteranopia, None),
+            &daltonize(&green, CvdType::Deuteranopia, None),
+        );
+        assert!(before < 0.05, "{}", before);
+        assert!(after > before * 2.0, "{} -> {}", before, after);
+    }
+
+    #[test]
+    fn test_red_green_conflict() {
+        let palette = [BigColor::new("#d62728"), BigColor::new("#2ca02c")];
+        let report = check_palette(&palette, None);
+        assert!(!report.is_safe());
+        assert!(report.pairs[0].conflicts.contains(&CvdType::Deuteranopia));
+
+        let separated = separate_palette(&palette, None);
+        let report = check_palette(&separated, None);
+        assert!(report.pairs[0].min_distance() > check_palette(&palette, None).pairs[0].min_distance());
+    }
+}
@@ -0,0 +1,141 @@
+// Dark-mode counterpart generation in OKLCH
+
+use alloc::vec::Vec;
+use crate::color_space::*;
+use crate::BigColor;
+
+/// Options for mapping a light-theme color to its dark-theme equivalent
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct DarkModeOptions {
+    /// OKLCH lightness that stays fixed when lightness is inverted (0.0 to 1.0)
+    pub pivot: f32,
+    /// Chroma multiplier applied after inversion. Colors on dark surfaces look
+    /// more saturated, so values slightly below 1.0 keep them balanced.
+    pub chroma_scale: f32,
+    /// Surface the color sits on in the light theme
+    pub light_surface: BigColor,
+    /// Surface the color will sit on in the dark theme
+    pub dark_surface: BigColor,
+    /// Keep at least the contrast ratio the color had against the light surface
+    pub preserve_contrast: bool,
+}
+
+impl Default for DarkModeOptions {
+    fn default() -> Self {
+        DarkModeOptions {
+            pivot: 0.5,
+            chroma_scale: 0.9,
+            light_surface: BigColor::new("#ffffff"),
+            dark_surface: BigColor::new("#121212"),
+            preserve_contrast: true,
+        }
+    }
+}
+
+/// Inverts lightness around the pivot so that 0 maps to 1, 1 maps to 0
+/// and the pivot maps to itself
+fn invert_lightness(l: f32, pivot: f32) -> f32 {
+    let pivot = pivot.clamp(0.01, 0.99);
+    let l = clamp_01(l);
+    if l <= pivot {
+        1.0 - l * (1.0 - pivot) / pivot
+    } else {
+        pivot - (l - pivot) * pivot / (1.0 - pivot)
+    }
+}
+
+/// Builds a color at the given lightness, keeping hue and fitting chroma into sRGB
+fn with_lightness(oklch: OKLCH, l: f32) -> BigColor {
+    let mapped = gamut_map_oklch(OKLCH { l, ..oklch });
+    BigColor::from_oklch(mapped.l, mapped.c, mapped.h, mapped.alpha)
+}
+
+/// Maps a light-theme color to a dark-theme equivalent
+///
+/// Lightness is inverted around `pivot`, hue is kept and chroma is scaled and
+/// fitted back into sRGB. With `preserve_contrast`, the result is pushed away
+/// from the dark surface until it reaches the contrast ratio the original had
+/// against the light surface, as far as the lightness range allows.
+pub fn to_dark_mode(color: &BigColor, options: Option<DarkModeOptions>) -> BigColor {
+    let options = options.unwrap_or_default();
+    let source = color.to_oklch();
+
+    let target = OKLCH {
+        l: invert_lightness(source.l, options.pivot),
+        c: source.c * options.chroma_scale.max(0.0),
+        h: source.h,
+        alpha: source.alpha,
+    };
+    let mut result = with_lightness(target, target.l);
+
+    if !options.preserve_contrast {
+        return result;
+    }
+
+    let wanted = color.get_contrast_ratio(&options.light_surface);
+    if result.get_contrast_ratio(&options.dark_surface) >= wanted {
+        return result;
+    }
+
+    // Move away from the dark surface: lighter if the surface is dark, darker otherwise
+    let surface_l = options.dark_surface.to_oklch().l;
+    let (mut near, mut far) = if target.l >= surface_l {
+        (target.l, 1.0)
+    } else {
+        (target.l, 0.0)
+    };
+
+    let extreme = with_lightness(target, far);
+    if extreme.get_contrast_ratio(&options.dark_surface) < wanted {
+        return extreme;
+    }
+    result = extreme;
+
+    for _ in 0..20 {
+        let mid = (near + far) / 2.0;
+        let candidate = with_lightness(target, mid);
+        if candidate.get_contrast_ratio(&options.dark_surface) >= wanted {
+            far = mid;
+            result = candidate;
+        } else {
+            near = mid;
+        }
+    }
+
+    result
+}
+
+/// Maps every color of a light-theme palette to its dark-theme equivalent
+pub fn to_dark_mode_palette(colors: &[BigColor], options: Option<DarkModeOptions>) -> Vec<BigColor> {
+    let options = options.unwrap_or_default();
+    colors
+        .iter()
+        .map(|color| to_dark_mode(color, Some(options.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverts_lightness_and_keeps_hue() {
+        let color = BigColor::new("#333333");
+        let dark = color.to_dark_mode(None);
+        assert!(dark.to_oklch().l > 0.8);
+
+        let blue = BigColor::new("#1a6ef5");
+        let dark_blue = blue.to_dark_mode(None);
+        assert!((dark_blue.to_oklch().h - blue.to_oklch().h).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_preserves_contrast_against_surface() {
+        let options = DarkModeOptions::default();
+        let color = BigColor::new("#d62728");
+        let wanted = color.get_contrast_ratio(&options.light_surface);
+        let dark = to_dark_mode(&color, Some(options.clone()));
+        assert!(dark.get_contrast_ratio(&options.dark_surface) >= wanted);
+    }
+}
@@ -0,0 +1,373 @@
+// Stylesheet and config emitters for palettes and themes
+
+use crate::swatches::Palette;
+use crate::theme::{Theme, ThemeColors};
+use crate::{BigColor, ColorFormat};
+
+/// Options shared by every emitter
+#[derive(Debug, Clone)]
+pub struct EmitOptions {
+    /// Format of each color value
+    pub format: ColorFormat,
+    /// Maximum number of decimals in OKLCH, OKLab, Lab, LCH and HSL values
+    pub precision: usize,
+    /// Prepended to every name, so `500` with prefix `brand` becomes `brand-500`
+    pub prefix: Option<String>,
+    /// Also emit hex values for browsers without support for the chosen format,
+    /// with the chosen format inside `@supports`. Only used by [`css_variables`].
+    pub fallback: bool,
+}
+
+impl Default for EmitOptions {
+    fn default() -> Self {
+        EmitOptions {
+            format: ColorFormat::OKLCH,
+            precision: 3,
+            prefix: None,
+            fallback: false,
+        }
+    }
+}
+
+/// Writes a number with at most `precision` decimals and no trailing zeros
+fn number(value: f32, precision: usize) -> String {
+    let fixed = format!("{:.*}", precision, value);
+    let trimmed = if fixed.contains('.') {
+        fixed.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &fixed
+    };
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Writes a color in the given format, rounding channels of the
+/// floating-point formats to `precision` decimals
+pub fn format_color(color: &BigColor, format: ColorFormat, precision: usize) -> String {
+    let n = |value: f32| number(value, precision);
+    let alpha = color.get_alpha();
+    let slash_alpha = if alpha < 1.0 { format!(" / {}", n(alpha)) } else { String::new() };
+
+    match format {
+        ColorFormat::OKLCH => {
+            let oklch = color.to_oklch();
+            format!("oklch({}% {} {}{})", n(oklch.l * 100.0), n(oklch.c), n(oklch.h), slash_alpha)
+        }
+        ColorFormat::OKLAB => {
+            let oklab = color.to_oklab();
+            format!("oklab({}% {} {}{})", n(oklab.l * 100.0), n(oklab.a), n(oklab.b), slash_alpha)
+        }
+        ColorFormat::LAB => {
+            let lab = color.to_lab();
+            format!("lab({} {} {}{})", n(lab.l), n(lab.a), n(lab.b), slash_alpha)
+        }
+        ColorFormat::LCH => {
+            let lch = color.to_lch();
+            format!("lch({} {} {}{})", n(lch.l), n(lch.c), n(lch.h), slash_alpha)
+        }
+        ColorFormat::HSL => {
+            let hsl = color.to_hsl();
+            format!("hsl({} {}% {}%{})", n(hsl.h), n(hsl.s * 100.0), n(hsl.l * 100.0), slash_alpha)
+        }
+        other => color.to(other),
+    }
+}
+
+/// Returns true for formats that older browsers do not understand
+fn needs_fallback(format: ColorFormat) -> bool {
+    matches!(format, ColorFormat::OKLCH | ColorFormat::OKLAB | ColorFormat::LAB | ColorFormat::LCH)
+}
+
+/// Turns a free-form name into a kebab-case identifier
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Returns every swatch of a palette with a kebab-case name. Grouped swatches
+/// are prefixed with their group, and unnamed ones are numbered.
+pub fn palette_tokens(palette: &Palette) -> Vec<(String, BigColor)> {
+    let mut tokens = Vec::new();
+    let mut name_swatches = |group: Option<&str>, swatches: &[crate::swatches::Swatch]| {
+        for (index, swatch) in swatches.iter().enumerate() {
+            let name = swatch
+                .name
+                .as_deref()
+                .map(slug)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("color-{}", index + 1));
+            let name = match group {
+                Some(group) => format!("{}-{}", slug(group), name),
+                None => name,
+            };
+            tokens.push((name, swatch.color.clone()));
+        }
+    };
+
+    name_swatches(None, &palette.swatches);
+    for group in &palette.groups {
+        name_swatches(Some(&group.name), &group.swatches);
+    }
+    tokens
+}
+
+/// Returns the tokens of a theme variant with their kebab-case names
+pub fn theme_tokens(colors: &ThemeColors) -> Vec<(String, BigColor)> {
+    colors
+        .tokens()
+        .into_iter()
+        .map(|(name, color)| (name.to_string(), color.clone()))
+        .collect()
+}
+
+/// Applies the prefix option to a name
+fn prefixed(name: &str, options: &EmitOptions) -> String {
+    match &options.prefix {
+        Some(prefix) => format!("{}-{}", prefix, name),
+        None => name.to_string(),
+    }
+}
+
+/// Writes a `:root` block of custom property declarations
+fn root_block(tokens: &[(String, BigColor)], options: &EmitOptions, format: ColorFormat, indent: &str) -> String {
+    let mut out = format!("{}:root {{\n", indent);
+    for (name, color) in tokens {
+        out.push_str(&format!(
+            "{}  --{}: {};\n",
+            indent,
+            prefixed(name, options),
+            format_color(color, format, options.precision)
+        ));
+    }
+    out.push_str(&format!("{}}}\n", indent));
+    out
+}
+
+/// Wraps lines in an at-rule, indenting them one level
+fn at_rule(rule: &str, body: &str) -> String {
+    let mut out = format!("{} {{\n", rule);
+    for line in body.lines() {
+        out.push_str(&format!("  {}\n", line).replace("  \n", "\n"));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Emits CSS custom properties, with dark values in a
+/// `@media (prefers-color-scheme: dark)` block when given
+///
+/// With `options.fallback` and a format older browsers lack, hex values are
+/// declared first and the chosen format is applied inside
+/// `@supports (color: oklch(0 0 0))`.
+pub fn css_variables(
+    light: &[(String, BigColor)],
+    dark: Option<&[(String, BigColor)]>,
+    options: Option<EmitOptions>,
+) -> String {
+    let options = options.unwrap_or_default();
+    let block = |format: ColorFormat| {
+        let mut css = root_block(light, &options, format, "");
+        if let Some(dark) = dark {
+            css.push('\n');
+            css.push_str(&at_rule("@media (prefers-color-scheme: dark)", &root_block(dark, &options, format, "")));
+        }
+        css
+    };
+
+    if !(options.fallback && needs_fallback(options.format)) {
+        return block(options.format);
+    }
+
+    let supports = match options.format {
+        ColorFormat::OKLAB => "@supports (color: oklab(0 0 0))",
+        ColorFormat::LAB => "@supports (color: lab(0 0 0))",
+        ColorFormat::LCH => "@supports (color: lch(0 0 0))",
+        _ => "@supports (color: oklch(0 0 0))",
+    };
+    format!("{}\n{}", block(ColorFormat::HEX), at_rule(supports, &block(options.format)))
+}
+
+/// Emits light and dark custom properties for a generated theme
+pub fn theme_css_variables(theme: &Theme, options: Option<EmitOptions>) -> String {
+    css_variables(&theme_tokens(&theme.light), Some(&theme_tokens(&theme.dark)), options)
+}
+
+/// Emits an SCSS map, `$name: ("brand-500": oklch(...), ...);`
+pub fn scss_map(map_name: &str, tokens: &[(String, BigColor)], options: Option<EmitOptions>) -> String {
+    let options = options.unwrap_or_default();
+    let mut out = format!("${}: (\n", slug(map_name));
+    for (name, color) in tokens {
+        out.push_str(&format!(
+            "  \"{}\": {},\n",
+            prefixed(name, &options),
+            format_color(color, options.format, options.precision)
+        ));
+    }
+    out.push_str(");\n");
+    out
+}
+
+/// Writes a key for a JavaScript object literal, quoting it if needed
+fn js_key(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    let is_index = !key.is_empty() && key.chars().all(|c| c.is_ascii_digit());
+    if is_identifier || is_index {
+        key.to_string()
+    } else {
+        format!("'{}'", key.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Converts a kebab-case name to camelCase
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    for (index, part) in name.split('-').filter(|part| !part.is_empty()).enumerate() {
+        let mut chars = part.chars();
+        if index == 0 {
+            out.push_str(part);
+        } else if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+/// Emits a Tailwind config with the tokens as `theme.colors`
+///
+/// Names ending in a numeric shade, such as `brand-500`, are nested as
+/// `brand: { 500: ... }` so utilities read `bg-brand-500`.
+pub fn tailwind_config(tokens: &[(String, BigColor)], typescript: bool, options: Option<EmitOptions>) -> String {
+    let options = options.unwrap_or_default();
+
+    // Group shades under their base name, keeping first-seen order
+    let mut entries: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for (name, color) in tokens {
+        let name = prefixed(name, &options);
+        let value = format_color(color, options.format, options.precision);
+        let (base, shade) = match name.rsplit_once('-') {
+            Some((base, shade)) if !shade.is_empty() && shade.chars().all(|c| c.is_ascii_digit()) => {
+                (base.to_string(), shade.to_string())
+            }
+            _ => (name.clone(), String::new()),
+        };
+        match entries.iter_mut().find(|(existing, _)| *existing == base) {
+            Some((_, shades)) => shades.push((shade, value)),
+            None => entries.push((base, vec![(shade, value)])),
+        }
+    }
+
+    let mut colors = String::new();
+    for (base, shades) in entries {
+        match shades.as_slice() {
+            [(shade, value)] if shade.is_empty() => {
+                colors.push_str(&format!("      {}: '{}',\n", js_key(&base), value));
+            }
+            _ => {
+                colors.push_str(&format!("      {}: {{\n", js_key(&base)));
+                for (shade, value) in &shades {
+                    let key = if shade.is_empty() { "DEFAULT" } else { shade };
+                    colors.push_str(&format!("        {}: '{}',\n", js_key(key), value));
+                }
+                colors.push_str("      },\n");
+            }
+        }
+    }
+
+    let body = format!("{{\n  theme: {{\n    colors: {{\n{}    }},\n  }},\n}}", colors);
+    if typescript {
+        format!("import type {{ Config }} from 'tailwindcss';\n\nexport default {} satisfies Config;\n", body)
+    } else {
+        format!("/** @type {{import('tailwindcss').Config}} */\nmodule.exports = {};\n", body)
+    }
+}
+
+/// Emits a CSS-in-JS object, `export const colors = { brand500: '...' };`,
+/// with camelCase keys. TypeScript output is marked `as const`.
+pub fn css_in_js(const_name: &str, tokens: &[(String, BigColor)], typescript: bool, options: Option<EmitOptions>) -> String {
+    let options = options.unwrap_or_default();
+    let mut out = format!("export const {} = {{\n", camel_case(&slug(const_name)));
+    for (name, color) in tokens {
+        out.push_str(&format!(
+            "  {}: '{}',\n",
+            js_key(&camel_case(&prefixed(name, &options))),
+            format_color(color, options.format, options.precision)
+        ));
+    }
+    out.push_str(if typescript { "} as const;\n" } else { "};\n" });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens() -> Vec<(String, BigColor)> {
+        vec![
+            ("brand-500".to_string(), BigColor::new("#1a6ef5")),
+            ("brand-600".to_string(), BigColor::new("#1559c7")),
+            ("on-primary".to_string(), BigColor::new("#ffffff")),
+        ]
+    }
+
+    #[test]
+    fn test_css_variables_with_dark_scheme_and_fallback() {
+        let options = EmitOptions { fallback: true, precision: 2, ..Default::default() };
+        let dark = vec![("brand-500".to_string(), BigColor::new("#5b9bff"))];
+        let css = css_variables(&tokens(), Some(&dark), Some(options));
+
+        assert!(css.starts_with(":root {\n  --brand-500: #1a6ef5;\n"));
+        assert!(css.contains("@media (prefers-color-scheme: dark) {\n  :root {\n    --brand-500: #5b9bff;"));
+        assert!(css.contains("@supports (color: oklch(0 0 0)) {\n  :root {\n    --brand-500: oklch("));
+        let precise = format_color(&BigColor::new("#1a6ef5"), ColorFormat::OKLCH, 2);
+        assert!(css.contains(&precise));
+        assert_eq!(precise.matches('.').count(), 3);
+    }
+
+    #[test]
+    fn test_scss_tailwind_and_css_in_js() {
+        let options = Some(EmitOptions { format: ColorFormat::HEX, ..Default::default() });
+        assert_eq!(
+            scss_map("colors", &tokens(), options.clone()),
+            "$colors: (\n  \"brand-500\": #1a6ef5,\n  \"brand-600\": #1559c7,\n  \"on-primary\": #ffffff,\n);\n"
+        );
+
+        let tailwind = tailwind_config(&tokens(), true, options.clone());
+        assert!(tailwind.contains("      brand: {\n        500: '#1a6ef5',\n        600: '#1559c7',\n      },\n"));
+        assert!(tailwind.contains("      'on-primary': '#ffffff',\n"));
+        assert!(tailwind.ends_with("} satisfies Config;\n"));
+
+        let js = css_in_js("theme colors", &tokens(), false, options);
+        assert!(js.starts_with("export const themeColors = {\n  brand500: '#1a6ef5',"));
+        assert!(js.contains("  onPrimary: '#ffffff',\n"));
+    }
+
+    #[test]
+    fn test_palette_names_become_slugs() {
+        use crate::swatches::{Swatch, SwatchGroup};
+        let palette = Palette {
+            name: None,
+            swatches: vec![Swatch::new(Some("Deep Blue!"), BigColor::new("#1a6ef5")), Swatch::new(None, BigColor::new("#000"))],
+            groups: vec![SwatchGroup {
+                name: "Status".to_string(),
+                swatches: vec![Swatch::new(Some("Error"), BigColor::new("#f00"))],
+            }],
+        };
+        let names: Vec<String> = palette_tokens(&palette).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["deep-blue", "color-2", "status-error"]);
+    }
+}
@@ -2,24 +2,72 @@
 // Original: https://github.com/bgrins/TinyColor
 // Brian Grinstead, MIT License
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 mod matrix_utils;
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+pub mod batch;
+pub mod color;
 pub mod color_space;
 mod parse;
+#[cfg(feature = "std")]
 pub mod conversion;
 pub mod accessibility;
-
-use std::fmt;
+pub mod cvd;
+pub mod dark_mode;
+pub mod theme;
+#[cfg(feature = "std")]
+pub mod scan;
+#[cfg(feature = "std")]
+pub mod code;
+#[cfg(feature = "std")]
+pub mod swatches;
+#[cfg(feature = "std")]
+pub mod emit;
+#[cfg(feature = "std")]
+pub mod android;
+#[cfg(feature = "std")]
+pub mod terminal;
+#[cfg(feature = "std")]
+pub mod terminal_theme;
+#[cfg(feature = "serde")]
+pub mod color_serde;
+#[cfg(feature = "dtcg")]
+pub mod tokens;
+#[cfg(feature = "xcode")]
+pub mod xcode;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+use core::fmt;
 use color_space::*;
 use parse::*;
-use crate::accessibility::{get_contrast_color as get_contrast_color_impl, get_contrast_ratio as get_contrast_ratio_impl};
+use crate::accessibility::{get_apca_contrast as get_apca_contrast_impl, get_contrast_color as get_contrast_color_impl, get_contrast_ratio as get_contrast_ratio_impl};
+use crate::dark_mode::{to_dark_mode as to_dark_mode_impl, DarkModeOptions};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+#[cfg(feature = "std")]
+use crate::code::{parse_code_literal, to_code as to_code_impl, ByteOrder, CodeFormat, CodeOptions};
+#[cfg(feature = "std")]
+use crate::terminal::{nearest_ansi256, parse_ansi_sequence, truecolor_sequence, Layer};
+use crate::cvd::{daltonize as daltonize_impl, simulate_cvd as simulate_cvd_impl, CvdType};
+#[cfg(feature = "std")]
 pub use peniko;
+pub use color::Color;
+
+/// The version of this crate, as published
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// BigColor struct represents a color with various formats
 /// Using OKLCH as the foundation
 #[derive(Debug, Clone)]
 pub struct BigColor {
     // OKLCH values serve as the foundation
-    oklch: OKLCH,
+    color: Color,
     original_input: String,
     format: ColorFormat,
     ok: bool,
@@ -27,6 +75,7 @@ pub struct BigColor {
 
 /// Format types for color representation
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColorFormat {
     RGB,
     PRGB,
@@ -46,24 +95,37 @@ pub enum ColorFormat {
     INVALID,
 }
 
+/// The smallest common RGB gamut that holds a color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gamut {
+    Srgb,
+    DisplayP3,
+    /// Outside Display P3 too
+    Wide,
+}
+
 impl PartialEq for BigColor {
     fn eq(&self, other: &Self) -> bool {
-        self.oklch.l == other.oklch.l &&
-        self.oklch.c == other.oklch.c &&
-        self.oklch.h == other.oklch.h &&
-        self.oklch.alpha == other.oklch.alpha
+        self.color == other.color
+    }
+}
+
+impl From<Color> for BigColor {
+    fn from(color: Color) -> Self {
+        BigColor::from_color(color)
+    }
+}
+
+impl From<&BigColor> for Color {
+    fn from(color: &BigColor) -> Self {
+        color.color
     }
 }
 
 impl Default for BigColor {
     fn default() -> Self {
         BigColor {
-            oklch: OKLCH {
-                l: 0.0,
-                c: 0.0,
-                h: 0.0,
-                alpha: 1.0,
-            },
+            color: Color::default(),
             original_input: String::new(),
             format: ColorFormat::INVALID,
             ok: false,
@@ -89,10 +151,10 @@ impl BigColor {
         }
         
         // Convert RGB to OKLCH to set our foundation
-        let oklch = rgb_to_oklch(rgb.r, rgb.g, rgb.b, rgb.a);
+        let color = rgb_to_oklch(rgb.r, rgb.g, rgb.b, rgb.a).into();
         
         BigColor {
-            oklch,
+            color,
             original_input: color_str,
             format: rgb.format,
             ok: rgb.ok,
@@ -111,14 +173,19 @@ impl BigColor {
 
     /// Returns true if the color is dark
     pub fn is_dark(&self) -> bool {
-        // With OKLCH, we can use L directly to determine darkness
-        // Values less than 0.5 are generally considered dark
-        self.oklch.l < 0.5
+        self.color.is_dark()
     }
 
     /// Returns true if the color is light
     pub fn is_light(&self) -> bool {
-        !self.is_dark()
+        self.color.is_light()
+    }
+
+    /// Maps a light-theme color to its dark-theme equivalent.
+    /// Lightness is inverted in OKLCH around a pivot while hue is kept,
+    /// see [`DarkModeOptions`] for the available controls.
+    pub fn to_dark_mode(&self, options: Option<DarkModeOptions>) -> BigColor {
+        to_dark_mode_impl(self, options)
     }
 
     /// Returns true if the color is valid
@@ -138,61 +205,28 @@ impl BigColor {
 
     /// Returns the alpha value
     pub fn get_alpha(&self) -> f32 {
-        self.oklch.alpha
+        self.color.alpha
     }
 
     /// Returns the brightness value
     pub fn get_brightness(&self) -> f32 {
-        // In OKLCH, we can use L directly but normalize to 0-255 range
-        self.oklch.l * 255.0
+        self.color.brightness()
     }
 
     /// Returns the luminance value
     pub fn get_luminance(&self) -> f32 {
-        // Convert to sRGB and calculate standard luminance
-        let rgb = self.to_rgb();
-        let r_srgb = rgb.r as f32 / 255.0;
-        let g_srgb = rgb.g as f32 / 255.0;
-        let b_srgb = rgb.b as f32 / 255.0;
-
-        let r = if r_srgb <= 0.03928 {
-            r_srgb / 12.92
-        } else {
-            ((r_srgb + 0.055) / 1.055).powf(2.4)
-        };
-        
-        let g = if g_srgb <= 0.03928 {
-            g_srgb / 12.92
-        } else {
-            ((g_srgb + 0.055) / 1.055).powf(2.4)
-        };
-        
-        let b = if b_srgb <= 0.03928 {
-            b_srgb / 12.92
-        } else {
-            ((b_srgb + 0.055) / 1.055).powf(2.4)
-        };
-
-        0.2126 * r + 0.7152 * g + 0.0722 * b
+        self.color.luminance()
     }
 
     /// Sets the alpha value
     pub fn set_alpha(&mut self, value: f32) -> &mut Self {
-        self.oklch.alpha = bound_alpha(value);
+        self.color = self.color.with_alpha(value);
         self
     }
 
     /// Converts the color to HSV
     pub fn to_hsv(&self) -> HSV {
-        // Convert to RGB first, then to HSV
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        let hsv = rgb_to_hsv(r, g, b);
-        HSV {
-            h: hsv.h * 360.0,
-            s: hsv.s,
-            v: hsv.v,
-            a: self.oklch.alpha,
-        }
+        self.color.to_hsv()
     }
 
     /// Converts the color to HSV string
@@ -202,49 +236,16 @@ impl BigColor {
         let s = (hsv.s * 100.0).round() as i32;
         let v = (hsv.v * 100.0).round() as i32;
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("hsv({}, {}%, {}%)", h, s, v)
         } else {
-            format!("hsva({}, {}%, {}%, {})", h, s, v, (self.oklch.alpha * 100.0).round() / 100.0)
+            format!("hsva({}, {}%, {}%, {})", h, s, v, (self.color.alpha * 100.0).round() / 100.0)
         }
     }
 
     /// Converts the color to HSL
     pub fn to_hsl(&self) -> HSL {
-        // Convert to RGB first, then manually calculate HSL
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        
-        let r_norm = r as f32 / 255.0;
-        let g_norm = g as f32 / 255.0;
-        let b_norm = b as f32 / 255.0;
-        
-        let max = r_norm.max(g_norm).max(b_norm);
-        let min = r_norm.min(g_norm).min(b_norm);
-        let mut h = 0.0;
-        let mut s = 0.0;
-        let l = (max + min) / 2.0;
-        
-        if max != min {
-            let d = max - min;
-            s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
-            
-            if max == r_norm {
-                h = (g_norm - b_norm) / d + (if g_norm < b_norm { 6.0 } else { 0.0 });
-            } else if max == g_norm {
-                h = (b_norm - r_norm) / d + 2.0;
-            } else if max == b_norm {
-                h = (r_norm - g_norm) / d + 4.0;
-            }
-            
-            h /= 6.0;
-        }
-        
-        HSL {
-            h: h * 360.0,
-            s,
-            l,
-            a: self.oklch.alpha,
-        }
+        self.color.to_hsl()
     }
 
     /// Converts the color to HSL string
@@ -254,34 +255,34 @@ impl BigColor {
         let s = (hsl.s * 100.0).round() as i32;
         let l = (hsl.l * 100.0).round() as i32;
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("hsl({}, {}%, {}%)", h, s, l)
         } else {
-            format!("hsla({}, {}%, {}%, {})", h, s, l, (self.oklch.alpha * 100.0).round() / 100.0)
+            format!("hsla({}, {}%, {}%, {})", h, s, l, (self.color.alpha * 100.0).round() / 100.0)
         }
     }
 
     /// Converts the color to OKLCH
     pub fn to_oklch(&self) -> OKLCH {
-        self.oklch
+        self.color.to_oklch()
     }
 
     /// Converts the color to OKLCH string
     pub fn to_oklch_string(&self) -> String {
-        let l = (self.oklch.l * 100.0).round() / 100.0;
-        let c = (self.oklch.c * 100.0).round() / 100.0;
-        let h = self.oklch.h.round();
+        let l = (self.color.l * 100.0).round() / 100.0;
+        let c = (self.color.c * 100.0).round() / 100.0;
+        let h = self.color.h.round();
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("oklch({}% {} {})", l * 100.0, c, h)
         } else {
-            format!("oklch({}% {} {} / {})", l * 100.0, c, h, self.oklch.alpha)
+            format!("oklch({}% {} {} / {})", l * 100.0, c, h, self.color.alpha)
         }
     }
 
     /// Converts the color to OKLab
     pub fn to_oklab(&self) -> OKLab {
-        oklch_to_oklab(self.oklch)
+        self.color.to_oklab()
     }
 
     /// Converts the color to OKLab string
@@ -291,17 +292,16 @@ impl BigColor {
         let a = (oklab.a * 100.0).round() / 100.0;
         let b = (oklab.b * 100.0).round() / 100.0;
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("oklab({}% {} {})", l * 100.0, a, b)
         } else {
-            format!("oklab({}% {} {} / {})", l * 100.0, a, b, self.oklch.alpha)
+            format!("oklab({}% {} {} / {})", l * 100.0, a, b, self.color.alpha)
         }
     }
 
     /// Converts the color to LCH
     pub fn to_lch(&self) -> LCH {
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        rgb_to_lch(r, g, b, self.oklch.alpha)
+        self.color.to_lch()
     }
 
     /// Converts the color to LCH string
@@ -311,19 +311,16 @@ impl BigColor {
         let c = lch.c.round();
         let h = lch.h.round();
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("lch({} {} {})", l, c, h)
         } else {
-            format!("lch({} {} {} / {})", l, c, h, self.oklch.alpha)
+            format!("lch({} {} {} / {})", l, c, h, self.color.alpha)
         }
     }
 
     /// Converts the color to Lab
     pub fn to_lab(&self) -> Lab {
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        let xyz_d65 = rgb_to_xyz_d65(r, g, b, self.oklch.alpha);
-        let xyz_d50 = xyz_d65_to_xyz_d50(xyz_d65);
-        xyz_d50_to_lab(xyz_d50)
+        self.color.to_lab()
     }
 
     /// Converts the color to Lab string
@@ -333,17 +330,17 @@ impl BigColor {
         let a = lab.a.round();
         let b = lab.b.round();
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("lab({} {} {})", l, a, b)
         } else {
-            format!("lab({} {} {} / {})", l, a, b, self.oklch.alpha)
+            format!("lab({} {} {} / {})", l, a, b, self.color.alpha)
         }
     }
 
     /// Converts the color to HEX
     pub fn to_hex(&self, allow_3_char: bool) -> String {
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        rgb_to_hex(r, g, b, allow_3_char)
+        let rgb = self.to_rgb();
+        rgb_to_hex(rgb.r, rgb.g, rgb.b, allow_3_char)
     }
 
     /// Converts the color to HEX string
@@ -353,8 +350,8 @@ impl BigColor {
 
     /// Converts the color to HEX8
     pub fn to_hex8(&self, allow_4_char: bool) -> String {
-        let (r, g, b, _) = oklch_to_rgb(self.oklch);
-        rgba_to_hex(r, g, b, self.oklch.alpha, allow_4_char)
+        let rgb = self.to_rgb();
+        rgba_to_hex(rgb.r, rgb.g, rgb.b, self.color.alpha, allow_4_char)
     }
 
     /// Converts the color to HEX8 string
@@ -364,20 +361,14 @@ impl BigColor {
 
     /// Converts the color to RGB
     pub fn to_rgb(&self) -> RGB {
-        let (r, g, b, a) = oklch_to_rgb(self.oklch);
-        RGB {
-            r,
-            g,
-            b,
-            a,
-        }
+        self.color.to_rgb()
     }
 
     /// Converts the color to RGB string
     pub fn to_rgb_string(&self) -> String {
         let rgb = self.to_rgb();
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("rgb({}, {}, {})", rgb.r, rgb.g, rgb.b)
         } else {
             format!("rgba({}, {}, {}, {})", rgb.r, rgb.g, rgb.b, (rgb.a * 100.0).round() / 100.0)
@@ -398,7 +389,7 @@ impl BigColor {
     /// Converts the color to percentage RGB string
     pub fn to_percentage_rgb_string(&self) -> String {
         let prgb = self.to_percentage_rgb();
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("rgb({}%, {}%, {}%)", prgb.r, prgb.g, prgb.b)
         } else {
             format!("rgba({}%, {}%, {}%, {})", prgb.r, prgb.g, prgb.b, (prgb.a * 100.0).round() / 100.0)
@@ -407,22 +398,14 @@ impl BigColor {
 
     /// Converts the color to a name if possible
     pub fn to_name(&self) -> Option<&'static str> {
-        if self.oklch.alpha == 0.0 {
-            Some("transparent")
-        } else if self.oklch.alpha < 1.0 {
-            None
-        } else {
-            let (r, g, b, _) = oklch_to_rgb(self.oklch);
-            let hex = rgb_to_hex(r, g, b, true);
-            hex_names().get(&hex).map(|&name| name)
-        }
+        self.color.to_name()
     }
 
     /// Converts the color to a string format
     pub fn to_string(&self, format: Option<ColorFormat>) -> String {
         let format = format.unwrap_or(self.format);
         
-        let has_alpha = self.oklch.alpha < 1.0 && self.oklch.alpha >= 0.0;
+        let has_alpha = self.color.alpha < 1.0 && self.color.alpha >= 0.0;
         let needs_alpha_format = 
             format == ColorFormat::HEX || 
             format == ColorFormat::HEX6 || 
@@ -433,7 +416,7 @@ impl BigColor {
         if has_alpha && needs_alpha_format {
             // Special case for "transparent", all other non-alpha formats
             // will return rgba when there is transparency
-            if format == ColorFormat::NAME && self.oklch.alpha == 0.0 {
+            if format == ColorFormat::NAME && self.color.alpha == 0.0 {
                 if let Some(name) = self.to_name() {
                     return name.to_string();
                 }
@@ -468,127 +451,79 @@ impl BigColor {
 
     /// Creates a clone of the color
     pub fn clone_color(&self) -> Self {
-        BigColor::new(self.to_string(None))
+        self.clone()
     }
 
     /// Lightens the color
     pub fn lighten(&mut self, amount: Option<f32>) -> &mut Self {
-        let amount = amount.unwrap_or(10.0);
-        // Direct manipulation in OKLCH space
-        self.oklch.l = (self.oklch.l + amount / 100.0).min(1.0).max(0.0);
+        self.color = self.color.lighten(amount.unwrap_or(10.0));
         self
     }
 
     /// Brightens the color
     pub fn brighten(&mut self, amount: Option<f32>) -> &mut Self {
-        let amount = amount.unwrap_or(10.0);
-        // Convert to RGB, apply brightening, then back to OKLCH
-        let (r, g, b, a) = oklch_to_rgb(self.oklch);
-        let r_new = (r as f32 - (255.0 * -(amount / 100.0))).round().max(0.0).min(255.0) as u8;
-        let g_new = (g as f32 - (255.0 * -(amount / 100.0))).round().max(0.0).min(255.0) as u8;
-        let b_new = (b as f32 - (255.0 * -(amount / 100.0))).round().max(0.0).min(255.0) as u8;
-        
-        self.oklch = rgb_to_oklch(r_new, g_new, b_new, a);
+        self.color = self.color.brighten(amount.unwrap_or(10.0));
         self
     }
 
     /// Darkens the color
     pub fn darken(&mut self, amount: Option<f32>) -> &mut Self {
-        let amount = amount.unwrap_or(10.0);
-        // Direct manipulation in OKLCH space
-        self.oklch.l = (self.oklch.l - amount / 100.0).min(1.0).max(0.0);
+        self.color = self.color.darken(amount.unwrap_or(10.0));
         self
     }
 
     /// Desaturates the color
     pub fn desaturate(&mut self, amount: Option<f32>) -> &mut Self {
-        let amount = amount.unwrap_or(10.0);
-        // Direct manipulation in OKLCH space
-        self.oklch.c = (self.oklch.c - amount / 100.0).max(0.0);
+        self.color = self.color.desaturate(amount.unwrap_or(10.0));
         self
     }
 
     /// Saturates the color
     pub fn saturate(&mut self, amount: Option<f32>) -> &mut Self {
-        let amount = amount.unwrap_or(10.0);
-        // Direct manipulation in OKLCH space
-        self.oklch.c = self.oklch.c + amount / 100.0;
+        self.color = self.color.saturate(amount.unwrap_or(10.0));
         self
     }
 
     /// Converts the color to grayscale
     pub fn greyscale(&mut self) -> &mut Self {
-        // Direct manipulation in OKLCH space - set chroma to 0
-        self.oklch.c = 0.0;
+        self.color = self.color.greyscale();
         self
     }
 
     /// Spins the hue of the color
     pub fn spin(&mut self, amount: f32) -> &mut Self {
-        // Direct manipulation in OKLCH space
-        let mut h = (self.oklch.h + amount) % 360.0;
-        if h < 0.0 {
-            h += 360.0;
-        }
-        self.oklch.h = h;
+        self.color = self.color.spin(amount);
         self
     }
 
+    /// Rotates the hue toward a source color's hue while keeping lightness
+    /// and chroma, so the color keeps its identity but sits better next to
+    /// the source. The hue moves by half the difference, at most `max_degrees`.
+    /// Works in OKLCH; achromatic colors and sources are left unchanged.
+    pub fn harmonize(&self, source: &BigColor, max_degrees: f32) -> BigColor {
+        self.with_color(self.color.harmonize(source.color, max_degrees))
+    }
+
     /// Creates analogous colors
     pub fn analogous(&self, results: Option<usize>, slices: Option<usize>) -> Vec<BigColor> {
-        let results = results.unwrap_or(6);
-        let slices = slices.unwrap_or(30);
-        
-        let part = 360.0 / slices as f32;
-        let mut ret = vec![self.clone()];
-        
-        let mut h = (self.oklch.h - ((part * results as f32) / 2.0) + 720.0) % 360.0;
-        for _ in 0..results-1 {
-            h = (h + part) % 360.0;
-            let mut new_color = self.clone();
-            new_color.oklch.h = h;
-            ret.push(new_color);
-        }
-        
-        ret
+        let colors = self.color.analogous(results.unwrap_or(6), slices.unwrap_or(30));
+        colors.map(|color| self.with_color(color)).collect()
     }
 
     /// Creates a complement color
     pub fn complement(&self) -> BigColor {
-        let mut new_color = self.clone();
-        new_color.oklch.h = (new_color.oklch.h + 180.0) % 360.0;
-        new_color
+        self.with_color(self.color.complement())
     }
 
     /// Creates monochromatic colors
     pub fn monochromatic(&self, results: Option<usize>) -> Vec<BigColor> {
-        let results = results.unwrap_or(6);
-        let step = 1.0 / results as f32;
-        
-        let mut ret = Vec::new();
-        let mut l: f32 = 0.0;
-        
-        for _ in 0..results {
-            let mut new_color = self.clone();
-            new_color.oklch.l = l.min(1.0);
-            ret.push(new_color);
-            l += step;
-        }
-        
-        ret
+        let colors = self.color.monochromatic(results.unwrap_or(6));
+        colors.map(|color| self.with_color(color)).collect()
     }
 
     /// Creates split complement colors
     pub fn split_complement(&self) -> Vec<BigColor> {
-        let h = self.oklch.h;
-        
-        let mut color1 = self.clone();
-        color1.oklch.h = (h + 72.0) % 360.0;
-        
-        let mut color2 = self.clone();
-        color2.oklch.h = (h + 216.0) % 360.0;
-        
-        vec![self.clone(), color1, color2]
+        self.color.split_complement().map(|color| self.with_color(color)).to_vec()
     }
 
     /// Creates a triad of colors
@@ -603,20 +538,27 @@ impl BigColor {
 
     /// Creates polyad colors
     pub fn polyad(&self, number: usize) -> Vec<BigColor> {
-        if number == 0 {
-            panic!("Argument to polyad must be a positive number");
-        }
-        
-        let mut result = vec![self.clone()];
-        let step = 360.0 / number as f32;
-        
-        for i in 1..number {
-            let mut new_color = self.clone();
-            new_color.oklch.h = (self.oklch.h + i as f32 * step) % 360.0;
-            result.push(new_color);
+        self.color.polyad(number).map(|color| self.with_color(color)).collect()
+    }
+
+    /// Returns the underlying `Copy` color value
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Creates a valid BigColor from a color value, in OKLCH format
+    pub fn from_color(color: Color) -> Self {
+        BigColor {
+            color,
+            original_input: String::new(),
+            format: ColorFormat::OKLCH,
+            ok: true,
         }
-        
-        result
+    }
+
+    /// A copy of this color, keeping its input and format, with a new value
+    fn with_color(&self, color: Color) -> BigColor {
+        BigColor { color, ..self.clone() }
     }
 
     // Factory methods for creating a BigColor from different formats
@@ -650,14 +592,38 @@ impl BigColor {
     
     /// Creates a BigColor from OKLCH values directly
     pub fn from_oklch(l: f32, c: f32, h: f32, a: f32) -> Self {
-        let oklch = OKLCH { l, c, h, alpha: a };
-        let mut color = BigColor::default();
-        color.oklch = oklch;
-        color.ok = true;
-        color.format = ColorFormat::OKLCH;
-        color
+        BigColor::from_color(Color::new(l, c, h, a))
     }
     
+    /// Creates a BigColor from XYZ D65 without rounding through 8-bit RGB
+    #[cfg(feature = "std")]
+    pub(crate) fn from_xyz_d65(xyz: XyzD65, format: ColorFormat) -> Self {
+        let oklch = oklab_to_oklch(xyz_d65_to_oklab(xyz));
+        let mut color = BigColor::from_oklch(oklch.l, oklch.c, oklch.h, xyz.a);
+        color.format = format;
+        color
+    }
+
+    /// Creates a BigColor from a color literal written in code, such as
+    /// `0xFF1A6EF5` or `Color.fromARGB(255, 26, 110, 245)`, falling back to the
+    /// regular parser. `byte_order` decides how packed integers and 8-digit hex are read.
+    #[cfg(feature = "std")]
+    pub fn from_code(input: &str, byte_order: ByteOrder) -> Self {
+        parse_code_literal(input, byte_order).unwrap_or_else(|| BigColor::new(input))
+    }
+
+    /// Creates a BigColor from the first color set by an ANSI escape sequence,
+    /// such as `\x1b[38;2;26;110;245m` or `\x1b[38;5;33m`. 16-color indices use
+    /// the xterm defaults.
+    #[cfg(feature = "std")]
+    pub fn from_ansi(sequence: &str) -> Self {
+        parse_ansi_sequence(sequence, None)
+            .into_iter()
+            .next()
+            .map(|ansi| ansi.color)
+            .unwrap_or_else(|| BigColor::new(sequence))
+    }
+
     /// Creates a BigColor from a ratio
     pub fn from_ratio(color: &str) -> Self {
         // This is a simplified version that just passes through to new
@@ -677,10 +643,10 @@ impl BigColor {
         let s = (hsv.s * 100.0).round() as i32;
         let b = (hsv.v * 100.0).round() as i32;
         
-        if (self.oklch.alpha - 1.0).abs() < f32::EPSILON {
+        if (self.color.alpha - 1.0).abs() < f32::EPSILON {
             format!("hsb({}, {}%, {}%)", h, s, b)
         } else {
-            format!("hsba({}, {}%, {}%, {})", h, s, b, (self.oklch.alpha * 100.0).round() / 100.0)
+            format!("hsba({}, {}%, {}%, {})", h, s, b, (self.color.alpha * 100.0).round() / 100.0)
         }
     }
 
@@ -710,16 +676,40 @@ impl BigColor {
     /// Create a new BigColor from CMYK values
     pub fn from_cmyk(c: f32, m: f32, y: f32, k: f32, a: f32) -> Self {
         let (r, g, b, a) = cmyk_to_rgb(CMYK { c, m, y, k, a });
-        let oklch = rgb_to_oklch(r, g, b, a);
+        let color = rgb_to_oklch(r, g, b, a).into();
         
         BigColor {
-            oklch,
+            color,
             original_input: format!("cmyk({}%, {}%, {}%, {}%)", c, m, y, k),
             format: ColorFormat::CMYK,
             ok: true,
         }
     }
 
+    /// Returns the color as a code literal for another platform or a shader
+    #[cfg(feature = "std")]
+    pub fn to_code(&self, format: CodeFormat, options: Option<CodeOptions>) -> String {
+        to_code_impl(self, format, options)
+    }
+
+    /// Returns the 24-bit escape sequence that sets this color as the text color
+    #[cfg(feature = "std")]
+    pub fn to_ansi_truecolor_fg(&self) -> String {
+        truecolor_sequence(self, Layer::Foreground)
+    }
+
+    /// Returns the 24-bit escape sequence that sets this color as the background
+    #[cfg(feature = "std")]
+    pub fn to_ansi_truecolor_bg(&self) -> String {
+        truecolor_sequence(self, Layer::Background)
+    }
+
+    /// Returns the closest xterm-256 color index
+    #[cfg(feature = "std")]
+    pub fn to_ansi256(&self) -> u8 {
+        nearest_ansi256(self)
+    }
+
     /// Returns the color as a CSS-compatible string in the specified format
     pub fn to(&self, format: ColorFormat) -> String {
         if !self.is_valid() {
@@ -762,6 +752,31 @@ impl BigColor {
     pub fn get_contrast_ratio(&self, other: &BigColor) -> f32 {
         get_contrast_ratio_impl(self, other)
     }
+
+    /// Gets the APCA lightness contrast (Lc) of this color used as text
+    /// on the given background. Positive values mean dark text on a light
+    /// background, negative values light text on a dark background.
+    pub fn get_apca_contrast(&self, background: &BigColor) -> f32 {
+        get_apca_contrast_impl(self, background)
+    }
+
+    /// Gets the perceptual distance (ΔEOK) between this color and another
+    /// color, measured in OKLab. Alpha is ignored.
+    pub fn delta_e(&self, other: &BigColor) -> f32 {
+        self.color.delta_e(other.color)
+    }
+
+    /// Simulates how this color is perceived with a color vision deficiency.
+    /// `severity` ranges from 0.0 to 1.0 and defaults to full dichromacy.
+    pub fn simulate_cvd(&self, cvd: CvdType, severity: Option<f32>) -> BigColor {
+        simulate_cvd_impl(self, cvd, severity)
+    }
+
+    /// Shifts the information lost to a color vision deficiency into
+    /// channels the viewer can still perceive. `strength` defaults to 1.0.
+    pub fn daltonize(&self, cvd: CvdType, strength: Option<f32>) -> BigColor {
+        daltonize_impl(self, cvd, strength)
+    }
 }
 
 /// Creates a random color
@@ -791,6 +806,34 @@ pub fn mix(color1: &BigColor, color2: &BigColor, amount: Option<f32>) -> BigColo
     BigColor::from_rgb(r as u8, g as u8, b as u8, a)
 }
 
+/// Returns the smallest gamut that holds a color string's channels as
+/// written. `BigColor::new` clips to 8-bit sRGB, so this reads the input
+/// itself. Returns None for invalid input and for `lab()`, `lch()`,
+/// `oklab()` and `oklch()` channels it cannot read.
+pub fn declared_gamut(input: &str) -> Option<Gamut> {
+    let Some(linear) = declared_linear_srgb(input) else {
+        let color = BigColor::new(input);
+        let declared = matches!(color.format, ColorFormat::LAB | ColorFormat::LCH | ColorFormat::OKLAB | ColorFormat::OKLCH);
+        return (color.is_valid() && !declared).then_some(Gamut::Srgb);
+    };
+    let in_range = |channels: [f32; 3]| channels.iter().all(|c| (-1e-4..=1.0001).contains(c));
+    if in_range(linear) {
+        Some(Gamut::Srgb)
+    } else if in_range(xyz_d65_to_linear_display_p3(linear_srgb_to_xyz_d65(linear, 1.0))) {
+        Some(Gamut::DisplayP3)
+    } else {
+        Some(Gamut::Wide)
+    }
+}
+
+/// Harmonizes every color of a palette toward a source color
+pub fn harmonize_palette(colors: &[BigColor], source: &BigColor, max_degrees: f32) -> Vec<BigColor> {
+    colors
+        .iter()
+        .map(|color| color.harmonize(source, max_degrees))
+        .collect()
+}
+
 /// Analyzes the readability between two colors
 pub fn readability(color1: &BigColor, color2: &BigColor) -> f32 {
     let l1 = color1.get_luminance();
@@ -804,6 +847,7 @@ pub fn readability(color1: &BigColor, color2: &BigColor) -> f32 {
 
 /// WCAG2 parameters
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct WCAG2Params {
     pub level: WCAG2Level,
     pub size: WCAG2Size,
@@ -811,6 +855,7 @@ pub struct WCAG2Params {
 
 /// WCAG2 levels
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WCAG2Level {
     AA,
     AAA,
@@ -818,6 +863,7 @@ pub enum WCAG2Level {
 
 /// WCAG2 sizes
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WCAG2Size {
     Small,
     Large,
@@ -857,6 +903,7 @@ pub fn is_readable(color1: &BigColor, color2: &BigColor, wcag2: Option<WCAG2Para
 
 /// Arguments for the most_readable function
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct MostReadableArgs {
     pub include_fallback_colors: bool,
     pub wcag2: WCAG2Params,
@@ -916,3 +963,33 @@ impl fmt::Display for BigColor {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harmonize_palette_rotates_toward_source() {
+        let hue = |color: &BigColor| (color.to_oklch().h * 1000.0).round() / 1000.0;
+        let source = BigColor::from_oklch(0.6, 0.15, 100.0, 1.0);
+
+        // Half the difference, capped at max_degrees
+        assert_eq!(hue(&BigColor::from_oklch(0.5, 0.1, 80.0, 1.0).harmonize(&source, 15.0)), 90.0);
+        assert_eq!(hue(&BigColor::from_oklch(0.5, 0.1, 20.0, 1.0).harmonize(&source, 15.0)), 35.0);
+
+        // The shorter way around crosses 0°/360°
+        let red = BigColor::from_oklch(0.6, 0.15, 10.0, 1.0);
+        assert_eq!(hue(&BigColor::from_oklch(0.5, 0.1, 350.0, 1.0).harmonize(&red, 15.0)), 0.0);
+
+        // Each entry is rotated on its own; achromatic ones are left unchanged
+        let palette = [
+            BigColor::from_oklch(0.5, 0.1, 20.0, 0.5),
+            BigColor::from_oklch(0.5, 0.0, 80.0, 1.0),
+        ];
+        let harmonized = harmonize_palette(&palette, &source, 15.0);
+        assert_eq!(harmonized.len(), 2);
+        assert_eq!(hue(&harmonized[0]), 35.0);
+        assert_eq!(harmonized[0].get_alpha(), 0.5);
+        assert_eq!(harmonized[1].color(), palette[1].color());
+    }
+}
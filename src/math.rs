@@ -0,0 +1,57 @@
+// Float functions for builds without std, backed by libm
+
+/// The `f32` methods the color math uses that `core` does not provide.
+/// With std the inherent methods are used instead.
+pub(crate) trait FloatExt {
+    fn powf(self, n: f32) -> f32;
+    fn powi(self, n: i32) -> f32;
+    fn sqrt(self) -> f32;
+    fn cbrt(self) -> f32;
+    fn sin(self) -> f32;
+    fn cos(self) -> f32;
+    fn atan2(self, other: f32) -> f32;
+    fn round(self) -> f32;
+    fn floor(self) -> f32;
+}
+
+impl FloatExt for f32 {
+    fn powf(self, n: f32) -> f32 {
+        libm::powf(self, n)
+    }
+
+    fn powi(self, n: i32) -> f32 {
+        let mut result = 1.0;
+        for _ in 0..n.unsigned_abs() {
+            result *= self;
+        }
+        if n < 0 { 1.0 / result } else { result }
+    }
+
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    fn cbrt(self) -> f32 {
+        libm::cbrtf(self)
+    }
+
+    fn sin(self) -> f32 {
+        libm::sinf(self)
+    }
+
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+
+    fn atan2(self, other: f32) -> f32 {
+        libm::atan2f(self, other)
+    }
+
+    fn round(self) -> f32 {
+        libm::roundf(self)
+    }
+
+    fn floor(self) -> f32 {
+        libm::floorf(self)
+    }
+}
@@ -12,6 +12,20 @@ pub fn multiply_v3_m3x3(v: Vector3, m: Matrix3x3) -> Vector3 {
     ]
 }
 
+// Linear-light sRGB to XYZ D65
+pub const LINEAR_SRGB_TO_XYZ_M: Matrix3x3 = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.072175],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+
+// XYZ D65 to linear-light sRGB
+pub const XYZ_TO_LINEAR_SRGB_M: Matrix3x3 = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.041556],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
 // Recalculated for consistent reference white
 // see https://github.com/w3c/csswg-drafts/issues/6642#issuecomment-943521484
 pub const XYZ_TO_LMS_M: Matrix3x3 = [
@@ -80,4 +94,12 @@ pub fn constrain_angle(angle: f32) -> f32 {
         a += 360.0;
     }
     a
-} 
\ No newline at end of file
+}
+
+/// Rotate a hue toward a target hue by half their shortest difference,
+/// capped at `max_degrees`
+pub fn rotate_hue_toward(hue: f32, target: f32, max_degrees: f32) -> f32 {
+    let diff = (target - hue + 540.0) % 360.0 - 180.0;
+    let rotation = (diff.abs() * 0.5).min(max_degrees.max(0.0));
+    constrain_angle(hue + rotation * diff.signum())
+}
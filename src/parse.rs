@@ -1,11 +1,10 @@
 // Color parsing functions
 // Ported from tinycolor.js
 
-use std::collections::HashMap;
-use lazy_static::lazy_static;
-use regex::Regex;
 use crate::color_space::*;
 use crate::ColorFormat;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
 
 /// RGB color input result
 #[derive(Debug, Clone)]
@@ -220,7 +219,7 @@ fn string_input_to_object(color: &str) -> Option<ColorInput> {
     let color = color.trim().to_lowercase();
     
     // Check for named colors first
-    if let Some(hex) = names().get(&color) {
+    if let Some(hex) = name_to_hex(&color) {
         if let Some(rgb) = parse_hex(hex) {
             return Some(ColorInput::NAME(rgb.0, rgb.1, rgb.2));
         }
@@ -231,509 +230,619 @@ fn string_input_to_object(color: &str) -> Option<ColorInput> {
         return Some(ColorInput::RGBA(0, 0, 0, 0.0));
     }
 
-    // Try to match using regex patterns
-    lazy_static! {
-        static ref HEX_3: Regex = Regex::new(r"^#?([0-9a-f]{1})([0-9a-f]{1})([0-9a-f]{1})$").unwrap();
-        static ref HEX_6: Regex = Regex::new(r"^#?([0-9a-f]{2})([0-9a-f]{2})([0-9a-f]{2})$").unwrap();
-        static ref HEX_4: Regex = Regex::new(r"^#?([0-9a-f]{1})([0-9a-f]{1})([0-9a-f]{1})([0-9a-f]{1})$").unwrap();
-        static ref HEX_8: Regex = Regex::new(r"^#?([0-9a-f]{2})([0-9a-f]{2})([0-9a-f]{2})([0-9a-f]{2})$").unwrap();
-        static ref RGB: Regex = Regex::new(r"^rgb\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\)$").unwrap();
-        static ref RGB_PERCENT: Regex = Regex::new(r"^rgb\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*\)$").unwrap();
-        static ref RGBA: Regex = Regex::new(r"^rgba\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*([01]?\.?\d*)\s*\)$").unwrap();
-        static ref RGBA_PERCENT: Regex = Regex::new(r"^rgba\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*([01]?\.?\d*)\s*\)$").unwrap();
-        static ref HSL: Regex = Regex::new(r"^hsl\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*\)$").unwrap();
-        static ref HSL_SPACE: Regex = Regex::new(r"^(\d+(?:\.\d+)?)\s+(\d+(?:\.\d+)?)%\s+(\d+(?:\.\d+)?)%$").unwrap();
-        static ref HSLA: Regex = Regex::new(r"^hsla\s*\(\s*(\d+(?:\.\d+)?)\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*([01]?\.?\d*)\s*\)$").unwrap();
-        static ref HSV: Regex = Regex::new(r"^hsv\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*\)$").unwrap();
-        static ref HSVA: Regex = Regex::new(r"^hsva\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*,\s*([01]?\.?\d*)\s*\)$").unwrap();
-        static ref HSB: Regex = Regex::new(r"^hsb\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*\)$").unwrap();
-        static ref HSBA: Regex = Regex::new(r"^hsba\s*\(\s*(\d+)\s*,\s*(\d+)%\s*,\s*(\d+)%\s*,\s*([01]?\.?\d*)\s*\)$").unwrap();
-        static ref LAB: Regex = Regex::new(r"^lab\s*\(\s*(\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref LAB_WITH_SLASH: Regex = Regex::new(r"^lab\s*\(\s*(\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-        static ref LCH: Regex = Regex::new(r"^lch\s*\(\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref LCH_WITH_SLASH: Regex = Regex::new(r"^lch\s*\(\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-        static ref OKLAB: Regex = Regex::new(r"^oklab\s*\(\s*(\d+(?:\.\d+)?)%\s*,?\s*(-?\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref OKLAB_WITH_SLASH: Regex = Regex::new(r"^oklab\s*\(\s*(\d+(?:\.\d+)?)%\s*,?\s*(-?\d+(?:\.\d+)?)\s*,?\s*(-?\d+(?:\.\d+)?)\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-        static ref OKLCH: Regex = Regex::new(r"^oklch\s*\(\s*(\d+(?:\.\d+)?)%\s*,?\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref OKLCH_WITH_SLASH: Regex = Regex::new(r"^oklch\s*\(\s*(\d+(?:\.\d+)?)%\s*,?\s*(\d+(?:\.\d+)?)\s*,?\s*(\d+(?:\.\d+)?)\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-        static ref OKLCH_DECIMAL: Regex = Regex::new(r"^oklch\s*\(\s*(\d*\.?\d+)\s+(\d*\.?\d+)\s+(\d+(?:\.\d+)?)\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref OKLCH_DECIMAL_WITH_SLASH: Regex = Regex::new(r"^oklch\s*\(\s*(\d*\.?\d+)\s+(\d*\.?\d+)\s+(\d+(?:\.\d+)?)\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-        static ref CMYK: Regex = Regex::new(r"^cmyk\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*(?:,\s*([01]?\.?\d+))?\s*\)$").unwrap();
-        static ref CMYK_WITH_SLASH: Regex = Regex::new(r"^cmyk\s*\(\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*,\s*(\d+(?:\.\d+)?)%\s*/\s*([01]?\.?\d+)\s*\)$").unwrap();
-    }
-
-    // Try to match hex formats
-    if let Some(caps) = HEX_3.captures(&color) {
-        let r = caps.get(1).map_or("", |m| m.as_str());
-        let g = caps.get(2).map_or("", |m| m.as_str());
-        let b = caps.get(3).map_or("", |m| m.as_str());
-        
-        let r = u8::from_str_radix(&format!("{}{}", r, r), 16).unwrap_or(0);
-        let g = u8::from_str_radix(&format!("{}{}", g, g), 16).unwrap_or(0);
-        let b = u8::from_str_radix(&format!("{}{}", b, b), 16).unwrap_or(0);
-        
-        return Some(ColorInput::HEX(r, g, b));
-    }
-    
-    if let Some(caps) = HEX_6.captures(&color) {
-        let r = caps.get(1).map_or("", |m| m.as_str());
-        let g = caps.get(2).map_or("", |m| m.as_str());
-        let b = caps.get(3).map_or("", |m| m.as_str());
-        
-        let r = u8::from_str_radix(r, 16).unwrap_or(0);
-        let g = u8::from_str_radix(g, 16).unwrap_or(0);
-        let b = u8::from_str_radix(b, 16).unwrap_or(0);
-        
-        return Some(ColorInput::HEX(r, g, b));
+    parse_hex_input(&color).or_else(|| parse_function(&color))
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, with or without the `#`
+fn parse_hex_input(color: &str) -> Option<ColorInput> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if !hex.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return None;
     }
-    
-    if let Some(caps) = HEX_4.captures(&color) {
-        let r = caps.get(1).map_or("", |m| m.as_str());
-        let g = caps.get(2).map_or("", |m| m.as_str());
-        let b = caps.get(3).map_or("", |m| m.as_str());
-        let a = caps.get(4).map_or("", |m| m.as_str());
-        
-        let r = u8::from_str_radix(&format!("{}{}", r, r), 16).unwrap_or(0);
-        let g = u8::from_str_radix(&format!("{}{}", g, g), 16).unwrap_or(0);
-        let b = u8::from_str_radix(&format!("{}{}", b, b), 16).unwrap_or(0);
-        let a = u8::from_str_radix(&format!("{}{}", a, a), 16).unwrap_or(0) as f32 / 255.0;
-        
-        return Some(ColorInput::HEX8(r, g, b, a));
+
+    let digits = hex.as_bytes();
+    let short = |i: usize| hex_value(digits[i]) * 17;
+    let long = |i: usize| hex_value(digits[i]) * 16 + hex_value(digits[i + 1]);
+    match digits.len() {
+        3 => Some(ColorInput::HEX(short(0), short(1), short(2))),
+        6 => Some(ColorInput::HEX(long(0), long(2), long(4))),
+        4 => Some(ColorInput::HEX8(short(0), short(1), short(2), short(3) as f32 / 255.0)),
+        8 => Some(ColorInput::HEX8(long(0), long(2), long(4), long(6) as f32 / 255.0)),
+        _ => None,
     }
-    
-    if let Some(caps) = HEX_8.captures(&color) {
-        let r = caps.get(1).map_or("", |m| m.as_str());
-        let g = caps.get(2).map_or("", |m| m.as_str());
-        let b = caps.get(3).map_or("", |m| m.as_str());
-        let a = caps.get(4).map_or("", |m| m.as_str());
-        
-        let r = u8::from_str_radix(r, 16).unwrap_or(0);
-        let g = u8::from_str_radix(g, 16).unwrap_or(0);
-        let b = u8::from_str_radix(b, 16).unwrap_or(0);
-        let a = u8::from_str_radix(a, 16).unwrap_or(0) as f32 / 255.0;
-        
-        return Some(ColorInput::HEX8(r, g, b, a));
+}
+
+/// Value of a lowercase hex digit
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        _ => digit - b'a' + 10,
     }
-    
-    // Try to match RGB formats
-    if let Some(caps) = RGB.captures(&color) {
-        let r = caps.get(1).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        let g = caps.get(2).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        
-        return Some(ColorInput::RGB(r, g, b));
+}
+
+/// Parses color functions such as `rgb(255, 0, 0)` or `oklch(70% 0.1 200 / 0.5)`,
+/// and the space-separated `0 100% 50%` HSL shorthand
+fn parse_function(color: &str) -> Option<ColorInput> {
+    let mut c = Cursor::new(color);
+    let name = c.take_while(|ch| ch.is_ascii_lowercase());
+    if name.is_empty() {
+        return hsl_shorthand(c);
+    }
+    c.skip_whitespace();
+    if !c.eat('(') {
+        return None;
+    }
+    c.skip_whitespace();
+
+    match name {
+        "rgb" => rgb_bytes(c).or_else(|| rgb_percentages(c)),
+        "rgba" => rgba_bytes(c).or_else(|| rgba_percentages(c)),
+        "hsl" => {
+            let h = c.number()?;
+            c.comma()?;
+            let s = c.percentage()?;
+            c.comma()?;
+            let l = c.percentage()?;
+            c.close()?;
+            Some(ColorInput::HSL(h / 360.0, s / 100.0, l / 100.0))
+        }
+        "hsla" => {
+            let h = c.number()?;
+            c.comma()?;
+            let s = c.percentage()?;
+            c.comma()?;
+            let l = c.percentage()?;
+            let a = c.comma_alpha()?;
+            Some(ColorInput::HSLA(h / 360.0, s / 100.0, l / 100.0, a))
+        }
+        "hsv" | "hsb" => {
+            let h = c.integer()?;
+            c.comma()?;
+            let s = c.integer_percentage()?;
+            c.comma()?;
+            let v = c.integer_percentage()?;
+            c.close()?;
+            Some(ColorInput::HSV(h / 360.0, s / 100.0, v / 100.0))
+        }
+        "hsva" | "hsba" => {
+            let h = c.integer()?;
+            c.comma()?;
+            let s = c.integer_percentage()?;
+            c.comma()?;
+            let v = c.integer_percentage()?;
+            let a = c.comma_alpha()?;
+            Some(ColorInput::HSVA(h / 360.0, s / 100.0, v / 100.0, a))
+        }
+        "lab" => {
+            let l = c.number()?;
+            c.optional_comma();
+            let a = c.signed_number()?;
+            c.optional_comma();
+            let b = c.signed_number()?;
+            let alpha = c.optional_alpha()?;
+            Some(ColorInput::LAB(l, a, b, alpha))
+        }
+        "lch" => {
+            let l = c.number()?;
+            c.optional_comma();
+            let chroma = c.number()?;
+            c.optional_comma();
+            let h = c.number()?;
+            let alpha = c.optional_alpha()?;
+            Some(ColorInput::LCH(l, chroma, h, alpha))
+        }
+        "oklab" => {
+            let l = c.percentage()?;
+            c.optional_comma();
+            let a = c.signed_number()?;
+            c.optional_comma();
+            let b = c.signed_number()?;
+            let alpha = c.optional_alpha()?;
+            Some(ColorInput::OKLAB(l / 100.0, a, b, alpha))
+        }
+        "oklch" => oklch_percentage(c).or_else(|| oklch_decimal(c)),
+        "cmyk" => {
+            let cyan = c.percentage()?;
+            c.comma()?;
+            let m = c.percentage()?;
+            c.comma()?;
+            let y = c.percentage()?;
+            c.comma()?;
+            let k = c.percentage()?;
+            let alpha = c.optional_alpha()?;
+            Some(ColorInput::CMYK(cyan, m, y, k, alpha))
+        }
+        _ => None,
     }
-    
-    if let Some(caps) = RGB_PERCENT.captures(&color) {
-        let r_pct = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let g_pct = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b_pct = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        
-        let r = (r_pct * 2.55).round() as u8;
-        let g = (g_pct * 2.55).round() as u8;
-        let b = (b_pct * 2.55).round() as u8;
-        
-        return Some(ColorInput::RGB(r, g, b));
+}
+
+fn rgb_bytes(mut c: Cursor) -> Option<ColorInput> {
+    let r = c.byte()?;
+    c.comma()?;
+    let g = c.byte()?;
+    c.comma()?;
+    let b = c.byte()?;
+    c.close()?;
+    Some(ColorInput::RGB(r, g, b))
+}
+
+fn rgb_percentages(mut c: Cursor) -> Option<ColorInput> {
+    let r = c.percentage()?;
+    c.comma()?;
+    let g = c.percentage()?;
+    c.comma()?;
+    let b = c.percentage()?;
+    c.close()?;
+    Some(ColorInput::RGB(percent_to_byte(r), percent_to_byte(g), percent_to_byte(b)))
+}
+
+fn rgba_bytes(mut c: Cursor) -> Option<ColorInput> {
+    let r = c.byte()?;
+    c.comma()?;
+    let g = c.byte()?;
+    c.comma()?;
+    let b = c.byte()?;
+    let a = c.comma_alpha()?;
+    Some(ColorInput::RGBA(r, g, b, a))
+}
+
+fn rgba_percentages(mut c: Cursor) -> Option<ColorInput> {
+    let r = c.percentage()?;
+    c.comma()?;
+    let g = c.percentage()?;
+    c.comma()?;
+    let b = c.percentage()?;
+    let a = c.comma_alpha()?;
+    Some(ColorInput::RGBA(percent_to_byte(r), percent_to_byte(g), percent_to_byte(b), a))
+}
+
+/// `oklch(70% 0.1 200)`, with optional commas
+fn oklch_percentage(mut c: Cursor) -> Option<ColorInput> {
+    let l = c.percentage()?;
+    c.optional_comma();
+    let chroma = c.number()?;
+    c.optional_comma();
+    let h = c.number()?;
+    let alpha = c.optional_alpha()?;
+    Some(ColorInput::OKLCH(l / 100.0, chroma, h, alpha))
+}
+
+/// `oklch(0.7 0.1 200)`, separated by spaces
+fn oklch_decimal(mut c: Cursor) -> Option<ColorInput> {
+    let l = c.decimal()?;
+    c.spaces()?;
+    let chroma = c.decimal()?;
+    c.spaces()?;
+    let h = c.number()?;
+    let alpha = c.optional_alpha()?;
+    Some(ColorInput::OKLCH(l, chroma, h, alpha))
+}
+
+/// `0 100% 50%`
+fn hsl_shorthand(mut c: Cursor) -> Option<ColorInput> {
+    let h = c.number()?;
+    c.spaces()?;
+    let s = c.percentage()?;
+    c.spaces()?;
+    let l = c.percentage()?;
+    c.is_empty().then_some(ColorInput::HSL(h / 360.0, s / 100.0, l / 100.0))
+}
+
+fn percent_to_byte(percent: f32) -> u8 {
+    (percent * 2.55).round() as u8
+}
+
+/// Reads the arguments of a color function. Each method consumes its token
+/// and returns `None` when the input does not match; callers copy the cursor
+/// to try another form.
+#[derive(Clone, Copy)]
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Cursor { rest: input }
     }
-    
-    if let Some(caps) = RGBA.captures(&color) {
-        let r = caps.get(1).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        let g = caps.get(2).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<u8>().unwrap_or(0);
-        let a = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::RGBA(r, g, b, a));
+
+    fn is_empty(&self) -> bool {
+        self.rest.is_empty()
     }
-    
-    if let Some(caps) = RGBA_PERCENT.captures(&color) {
-        let r_pct = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let g_pct = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b_pct = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let a = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        let r = (r_pct * 2.55).round() as u8;
-        let g = (g_pct * 2.55).round() as u8;
-        let b = (b_pct * 2.55).round() as u8;
-        
-        return Some(ColorInput::RGBA(r, g, b, a));
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|ch: char| !f(ch)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
     }
-    
-    // Try to match HSL formats
-    if let Some(caps) = HSL.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let l = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        
-        return Some(ColorInput::HSL(h / 360.0, s, l));
+
+    fn eat(&mut self, ch: char) -> bool {
+        match self.rest.strip_prefix(ch) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
     }
-    
-    // Match space-separated HSL format
-    if let Some(caps) = HSL_SPACE.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let l = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        
-        return Some(ColorInput::HSL(h / 360.0, s, l));
+
+    fn skip_whitespace(&mut self) -> bool {
+        !self.take_while(char::is_whitespace).is_empty()
     }
-    
-    if let Some(caps) = HSLA.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let l = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let a = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::HSLA(h / 360.0, s, l, a));
+
+    fn digits(&mut self) -> &'a str {
+        self.take_while(|ch| ch.is_ascii_digit())
     }
-    
-    // Try to match HSV formats
-    if let Some(caps) = HSV.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let v = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        
-        return Some(ColorInput::HSV(h / 360.0, s, v));
+
+    /// The text consumed since `start`
+    fn since(&self, start: Cursor<'a>) -> &'a str {
+        &start.rest[..start.rest.len() - self.rest.len()]
     }
-    
-    if let Some(caps) = HSVA.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let v = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let a = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::HSVA(h / 360.0, s, v, a));
+
+    /// `\d+`, clamped to 0 when it does not fit a byte
+    fn byte(&mut self) -> Option<u8> {
+        let digits = self.digits();
+        (!digits.is_empty()).then(|| digits.parse().unwrap_or(0))
     }
-    
-    // Try to match LAB formats
-    if let Some(caps) = LAB.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let a = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::LAB(l, a, b, alpha));
+
+    /// `\d+`
+    fn integer(&mut self) -> Option<f32> {
+        let digits = self.digits();
+        (!digits.is_empty()).then(|| digits.parse().unwrap_or(0.0))
     }
-    
-    if let Some(caps) = LAB_WITH_SLASH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let a = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::LAB(l, a, b, alpha));
+
+    /// `\d+%`
+    fn integer_percentage(&mut self) -> Option<f32> {
+        let value = self.integer()?;
+        self.eat('%').then_some(value)
     }
-    
-    // Try to match LCH formats
-    if let Some(caps) = LCH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::LCH(l, c, h, alpha));
+
+    /// `\d+(\.\d+)?`
+    fn number(&mut self) -> Option<f32> {
+        let start = *self;
+        if self.digits().is_empty() {
+            return None;
+        }
+        let mut fraction = *self;
+        if fraction.eat('.') && !fraction.digits().is_empty() {
+            *self = fraction;
+        }
+        Some(self.since(start).parse().unwrap_or(0.0))
     }
-    
-    if let Some(caps) = LCH_WITH_SLASH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::LCH(l, c, h, alpha));
+
+    /// `-?\d+(\.\d+)?`
+    fn signed_number(&mut self) -> Option<f32> {
+        let start = *self;
+        self.eat('-');
+        self.number()?;
+        Some(self.since(start).parse().unwrap_or(0.0))
     }
-    
-    // Try to match OKLab formats
-    if let Some(caps) = OKLAB.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let a = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLAB(l, a, b, alpha));
+
+    /// `\d*\.?\d+`
+    fn decimal(&mut self) -> Option<f32> {
+        let start = *self;
+        let whole = self.digits();
+        let mut fraction = *self;
+        if fraction.eat('.') && !fraction.digits().is_empty() {
+            *self = fraction;
+        } else if whole.is_empty() {
+            return None;
+        }
+        Some(self.since(start).parse().unwrap_or(0.0))
     }
-    
-    if let Some(caps) = OKLAB_WITH_SLASH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let a = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLAB(l, a, b, alpha));
+
+    /// `\d+(\.\d+)?%`
+    fn percentage(&mut self) -> Option<f32> {
+        let value = self.number()?;
+        self.eat('%').then_some(value)
     }
-    
-    // Try to match OKLCH formats
-    if let Some(caps) = OKLCH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLCH(l, c, h, alpha));
+
+    /// An alpha value, `[01]?\.?\d*`, or `[01]?\.?\d+` when a digit is required.
+    /// Text that is not a number reads as 1.
+    fn alpha(&mut self, digit_required: bool) -> Option<f32> {
+        let start = *self;
+        let whole = self.digits();
+        let mut has_digit = !whole.is_empty();
+        if matches!(whole, "" | "0" | "1") && self.eat('.') {
+            has_digit = !self.digits().is_empty();
+        }
+        if digit_required && !has_digit {
+            return None;
+        }
+        Some(self.since(start).parse().unwrap_or(1.0))
     }
-    
-    if let Some(caps) = OKLCH_WITH_SLASH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLCH(l, c, h, alpha));
+
+    /// `\s*,\s*`
+    fn comma(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        if !self.eat(',') {
+            return None;
+        }
+        self.skip_whitespace();
+        Some(())
     }
-    
-    if let Some(caps) = OKLCH_DECIMAL.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLCH(l, c, h, alpha));
+
+    /// `\s*,?\s*`
+    fn optional_comma(&mut self) {
+        self.skip_whitespace();
+        self.eat(',');
+        self.skip_whitespace();
     }
-    
-    if let Some(caps) = OKLCH_DECIMAL_WITH_SLASH.captures(&color) {
-        let l = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let c = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let h = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::OKLCH(l, c, h, alpha));
+
+    /// `\s+`
+    fn spaces(&mut self) -> Option<()> {
+        self.skip_whitespace().then_some(())
     }
-    
-    // Try to match CMYK formats
-    if let Some(caps) = CMYK.captures(&color) {
-        let c = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let m = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let y = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let k = caps.get(4).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(5).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::CMYK(c, m, y, k, alpha));
+
+    /// `\s*\)` at the end of the input
+    fn close(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        (self.eat(')') && self.is_empty()).then_some(())
     }
-    
-    if let Some(caps) = CMYK_WITH_SLASH.captures(&color) {
-        let c = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let m = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let y = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let k = caps.get(4).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let alpha = caps.get(5).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::CMYK(c, m, y, k, alpha));
+
+    /// `, alpha)` where the alpha may be empty
+    fn comma_alpha(&mut self) -> Option<f32> {
+        self.comma()?;
+        let alpha = self.alpha(false)?;
+        self.close()?;
+        Some(alpha)
     }
-    
-    // Try to match HSB formats
-    if let Some(caps) = HSB.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        
-        return Some(ColorInput::HSV(h / 360.0, s, b));
+
+    /// `)`, `, alpha)` or `/ alpha)`, with alpha defaulting to 1
+    fn optional_alpha(&mut self) -> Option<f32> {
+        self.skip_whitespace();
+        let mut alpha = 1.0;
+        if self.eat(',') || self.eat('/') {
+            self.skip_whitespace();
+            alpha = self.alpha(true)?;
+        }
+        self.close()?;
+        Some(alpha)
     }
-    
-    if let Some(caps) = HSBA.captures(&color) {
-        let h = caps.get(1).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0);
-        let s = caps.get(2).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let b = caps.get(3).map_or("0", |m| m.as_str()).parse::<f32>().unwrap_or(0.0) / 100.0;
-        let a = caps.get(4).map_or("1.0", |m| m.as_str()).parse::<f32>().unwrap_or(1.0);
-        
-        return Some(ColorInput::HSVA(h / 360.0, s, b, a));
+}
+
+/// Reads a hue with an optional `deg`, `grad`, `rad` or `turn` unit as degrees
+fn hue_degrees(part: &str) -> Option<f32> {
+    let (number, scale) = if let Some(n) = part.strip_suffix("deg") {
+        (n, 1.0)
+    } else if let Some(n) = part.strip_suffix("grad") {
+        (n, 0.9)
+    } else if let Some(n) = part.strip_suffix("rad") {
+        (n, 180.0 / core::f32::consts::PI)
+    } else if let Some(n) = part.strip_suffix("turn") {
+        (n, 360.0)
+    } else {
+        (part, 1.0)
+    };
+    number.parse::<f32>().ok().map(|value| value * scale)
+}
+
+/// Reads the channels of an `oklch()`, `oklab()`, `lch()` or `lab()` literal
+/// as written and returns them as linear sRGB, which may fall outside 0 to 1,
+/// since parsing clips them to 8-bit sRGB. Returns None for other literals
+/// and for channels it cannot read.
+pub(crate) fn declared_linear_srgb(literal: &str) -> Option<[f32; 3]> {
+    let literal = literal.trim();
+    let open = literal.find('(')?;
+    let space = &literal[..open];
+    let body = literal[open + 1..].strip_suffix(')')?;
+    let mut parts = body
+        .split('/')
+        .next()?
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| if part.eq_ignore_ascii_case("none") { "0" } else { part });
+    let (first, second, third) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
     }
-    
-    None
+
+    // Percentages scale the reference ranges of CSS Color 4
+    let channel = |part: &str, full: f32| -> Option<f32> {
+        match part.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().ok().map(|value| value / 100.0 * full),
+            None => part.parse().ok(),
+        }
+    };
+    let is = |name: &str| space.eq_ignore_ascii_case(name);
+
+    let xyz = if is("oklch") || is("oklab") {
+        let l = channel(first, 1.0)?;
+        let oklab = if is("oklch") {
+            oklch_to_oklab(OKLCH { l, c: channel(second, 0.4)?, h: hue_degrees(third)?, alpha: 1.0 })
+        } else {
+            OKLab { l, a: channel(second, 0.4)?, b: channel(third, 0.4)?, alpha: 1.0 }
+        };
+        oklab_to_xyz_d65(oklab)
+    } else if is("lch") || is("lab") {
+        let l = channel(first, 100.0)?;
+        let lab = if is("lch") {
+            lch_to_lab(LCH { l, c: channel(second, 150.0)?, h: hue_degrees(third)?, alpha: 1.0 })
+        } else {
+            Lab { l, a: channel(second, 125.0)?, b: channel(third, 125.0)?, alpha: 1.0 }
+        };
+        xyz_d50_to_xyz_d65(lab_to_xyz_d50(lab))
+    } else {
+        return None;
+    };
+    Some(xyz_d65_to_linear_srgb(xyz))
 }
 
 /// Helper function to parse hex values
 fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
-    let hex = hex.trim_start_matches('#');
-    
-    match hex.len() {
-        3 => {
-            let r = u8::from_str_radix(&format!("{}{}", &hex[0..1], &hex[0..1]), 16).ok()?;
-            let g = u8::from_str_radix(&format!("{}{}", &hex[1..2], &hex[1..2]), 16).ok()?;
-            let b = u8::from_str_radix(&format!("{}{}", &hex[2..3], &hex[2..3]), 16).ok()?;
-            Some((r, g, b))
-        },
-        6 => {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            Some((r, g, b))
-        },
+    match parse_hex_input(hex)? {
+        ColorInput::HEX(r, g, b) => Some((r, g, b)),
         _ => None,
     }
 }
 
-/// Color names map (CSS/SVG color names)
-pub fn names() -> &'static HashMap<String, &'static str> {
-    lazy_static! {
-        static ref NAMES: HashMap<String, &'static str> = {
-            let mut m = HashMap::new();
-            m.insert("aliceblue".to_string(), "f0f8ff");
-            m.insert("antiquewhite".to_string(), "faebd7");
-            m.insert("aqua".to_string(), "0ff");
-            m.insert("aquamarine".to_string(), "7fffd4");
-            m.insert("azure".to_string(), "f0ffff");
-            m.insert("beige".to_string(), "f5f5dc");
-            m.insert("bisque".to_string(), "ffe4c4");
-            m.insert("black".to_string(), "000");
-            m.insert("blanchedalmond".to_string(), "ffebcd");
-            m.insert("blue".to_string(), "00f");
-            m.insert("blueviolet".to_string(), "8a2be2");
-            m.insert("brown".to_string(), "a52a2a");
-            m.insert("burlywood".to_string(), "deb887");
-            m.insert("burntsienna".to_string(), "ea7e5d");
-            m.insert("cadetblue".to_string(), "5f9ea0");
-            m.insert("chartreuse".to_string(), "7fff00");
-            m.insert("chocolate".to_string(), "d2691e");
-            m.insert("coral".to_string(), "ff7f50");
-            m.insert("cornflowerblue".to_string(), "6495ed");
-            m.insert("cornsilk".to_string(), "fff8dc");
-            m.insert("crimson".to_string(), "dc143c");
-            m.insert("cyan".to_string(), "0ff");
-            m.insert("darkblue".to_string(), "00008b");
-            m.insert("darkcyan".to_string(), "008b8b");
-            m.insert("darkgoldenrod".to_string(), "b8860b");
-            m.insert("darkgray".to_string(), "a9a9a9");
-            m.insert("darkgreen".to_string(), "006400");
-            m.insert("darkgrey".to_string(), "a9a9a9");
-            m.insert("darkkhaki".to_string(), "bdb76b");
-            m.insert("darkmagenta".to_string(), "8b008b");
-            m.insert("darkolivegreen".to_string(), "556b2f");
-            m.insert("darkorange".to_string(), "ff8c00");
-            m.insert("darkorchid".to_string(), "9932cc");
-            m.insert("darkred".to_string(), "8b0000");
-            m.insert("darksalmon".to_string(), "e9967a");
-            m.insert("darkseagreen".to_string(), "8fbc8f");
-            m.insert("darkslateblue".to_string(), "483d8b");
-            m.insert("darkslategray".to_string(), "2f4f4f");
-            m.insert("darkslategrey".to_string(), "2f4f4f");
-            m.insert("darkturquoise".to_string(), "00ced1");
-            m.insert("darkviolet".to_string(), "9400d3");
-            m.insert("deeppink".to_string(), "ff1493");
-            m.insert("deepskyblue".to_string(), "00bfff");
-            m.insert("dimgray".to_string(), "696969");
-            m.insert("dimgrey".to_string(), "696969");
-            m.insert("dodgerblue".to_string(), "1e90ff");
-            m.insert("firebrick".to_string(), "b22222");
-            m.insert("floralwhite".to_string(), "fffaf0");
-            m.insert("forestgreen".to_string(), "228b22");
-            m.insert("fuchsia".to_string(), "f0f");
-            m.insert("gainsboro".to_string(), "dcdcdc");
-            m.insert("ghostwhite".to_string(), "f8f8ff");
-            m.insert("gold".to_string(), "ffd700");
-            m.insert("goldenrod".to_string(), "daa520");
-            m.insert("gray".to_string(), "808080");
-            m.insert("green".to_string(), "008000");
-            m.insert("greenyellow".to_string(), "adff2f");
-            m.insert("grey".to_string(), "808080");
-            m.insert("honeydew".to_string(), "f0fff0");
-            m.insert("hotpink".to_string(), "ff69b4");
-            m.insert("indianred".to_string(), "cd5c5c");
-            m.insert("indigo".to_string(), "4b0082");
-            m.insert("ivory".to_string(), "fffff0");
-            m.insert("khaki".to_string(), "f0e68c");
-            m.insert("lavender".to_string(), "e6e6fa");
-            m.insert("lavenderblush".to_string(), "fff0f5");
-            m.insert("lawngreen".to_string(), "7cfc00");
-            m.insert("lemonchiffon".to_string(), "fffacd");
-            m.insert("lightblue".to_string(), "add8e6");
-            m.insert("lightcoral".to_string(), "f08080");
-            m.insert("lightcyan".to_string(), "e0ffff");
-            m.insert("lightgoldenrodyellow".to_string(), "fafad2");
-            m.insert("lightgray".to_string(), "d3d3d3");
-            m.insert("lightgreen".to_string(), "90ee90");
-            m.insert("lightgrey".to_string(), "d3d3d3");
-            m.insert("lightpink".to_string(), "ffb6c1");
-            m.insert("lightsalmon".to_string(), "ffa07a");
-            m.insert("lightseagreen".to_string(), "20b2aa");
-            m.insert("lightskyblue".to_string(), "87cefa");
-            m.insert("lightslategray".to_string(), "789");
-            m.insert("lightslategrey".to_string(), "789");
-            m.insert("lightsteelblue".to_string(), "b0c4de");
-            m.insert("lightyellow".to_string(), "ffffe0");
-            m.insert("lime".to_string(), "0f0");
-            m.insert("limegreen".to_string(), "32cd32");
-            m.insert("linen".to_string(), "faf0e6");
-            m.insert("magenta".to_string(), "f0f");
-            m.insert("maroon".to_string(), "800000");
-            m.insert("mediumaquamarine".to_string(), "66cdaa");
-            m.insert("mediumblue".to_string(), "0000cd");
-            m.insert("mediumorchid".to_string(), "ba55d3");
-            m.insert("mediumpurple".to_string(), "9370db");
-            m.insert("mediumseagreen".to_string(), "3cb371");
-            m.insert("mediumslateblue".to_string(), "7b68ee");
-            m.insert("mediumspringgreen".to_string(), "00fa9a");
-            m.insert("mediumturquoise".to_string(), "48d1cc");
-            m.insert("mediumvioletred".to_string(), "c71585");
-            m.insert("midnightblue".to_string(), "191970");
-            m.insert("mintcream".to_string(), "f5fffa");
-            m.insert("mistyrose".to_string(), "ffe4e1");
-            m.insert("moccasin".to_string(), "ffe4b5");
-            m.insert("navajowhite".to_string(), "ffdead");
-            m.insert("navy".to_string(), "000080");
-            m.insert("oldlace".to_string(), "fdf5e6");
-            m.insert("olive".to_string(), "808000");
-            m.insert("olivedrab".to_string(), "6b8e23");
-            m.insert("orange".to_string(), "ffa500");
-            m.insert("orangered".to_string(), "ff4500");
-            m.insert("orchid".to_string(), "da70d6");
-            m.insert("palegoldenrod".to_string(), "eee8aa");
-            m.insert("palegreen".to_string(), "98fb98");
-            m.insert("paleturquoise".to_string(), "afeeee");
-            m.insert("palevioletred".to_string(), "db7093");
-            m.insert("papayawhip".to_string(), "ffefd5");
-            m.insert("peachpuff".to_string(), "ffdab9");
-            m.insert("peru".to_string(), "cd853f");
-            m.insert("pink".to_string(), "ffc0cb");
-            m.insert("plum".to_string(), "dda0dd");
-            m.insert("powderblue".to_string(), "b0e0e6");
-            m.insert("purple".to_string(), "800080");
-            m.insert("rebeccapurple".to_string(), "663399");
-            m.insert("red".to_string(), "f00");
-            m.insert("rosybrown".to_string(), "bc8f8f");
-            m.insert("royalblue".to_string(), "4169e1");
-            m.insert("saddlebrown".to_string(), "8b4513");
-            m.insert("salmon".to_string(), "fa8072");
-            m.insert("sandybrown".to_string(), "f4a460");
-            m.insert("seagreen".to_string(), "2e8b57");
-            m.insert("seashell".to_string(), "fff5ee");
-            m.insert("sienna".to_string(), "a0522d");
-            m.insert("silver".to_string(), "c0c0c0");
-            m.insert("skyblue".to_string(), "87ceeb");
-            m.insert("slateblue".to_string(), "6a5acd");
-            m.insert("slategray".to_string(), "708090");
-            m.insert("slategrey".to_string(), "708090");
-            m.insert("snow".to_string(), "fffafa");
-            m.insert("springgreen".to_string(), "00ff7f");
-            m.insert("steelblue".to_string(), "4682b4");
-            m.insert("tan".to_string(), "d2b48c");
-            m.insert("teal".to_string(), "008080");
-            m.insert("thistle".to_string(), "d8bfd8");
-            m.insert("tomato".to_string(), "ff6347");
-            m.insert("turquoise".to_string(), "40e0d0");
-            m.insert("violet".to_string(), "ee82ee");
-            m.insert("wheat".to_string(), "f5deb3");
-            m.insert("white".to_string(), "fff");
-            m.insert("whitesmoke".to_string(), "f5f5f5");
-            m.insert("yellow".to_string(), "ff0");
-            m.insert("yellowgreen".to_string(), "9acd32");
-            m
-        };
-    }
-    &NAMES
+/// Returns the hex value of a CSS/SVG color name
+pub fn name_to_hex(name: &str) -> Option<&'static str> {
+    NAMES
+        .binary_search_by(|(candidate, _)| (*candidate).cmp(name))
+        .ok()
+        .map(|index| NAMES[index].1)
 }
 
-/// Hex to name mapping
-pub fn hex_names() -> &'static HashMap<String, &'static str> {
-    lazy_static! {
-        static ref HEX_NAMES: HashMap<String, &'static str> = {
-            let mut m = HashMap::new();
-            for (name, hex) in names().iter() {
-                m.insert(hex.to_string(), name.as_str());
-            }
-            m
-        };
-    }
-    &HEX_NAMES
+/// Returns the first color name, alphabetically, for a hex value in the
+/// shortest form `rgb_to_hex` produces
+pub fn hex_to_name(hex: &str) -> Option<&'static str> {
+    NAMES.iter().find(|(_, value)| *value == hex).map(|(name, _)| *name)
 }
 
+/// CSS/SVG color names and their hex values, sorted by name
+static NAMES: [(&str, &str); 149] = [
+    ("aliceblue", "f0f8ff"),
+    ("antiquewhite", "faebd7"),
+    ("aqua", "0ff"),
+    ("aquamarine", "7fffd4"),
+    ("azure", "f0ffff"),
+    ("beige", "f5f5dc"),
+    ("bisque", "ffe4c4"),
+    ("black", "000"),
+    ("blanchedalmond", "ffebcd"),
+    ("blue", "00f"),
+    ("blueviolet", "8a2be2"),
+    ("brown", "a52a2a"),
+    ("burlywood", "deb887"),
+    ("burntsienna", "ea7e5d"),
+    ("cadetblue", "5f9ea0"),
+    ("chartreuse", "7fff00"),
+    ("chocolate", "d2691e"),
+    ("coral", "ff7f50"),
+    ("cornflowerblue", "6495ed"),
+    ("cornsilk", "fff8dc"),
+    ("crimson", "dc143c"),
+    ("cyan", "0ff"),
+    ("darkblue", "00008b"),
+    ("darkcyan", "008b8b"),
+    ("darkgoldenrod", "b8860b"),
+    ("darkgray", "a9a9a9"),
+    ("darkgreen", "006400"),
+    ("darkgrey", "a9a9a9"),
+    ("darkkhaki", "bdb76b"),
+    ("darkmagenta", "8b008b"),
+    ("darkolivegreen", "556b2f"),
+    ("darkorange", "ff8c00"),
+    ("darkorchid", "9932cc"),
+    ("darkred", "8b0000"),
+    ("darksalmon", "e9967a"),
+    ("darkseagreen", "8fbc8f"),
+    ("darkslateblue", "483d8b"),
+    ("darkslategray", "2f4f4f"),
+    ("darkslategrey", "2f4f4f"),
+    ("darkturquoise", "00ced1"),
+    ("darkviolet", "9400d3"),
+    ("deeppink", "ff1493"),
+    ("deepskyblue", "00bfff"),
+    ("dimgray", "696969"),
+    ("dimgrey", "696969"),
+    ("dodgerblue", "1e90ff"),
+    ("firebrick", "b22222"),
+    ("floralwhite", "fffaf0"),
+    ("forestgreen", "228b22"),
+    ("fuchsia", "f0f"),
+    ("gainsboro", "dcdcdc"),
+    ("ghostwhite", "f8f8ff"),
+    ("gold", "ffd700"),
+    ("goldenrod", "daa520"),
+    ("gray", "808080"),
+    ("green", "008000"),
+    ("greenyellow", "adff2f"),
+    ("grey", "808080"),
+    ("honeydew", "f0fff0"),
+    ("hotpink", "ff69b4"),
+    ("indianred", "cd5c5c"),
+    ("indigo", "4b0082"),
+    ("ivory", "fffff0"),
+    ("khaki", "f0e68c"),
+    ("lavender", "e6e6fa"),
+    ("lavenderblush", "fff0f5"),
+    ("lawngreen", "7cfc00"),
+    ("lemonchiffon", "fffacd"),
+    ("lightblue", "add8e6"),
+    ("lightcoral", "f08080"),
+    ("lightcyan", "e0ffff"),
+    ("lightgoldenrodyellow", "fafad2"),
+    ("lightgray", "d3d3d3"),
+    ("lightgreen", "90ee90"),
+    ("lightgrey", "d3d3d3"),
+    ("lightpink", "ffb6c1"),
+    ("lightsalmon", "ffa07a"),
+    ("lightseagreen", "20b2aa"),
+    ("lightskyblue", "87cefa"),
+    ("lightslategray", "789"),
+    ("lightslategrey", "789"),
+    ("lightsteelblue", "b0c4de"),
+    ("lightyellow", "ffffe0"),
+    ("lime", "0f0"),
+    ("limegreen", "32cd32"),
+    ("linen", "faf0e6"),
+    ("magenta", "f0f"),
+    ("maroon", "800000"),
+    ("mediumaquamarine", "66cdaa"),
+    ("mediumblue", "0000cd"),
+    ("mediumorchid", "ba55d3"),
+    ("mediumpurple", "9370db"),
+    ("mediumseagreen", "3cb371"),
+    ("mediumslateblue", "7b68ee"),
+    ("mediumspringgreen", "00fa9a"),
+    ("mediumturquoise", "48d1cc"),
+    ("mediumvioletred", "c71585"),
+    ("midnightblue", "191970"),
+    ("mintcream", "f5fffa"),
+    ("mistyrose", "ffe4e1"),
+    ("moccasin", "ffe4b5"),
+    ("navajowhite", "ffdead"),
+    ("navy", "000080"),
+    ("oldlace", "fdf5e6"),
+    ("olive", "808000"),
+    ("olivedrab", "6b8e23"),
+    ("orange", "ffa500"),
+    ("orangered", "ff4500"),
+    ("orchid", "da70d6"),
+    ("palegoldenrod", "eee8aa"),
+    ("palegreen", "98fb98"),
+    ("paleturquoise", "afeeee"),
+    ("palevioletred", "db7093"),
+    ("papayawhip", "ffefd5"),
+    ("peachpuff", "ffdab9"),
+    ("peru", "cd853f"),
+    ("pink", "ffc0cb"),
+    ("plum", "dda0dd"),
+    ("powderblue", "b0e0e6"),
+    ("purple", "800080"),
+    ("rebeccapurple", "663399"),
+    ("red", "f00"),
+    ("rosybrown", "bc8f8f"),
+    ("royalblue", "4169e1"),
+    ("saddlebrown", "8b4513"),
+    ("salmon", "fa8072"),
+    ("sandybrown", "f4a460"),
+    ("seagreen", "2e8b57"),
+    ("seashell", "fff5ee"),
+    ("sienna", "a0522d"),
+    ("silver", "c0c0c0"),
+    ("skyblue", "87ceeb"),
+    ("slateblue", "6a5acd"),
+    ("slategray", "708090"),
+    ("slategrey", "708090"),
+    ("snow", "fffafa"),
+    ("springgreen", "00ff7f"),
+    ("steelblue", "4682b4"),
+    ("tan", "d2b48c"),
+    ("teal", "008080"),
+    ("thistle", "d8bfd8"),
+    ("tomato", "ff6347"),
+    ("turquoise", "40e0d0"),
+    ("violet", "ee82ee"),
+    ("wheat", "f5deb3"),
+    ("white", "fff"),
+    ("whitesmoke", "f5f5f5"),
+    ("yellow", "ff0"),
+    ("yellowgreen", "9acd32"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -811,4 +920,32 @@ mod tests {
         assert_eq!(rgb.b, 0);
         assert_eq!(rgb.format, ColorFormat::CMYK);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_alpha_and_names() {
+        let rgb = input_to_rgb("rgba(255, 0, 0, .5)");
+        assert_eq!((rgb.r, rgb.a), (255, 0.5));
+
+        let rgb = input_to_rgb("oklch(0.7 0.1 200 / 0.5)");
+        assert_eq!(rgb.a, 0.5);
+        assert_eq!(rgb.format, ColorFormat::OKLCH);
+
+        assert_eq!(name_to_hex("red"), Some("f00"));
+        assert_eq!(hex_to_name("0ff"), Some("aqua"));
+        assert_eq!(name_to_hex("notacolor"), None);
+    }
+
+    #[test]
+    fn test_declared_channels_keep_wide_gamut_values() {
+        let wide = declared_linear_srgb("oklch(70% 0.3 145)").unwrap();
+        assert!(wide.iter().any(|c| *c < 0.0 || *c > 1.0));
+        let degrees = declared_linear_srgb("oklch(0.7 0.1 180deg)").unwrap();
+        for unit in ["0.5turn", "200grad", "3.14159265rad"] {
+            let other = declared_linear_srgb(&format!("oklch(0.7 0.1 {})", unit)).unwrap();
+            assert!(degrees.iter().zip(other).all(|(a, b)| (a - b).abs() < 1e-4), "{}", unit);
+        }
+        assert_eq!(declared_linear_srgb("LAB(50% 40 none / 0.5)"), declared_linear_srgb("lab(50 40 0)"));
+        assert_eq!(declared_linear_srgb("oklch(70% 0.1 1.2foo)"), None);
+        assert_eq!(declared_linear_srgb("rgb(1 2 3)"), None);
+    }
+} 
@@ -0,0 +1,935 @@
+// Color literal scanner and single-pass rewriter for source text
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use crate::parse::name_to_hex;
+use crate::{BigColor, ColorFormat};
+
+/// Functional notations the scanner looks for, matched case-insensitively
+const COLOR_FUNCTIONS: [&str; 13] = [
+    "rgba", "rgb", "hsla", "hsl", "hsva", "hsv", "hsba", "hsb", "oklab", "oklch", "lab", "lch", "cmyk",
+];
+
+/// Longest functional notation the scanner will consider, in bytes
+const MAX_FUNCTION_LEN: usize = 256;
+
+/// Options controlling which literals the scanner reports
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ScanOptions {
+    /// Report CSS color keywords such as `red` or `transparent`.
+    /// Off by default, since plain words in prose and code are rarely colors.
+    pub named_colors: bool,
+    /// Report space-separated HSL values such as `0 0% 100%`, but only when
+    /// they are the whole value of a declaration (`--background: 0 0% 100%;`)
+    pub space_separated_hsl: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            named_colors: false,
+            space_separated_hsl: true,
+        }
+    }
+}
+
+/// Source languages understood by [`scan_source`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Syntax {
+    /// Any text. Nothing is skipped.
+    Plain,
+    Css,
+    Scss,
+    Less,
+    /// JavaScript or TypeScript. Only string and template literals are scanned.
+    JavaScript,
+}
+
+impl Syntax {
+    /// Picks the syntax for a file extension, falling back to plain text
+    pub fn from_extension(extension: &str) -> Self {
+        match extension.trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "css" => Syntax::Css,
+            "scss" | "sass" => Syntax::Scss,
+            "less" => Syntax::Less,
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Syntax::JavaScript,
+            _ => Syntax::Plain,
+        }
+    }
+
+    fn is_stylesheet(self) -> bool {
+        matches!(self, Syntax::Css | Syntax::Scss | Syntax::Less)
+    }
+}
+
+/// Where a literal sits in source code
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiteralContext {
+    /// An ordinary literal
+    Plain,
+    /// A Tailwind arbitrary value such as `bg-[#1a6ef5]`. The span covers only
+    /// the value, and spaces are written as `_`.
+    TailwindArbitrary,
+    /// A preprocessor helper such as `rgba($brand, .5)`. Its color is resolved
+    /// through a variable, so replacing it drops the reference.
+    Helper,
+}
+
+/// A color literal found in a text
+#[derive(Debug, Clone)]
+pub struct ColorMatch {
+    /// Byte range of the literal in the scanned text
+    pub span: Range<usize>,
+    /// Syntax the literal was written in
+    pub format: ColorFormat,
+    /// The parsed color
+    pub color: BigColor,
+    /// Where the literal sits in source code
+    pub context: LiteralContext,
+}
+
+impl ColorMatch {
+    /// Returns the literal as written in the scanned text
+    pub fn as_str<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.span.clone()]
+    }
+}
+
+/// Returns true for bytes that can be part of an identifier or number
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'-'
+}
+
+/// Returns true if the byte before `i` does not continue a word
+fn at_word_start(bytes: &[u8], i: usize) -> bool {
+    i == 0 || !is_word_byte(bytes[i - 1])
+}
+
+/// Returns true if the `#` at `i` belongs to a URL, an HTML entity or similar
+fn in_url_like_token(text: &str, i: usize) -> bool {
+    let bytes = text.as_bytes();
+    if i > 0 && matches!(bytes[i - 1], b'&' | b'/' | b'.' | b'?' | b'=' | b'%' | b'\\') {
+        return true;
+    }
+
+    // Walk back to the start of the surrounding token
+    let token_start = bytes[..i]
+        .iter()
+        .rposition(|&b| b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b'`' | b'(' | b')' | b'<' | b'>'))
+        .map_or(0, |p| p + 1);
+    let token = &text[token_start..i];
+    if token.contains("://") || token.contains('?') || token.starts_with("www.") {
+        return true;
+    }
+
+    // Fragments inside url(...) are never colors
+    token_start >= 4 && bytes[token_start - 4..token_start].eq_ignore_ascii_case(b"url(")
+}
+
+/// Returns true if the hex literal ending at `end` is followed by a rule body,
+/// which makes it a CSS id selector such as `#fad { ... }`
+fn is_selector(bytes: &[u8], end: usize) -> bool {
+    bytes[end..]
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|&b| b == b'{')
+}
+
+/// Tries to read a hex color starting at the `#` at `i`
+fn scan_hex(text: &str, i: usize) -> Option<ColorMatch> {
+    let bytes = text.as_bytes();
+    if !at_word_start(bytes, i) || in_url_like_token(text, i) {
+        return None;
+    }
+
+    let digits = bytes[i + 1..].iter().take_while(|b| b.is_ascii_hexdigit()).count();
+    let end = i + 1 + digits;
+    if end < bytes.len() && is_word_byte(bytes[end]) {
+        return None;
+    }
+
+    let format = match digits {
+        3 => ColorFormat::HEX3,
+        6 => ColorFormat::HEX6,
+        4 | 8 => ColorFormat::HEX8,
+        _ => return None,
+    };
+    if is_selector(bytes, end) {
+        return None;
+    }
+
+    let color = BigColor::new(&text[i..end]);
+    color.is_valid().then_some(ColorMatch { span: i..end, format, color, context: LiteralContext::Plain })
+}
+
+/// Returns the position of the parenthesis closing the one at `open`
+fn matching_paren(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, &b) in bytes[open..].iter().enumerate().take(MAX_FUNCTION_LEN) {
+        match b {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Tries to read a functional notation such as `rgb(...)` starting at `i`
+fn scan_function(text: &str, i: usize) -> Option<ColorMatch> {
+    let bytes = text.as_bytes();
+    if !at_word_start(bytes, i) {
+        return None;
+    }
+
+    let name = COLOR_FUNCTIONS.iter().find(|name| {
+        bytes.len() >= i + name.len() && bytes[i..i + name.len()].eq_ignore_ascii_case(name.as_bytes())
+    })?;
+
+    // Allow whitespace between the name and the opening parenthesis
+    let mut open = i + name.len();
+    while open < bytes.len() && bytes[open].is_ascii_whitespace() {
+        open += 1;
+    }
+    if open >= bytes.len() || bytes[open] != b'(' {
+        return None;
+    }
+
+    let end = matching_paren(bytes, open)? + 1;
+
+    let literal = &text[i..end];
+    let color = BigColor::new(literal);
+    if !color.is_valid() {
+        return None;
+    }
+
+    let format = match *name {
+        "rgb" | "rgba" if literal.contains('%') => ColorFormat::PRGB,
+        "hsb" | "hsba" => ColorFormat::HSB,
+        _ => color.get_format(),
+    };
+    Some(ColorMatch { span: i..end, format, color, context: LiteralContext::Plain })
+}
+
+/// Tries to read a color keyword starting at `i`
+fn scan_name(text: &str, i: usize) -> Option<ColorMatch> {
+    let bytes = text.as_bytes();
+    if !at_word_start(bytes, i) {
+        return None;
+    }
+
+    let len = bytes[i..].iter().take_while(|b| b.is_ascii_alphabetic()).count();
+    let end = i + len;
+    if len == 0 || (end < bytes.len() && is_word_byte(bytes[end])) {
+        return None;
+    }
+
+    let word = text[i..end].to_ascii_lowercase();
+    if word != "transparent" && name_to_hex(&word).is_none() {
+        return None;
+    }
+
+    let color = BigColor::new(&text[i..end]);
+    color.is_valid().then_some(ColorMatch {
+        span: i..end,
+        format: ColorFormat::NAME,
+        color,
+        context: LiteralContext::Plain,
+    })
+}
+
+/// Returns true if a token is a plain number such as `210` or `12.5`
+fn is_number(token: &str) -> bool {
+    !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        && token.bytes().filter(|&b| b == b'.').count() <= 1
+}
+
+/// Tries to read a space-separated HSL value that forms the whole value of
+/// the declaration whose `:` is at `colon`
+fn scan_space_separated_hsl(text: &str, colon: usize) -> Option<ColorMatch> {
+    let bytes = text.as_bytes();
+    let mut start = colon + 1;
+    while start < bytes.len() && matches!(bytes[start], b' ' | b'\t') {
+        start += 1;
+    }
+
+    let len = bytes[start..]
+        .iter()
+        .take_while(|&&b| !matches!(b, b';' | b'}' | b'\n' | b'\r' | b'!' | b',' | b'"' | b'\''))
+        .count();
+    let value = text[start..start + len].trim_end();
+    let end = start + value.len();
+
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let is_hsl = tokens.len() == 3
+        && is_number(tokens[0])
+        && tokens[1..].iter().all(|t| t.strip_suffix('%').is_some_and(is_number));
+    if !is_hsl {
+        return None;
+    }
+
+    let color = BigColor::new(value);
+    color.is_valid().then_some(ColorMatch {
+        span: start..end,
+        format: ColorFormat::HSL,
+        color,
+        context: LiteralContext::Plain,
+    })
+}
+
+/// Finds every color literal in a text
+///
+/// Matches never overlap and are returned in order. Hex values inside URLs,
+/// HTML entities and CSS id selectors are skipped, as are number triples that
+/// are not the whole value of a declaration.
+pub fn scan(text: &str, options: Option<ScanOptions>) -> Vec<ColorMatch> {
+    let options = options.unwrap_or_default();
+    let bytes = text.as_bytes();
+    let mut matches = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let found = match bytes[i] {
+            b'#' => scan_hex(text, i),
+            b':' if options.space_separated_hsl => scan_space_separated_hsl(text, i),
+            b if b.is_ascii_alphabetic() => scan_function(text, i)
+                .or_else(|| if options.named_colors { scan_name(text, i) } else { None }),
+            _ => None,
+        };
+
+        match found {
+            Some(m) => {
+                i = m.span.end;
+                matches.push(m);
+            }
+            None if bytes[i].is_ascii_alphanumeric() => {
+                // Skip the rest of the word so literals are only found at word starts
+                while i < bytes.len() && is_word_byte(bytes[i]) {
+                    i += 1;
+                }
+            }
+            None => i += 1,
+        }
+    }
+
+    matches
+}
+
+/// Rewrites every color literal in a single pass
+///
+/// The callback receives each match and returns its replacement, or `None`
+/// to keep the literal unchanged. Replacements are never rescanned.
+pub fn rewrite<F>(text: &str, options: Option<ScanOptions>, mut replace: F) -> String
+where
+    F: FnMut(&ColorMatch) -> Option<String>,
+{
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for m in scan(text, options) {
+        if let Some(replacement) = replace(&m) {
+            result.push_str(&text[last..m.span.start]);
+            result.push_str(&replacement);
+            last = m.span.end;
+        }
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+/// Converts every color literal in a text to the target format
+pub fn convert_colors(text: &str, target: ColorFormat, options: Option<ScanOptions>) -> String {
+    rewrite(text, options, |m| Some(m.color.to(target)))
+}
+
+/// A custom property or preprocessor variable definition, or a reference to one
+#[derive(Debug, Clone)]
+pub struct Variable {
+    /// Name including its prefix: `--brand`, `$brand` or `@brand`
+    pub name: String,
+    /// Byte range of the defined value, or of the whole reference such as `var(--brand)`
+    pub span: Range<usize>,
+    /// The color the variable holds, if it resolves to one
+    pub color: Option<BigColor>,
+}
+
+/// The result of scanning source code
+#[derive(Debug, Clone, Default)]
+pub struct SourceScan {
+    /// Color literals in order, including Tailwind values and preprocessor helpers
+    pub matches: Vec<ColorMatch>,
+    /// Custom property and variable definitions in order
+    pub definitions: Vec<Variable>,
+    /// `var()`, `$var` and `@var` references in order
+    pub references: Vec<Variable>,
+}
+
+impl SourceScan {
+    /// Returns the color of the last definition of a variable
+    pub fn variable(&self, name: &str) -> Option<&BigColor> {
+        self.definitions
+            .iter()
+            .rev()
+            .find(|definition| definition.name == name)
+            .and_then(|definition| definition.color.as_ref())
+    }
+}
+
+/// Returns true if `//` at `i` starts a comment rather than belonging to a
+/// value such as `url(http://...)`
+fn starts_line_comment(bytes: &[u8], i: usize) -> bool {
+    i == 0 || bytes[i - 1].is_ascii_whitespace() || matches!(bytes[i - 1], b';' | b'{' | b'}')
+}
+
+/// Returns the position of the quote closing the string that opens at `open`,
+/// or of the line break ending an unterminated string
+fn string_end(bytes: &[u8], open: usize, quote: u8) -> usize {
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' if quote != b'`' => return i,
+            b if b == quote => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Returns the byte ranges of a source text that may hold colors: the code
+/// outside comments and strings for stylesheets, or the contents of string
+/// literals for scripts
+fn scannable_regions(text: &str, syntax: Syntax) -> Vec<Range<usize>> {
+    if syntax == Syntax::Plain {
+        return std::iter::once(0..text.len()).collect();
+    }
+
+    let bytes = text.as_bytes();
+    let scripts = syntax == Syntax::JavaScript;
+    let line_comments = syntax != Syntax::Css;
+    let mut regions = Vec::new();
+    let mut code_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        let skip_to = match bytes[i] {
+            b'/' if next == Some(b'*') => Some(text[i + 2..].find("*/").map_or(bytes.len(), |p| i + p + 4)),
+            b'/' if next == Some(b'/') && line_comments && (scripts || starts_line_comment(bytes, i)) => {
+                Some(text[i..].find('\n').map_or(bytes.len(), |p| i + p))
+            }
+            quote @ (b'"' | b'\'' | b'`') if quote != b'`' || scripts => {
+                let end = string_end(bytes, i, quote);
+                if scripts {
+                    regions.push(i + 1..end);
+                }
+                Some((end + 1).min(bytes.len()))
+            }
+            _ => None,
+        };
+
+        match skip_to {
+            Some(end) => {
+                if !scripts && code_start < i {
+                    regions.push(code_start..i);
+                }
+                i = end;
+                code_start = end;
+            }
+            None => i += 1,
+        }
+    }
+
+    if !scripts && code_start < bytes.len() {
+        regions.push(code_start..bytes.len());
+    }
+    regions
+}
+
+/// Tries to read a Tailwind arbitrary value such as `bg-[#1a6ef5]` whose `[` is at `open`
+fn scan_tailwind(text: &str, open: usize) -> Option<ColorMatch> {
+    let bytes = text.as_bytes();
+    if open < 2 || bytes[open - 1] != b'-' || !bytes[open - 2].is_ascii_alphanumeric() {
+        return None;
+    }
+
+    let len = bytes[open + 1..]
+        .iter()
+        .take_while(|&&b| b != b']' && !b.is_ascii_whitespace() && !matches!(b, b'"' | b'\'' | b'`'))
+        .count();
+    let close = open + 1 + len;
+    if close >= bytes.len() || bytes[close] != b']' {
+        return None;
+    }
+
+    // Values may carry a `color:` type hint, and Tailwind writes spaces as underscores
+    let start = open + 1 + if text[open + 1..close].starts_with("color:") { 6 } else { 0 };
+    let value = text[start..close].replace('_', " ");
+    let options = ScanOptions {
+        named_colors: true,
+        space_separated_hsl: false,
+    };
+    let found = scan(&value, Some(options))
+        .into_iter()
+        .next()
+        .filter(|m| m.span == (0..value.len()))?;
+
+    Some(ColorMatch {
+        span: start..close,
+        context: LiteralContext::TailwindArbitrary,
+        ..found
+    })
+}
+
+/// Returns the end of the identifier starting at `i`
+fn identifier_end(bytes: &[u8], i: usize) -> usize {
+    i + bytes[i..].iter().take_while(|&&b| is_word_byte(b)).count()
+}
+
+/// Returns the range of a declaration's value if the variable name spanning
+/// `name` is followed by a colon and starts a declaration
+fn declaration_value(text: &str, name: Range<usize>) -> Option<Range<usize>> {
+    let bytes = text.as_bytes();
+    let before = bytes[..name.start].iter().rposition(|b| !b.is_ascii_whitespace());
+    if before.is_some_and(|p| !matches!(bytes[p], b'{' | b'}' | b';' | b'(' | b',')) {
+        return None;
+    }
+
+    let colon = name.end + bytes[name.end..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+    if bytes.get(colon) != Some(&b':') {
+        return None;
+    }
+
+    let start = colon + 1 + bytes[colon + 1..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+    let mut depth = 0;
+    let mut end = start;
+    while end < bytes.len() {
+        match bytes[end] {
+            b'(' => depth += 1,
+            b')' if depth > 0 => depth -= 1,
+            b';' | b'}' | b'{' | b')' | b'!' if depth == 0 => break,
+            _ => {}
+        }
+        end += 1;
+    }
+
+    let value = text[start..end].trim_end();
+    (!value.is_empty()).then_some(start..start + value.len())
+}
+
+/// Tries to read a `var(--name)` reference starting at `i`
+fn scan_var(text: &str, i: usize) -> Option<(String, Range<usize>)> {
+    let bytes = text.as_bytes();
+    if !at_word_start(bytes, i) || !bytes[i..].starts_with(b"var(") {
+        return None;
+    }
+
+    let name_start = i + 4 + bytes[i + 4..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+    if !bytes[name_start..].starts_with(b"--") {
+        return None;
+    }
+    let name_end = identifier_end(bytes, name_start);
+    let close = matching_paren(bytes, i + 3)?;
+    Some((text[name_start..name_end].to_string(), i..close + 1))
+}
+
+/// The color argument of a preprocessor helper
+enum HelperColor {
+    Variable(String),
+    Literal(BigColor),
+}
+
+/// A call such as `rgba($brand, .5)` or Less `fade(@brand, 50%)`
+struct HelperCall {
+    span: Range<usize>,
+    color: HelperColor,
+    alpha: f32,
+}
+
+/// Parses an alpha argument written as a fraction or a percentage
+fn parse_alpha(value: &str) -> Option<f32> {
+    let alpha = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+        None => value.parse::<f32>().ok()?,
+    };
+    (0.0..=1.0).contains(&alpha).then_some(alpha)
+}
+
+/// Tries to read a preprocessor helper that sets the alpha of a color, starting at `i`
+fn scan_helper(text: &str, i: usize, syntax: Syntax) -> Option<HelperCall> {
+    let bytes = text.as_bytes();
+    if !at_word_start(bytes, i) {
+        return None;
+    }
+
+    let names: &[&str] = if syntax == Syntax::Less { &["rgba", "rgb", "fade"] } else { &["rgba", "rgb"] };
+    let name = names.iter().find(|name| bytes[i..].starts_with(name.as_bytes()))?;
+    let open = i + name.len();
+    if bytes.get(open) != Some(&b'(') {
+        return None;
+    }
+    let close = matching_paren(bytes, open)?;
+
+    let args: Vec<&str> = text[open + 1..close].split(',').map(str::trim).collect();
+    if args.len() != 2 {
+        return None;
+    }
+    let prefix = if syntax == Syntax::Less { '@' } else { '$' };
+    let color = if args[0].starts_with(prefix) {
+        HelperColor::Variable(args[0].to_string())
+    } else {
+        let literal = BigColor::new(args[0]);
+        if !literal.is_valid() {
+            return None;
+        }
+        HelperColor::Literal(literal)
+    };
+
+    Some(HelperCall {
+        span: i..close + 1,
+        color,
+        alpha: parse_alpha(args[1])?,
+    })
+}
+
+/// Variables, references and helpers found in one region, before resolution
+#[derive(Default)]
+struct SourceStructure {
+    tailwind: Vec<ColorMatch>,
+    declarations: Vec<(String, Range<usize>)>,
+    references: Vec<(String, Range<usize>)>,
+    helpers: Vec<HelperCall>,
+}
+
+/// Collects the stylesheet structure and Tailwind values of one region
+fn scan_structure(text: &str, syntax: Syntax) -> SourceStructure {
+    let bytes = text.as_bytes();
+    let mut structure = SourceStructure::default();
+    let variable_prefix = match syntax {
+        Syntax::Scss => Some(b'$'),
+        Syntax::Less => Some(b'@'),
+        _ => None,
+    };
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'[' {
+            if let Some(m) = scan_tailwind(text, i) {
+                i = m.span.end;
+                structure.tailwind.push(m);
+                continue;
+            }
+        } else if syntax.is_stylesheet() && b == b'-' && bytes[i..].starts_with(b"--") && at_word_start(bytes, i) {
+            let end = identifier_end(bytes, i);
+            if let Some(value) = declaration_value(text, i..end) {
+                structure.declarations.push((text[i..end].to_string(), value));
+            }
+            i = end;
+            continue;
+        } else if Some(b) == variable_prefix && bytes.get(i + 1).is_some_and(|b| b.is_ascii_alphabetic()) {
+            let end = identifier_end(bytes, i + 1);
+            let name = text[i..end].to_string();
+            match declaration_value(text, i..end) {
+                Some(value) => structure.declarations.push((name, value)),
+                None => structure.references.push((name, i..end)),
+            }
+            i = end;
+            continue;
+        } else if syntax.is_stylesheet() && b.is_ascii_alphabetic() {
+            if let Some((name, span)) = scan_var(text, i) {
+                structure.references.push((name, span));
+            } else if let Some(helper) = scan_helper(text, i, syntax).filter(|_| variable_prefix.is_some()) {
+                i = helper.span.end;
+                structure.helpers.push(helper);
+                continue;
+            }
+            i = identifier_end(bytes, i);
+            continue;
+        }
+        i += 1;
+    }
+
+    structure
+}
+
+/// Shifts a range by an offset
+fn shift(range: &Range<usize>, offset: usize) -> Range<usize> {
+    range.start + offset..range.end + offset
+}
+
+/// Finds color literals in source code of the given syntax
+///
+/// Stylesheets are scanned outside comments and strings, scripts only inside
+/// string and template literals. Tailwind arbitrary values are recognized in
+/// every syntax. For stylesheets, custom property and variable definitions and
+/// references are collected and resolved, and SCSS/Less helpers that set the
+/// alpha of a variable, such as `rgba($brand, .5)`, are reported as one match.
+pub fn scan_source(text: &str, syntax: Syntax, options: Option<ScanOptions>) -> SourceScan {
+    let mut matches = Vec::new();
+    let mut declarations = Vec::new();
+    let mut references = Vec::new();
+    let mut helpers = Vec::new();
+
+    for region in scannable_regions(text, syntax) {
+        let source = &text[region.clone()];
+        let structure = scan_structure(source, syntax);
+        let offset = region.start;
+
+        let taken: Vec<Range<usize>> = structure
+            .tailwind
+            .iter()
+            .map(|m| m.span.clone())
+            .chain(structure.helpers.iter().map(|h| h.span.clone()))
+            .collect();
+        let overlaps = |span: &Range<usize>| taken.iter().any(|t| span.start < t.end && t.start < span.end);
+
+        for mut m in scan(source, options).into_iter().filter(|m| !overlaps(&m.span)) {
+            m.span = shift(&m.span, offset);
+            matches.push(m);
+        }
+        for mut m in structure.tailwind {
+            m.span = shift(&m.span, offset);
+            matches.push(m);
+        }
+        declarations.extend(structure.declarations.into_iter().map(|(name, value)| (name, shift(&value, offset))));
+        references.extend(structure.references.into_iter().map(|(name, span)| (name, shift(&span, offset))));
+        helpers.extend(structure.helpers.into_iter().map(|h| HelperCall { span: shift(&h.span, offset), ..h }));
+    }
+
+    // Less shares `@` with at-rules, so only names defined in the file are references
+    if syntax == Syntax::Less {
+        let defined: HashSet<&str> = declarations.iter().map(|(name, _)| name.as_str()).collect();
+        references.retain(|(name, _)| !name.starts_with('@') || defined.contains(name.as_str()));
+    }
+
+    // Resolve in source order: a definition is complete at the end of its value
+    enum Event {
+        Helper(usize),
+        Reference(usize),
+        Definition(usize),
+    }
+    let mut events: Vec<(usize, Event)> = helpers
+        .iter()
+        .enumerate()
+        .map(|(index, h)| (h.span.start, Event::Helper(index)))
+        .chain(references.iter().enumerate().map(|(index, (_, span))| (span.start, Event::Reference(index))))
+        .chain(declarations.iter().enumerate().map(|(index, (_, value))| (value.end, Event::Definition(index))))
+        .collect();
+    events.sort_by_key(|(position, _)| *position);
+
+    let mut values: HashMap<String, BigColor> = HashMap::new();
+    let mut resolved_references: Vec<Option<BigColor>> = vec![None; references.len()];
+    let mut definitions = Vec::with_capacity(declarations.len());
+
+    for (_, event) in events {
+        match event {
+            Event::Helper(index) => {
+                let helper = &helpers[index];
+                let color = match &helper.color {
+                    HelperColor::Variable(name) => values.get(name).cloned(),
+                    HelperColor::Literal(color) => Some(color.clone()),
+                };
+                if let Some(mut color) = color {
+                    color.set_alpha(helper.alpha);
+                    matches.push(ColorMatch {
+                        span: helper.span.clone(),
+                        format: ColorFormat::RGB,
+                        color,
+                        context: LiteralContext::Helper,
+                    });
+                }
+            }
+            Event::Reference(index) => {
+                resolved_references[index] = values.get(&references[index].0).cloned();
+            }
+            Event::Definition(index) => {
+                let (name, value) = &declarations[index];
+                let color = matches
+                    .iter()
+                    .find(|m| m.span == *value)
+                    .map(|m| m.color.clone())
+                    .or_else(|| {
+                        let position = references.iter().position(|(_, span)| span == value)?;
+                        resolved_references[position].clone()
+                    });
+                if let Some(color) = &color {
+                    values.insert(name.clone(), color.clone());
+                }
+                definitions.push(Variable {
+                    name: name.clone(),
+                    span: value.clone(),
+                    color,
+                });
+            }
+        }
+    }
+
+    // Custom properties resolve regardless of order, so fall back to the last definition
+    let references = references
+        .into_iter()
+        .zip(resolved_references)
+        .map(|((name, span), color)| Variable {
+            color: color.or_else(|| values.get(&name).cloned()),
+            name,
+            span,
+        })
+        .collect();
+
+    matches.sort_by_key(|m| m.span.start);
+    SourceScan {
+        matches,
+        definitions,
+        references,
+    }
+}
+
+/// Rewrites every color literal in source code in a single pass
+///
+/// Works like [`rewrite`] on the matches of [`scan_source`]. Spaces in
+/// replacements of Tailwind arbitrary values are written as `_`.
+pub fn rewrite_source<F>(text: &str, syntax: Syntax, options: Option<ScanOptions>, mut replace: F) -> String
+where
+    F: FnMut(&ColorMatch) -> Option<String>,
+{
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for m in scan_source(text, syntax, options).matches {
+        if let Some(mut replacement) = replace(&m) {
+            if m.context == LiteralContext::TailwindArbitrary {
+                replacement = replacement.replace(' ', "_");
+            }
+            result.push_str(&text[last..m.span.start]);
+            result.push_str(&replacement);
+            last = m.span.end;
+        }
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+/// Converts every color literal in source code to the target format
+///
+/// Preprocessor helpers are left alone so they keep referring to their variable.
+pub fn convert_source(text: &str, syntax: Syntax, target: ColorFormat, options: Option<ScanOptions>) -> String {
+    rewrite_source(text, syntax, options, |m| {
+        (m.context != LiteralContext::Helper).then(|| m.color.to(target))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literals(text: &str, options: Option<ScanOptions>) -> Vec<&str> {
+        scan(text, options).iter().map(|m| m.as_str(text)).collect()
+    }
+
+    #[test]
+    fn test_finds_literals_with_spans_and_formats() {
+        let text = "a { color: #f00; background: rgba(0, 0, 255, 0.5); border-color: oklch(50% 0.1 200); }";
+        let found = scan(text, None);
+        assert_eq!(found.len(), 3);
+        assert_eq!(&text[found[0].span.clone()], "#f00");
+        assert_eq!(found[0].format, ColorFormat::HEX3);
+        assert_eq!(found[1].format, ColorFormat::RGB);
+        assert_eq!(found[2].format, ColorFormat::OKLCH);
+    }
+
+    #[test]
+    fn test_skips_false_positives() {
+        let text = "see https://example.com/page#fff or url(icons.svg#add), &#123; and #fad { color: red }";
+        assert!(literals(text, None).is_empty());
+
+        // Number triples only count as the whole value of a declaration
+        assert!(literals("move 12 50% 30% of the way", None).is_empty());
+        assert_eq!(literals("--background: 0 0% 100%;", None), vec!["0 0% 100%"]);
+    }
+
+    #[test]
+    fn test_named_colors_are_opt_in() {
+        let text = "color: red;";
+        assert!(literals(text, None).is_empty());
+        let options = ScanOptions { named_colors: true, ..Default::default() };
+        assert_eq!(literals(text, Some(options)), vec!["red"]);
+    }
+
+    #[test]
+    fn test_rewrite_is_single_pass() {
+        // Converting to HEX must not re-convert the output of an earlier match
+        let text = "rgb(255, 0, 0) #00ff00 hsl(240, 100%, 50%)";
+        assert_eq!(convert_colors(text, ColorFormat::HEX, None), "#ff0000 #00ff00 #0000ff");
+
+        let only_hex = rewrite(text, None, |m| {
+            matches!(m.format, ColorFormat::HEX6).then(|| m.color.to(ColorFormat::RGB))
+        });
+        assert_eq!(only_hex, "rgb(255, 0, 0) rgb(0, 255, 0) hsl(240, 100%, 50%)");
+    }
+
+    #[test]
+    fn test_source_skips_comments_and_strings() {
+        let css = "/* was #ff0000 */ a { color: #00f; content: \"#abc\"; }";
+        let found = scan_source(css, Syntax::Css, None);
+        assert_eq!(found.matches.iter().map(|m| m.as_str(css)).collect::<Vec<_>>(), vec!["#00f"]);
+
+        // Scripts are scanned only inside strings
+        let js = "// #123456\nconst theme = { accent: '#1a6ef5', border: `rgb(0, 0, 0)` }; el.id = x #fff;";
+        let found = scan_source(js, Syntax::JavaScript, None);
+        let literals: Vec<&str> = found.matches.iter().map(|m| m.as_str(js)).collect();
+        assert_eq!(literals, vec!["#1a6ef5", "rgb(0, 0, 0)"]);
+    }
+
+    #[test]
+    fn test_source_resolves_variables_and_helpers() {
+        let css = ":root { --brand: #1a6ef5; --link: var(--brand); } a { color: var(--brand, #000); }";
+        let found = scan_source(css, Syntax::Css, None);
+        assert_eq!(found.definitions.len(), 2);
+        assert_eq!(found.variable("--link").unwrap().to_hex_string(false), "#1a6ef5");
+        assert_eq!(found.references.len(), 2);
+        assert!(found.references.iter().all(|r| r.color.is_some()));
+
+        let scss = "$brand: #1a6ef5 !default;\n// $old: #000;\n.overlay { background: rgba($brand, .5); border: 1px solid $brand; }";
+        let found = scan_source(scss, Syntax::Scss, None);
+        let helper = found.matches.iter().find(|m| m.context == LiteralContext::Helper).unwrap();
+        assert_eq!(helper.as_str(scss), "rgba($brand, .5)");
+        assert_eq!(helper.color.get_alpha(), 0.5);
+        assert_eq!(found.definitions.len(), 1);
+        assert_eq!(found.references.len(), 1);
+
+        let less = "@media screen { @brand: #1a6ef5; a { color: fade(@brand, 50%); } }";
+        let found = scan_source(less, Syntax::Less, None);
+        assert_eq!(found.definitions[0].name, "@brand");
+        assert!(found.references.is_empty());
+        assert!(found.matches.iter().any(|m| m.context == LiteralContext::Helper));
+    }
+
+    #[test]
+    fn test_source_rewrites_tailwind_and_keeps_helpers() {
+        let html = r#"<div class="bg-[#ff0000] text-[rgb(0,_0,_255)] p-[4px]">"#;
+        let found = scan_source(html, Syntax::Plain, None);
+        assert_eq!(found.matches.len(), 2);
+        assert!(found.matches.iter().all(|m| m.context == LiteralContext::TailwindArbitrary));
+        assert_eq!(
+            convert_source(html, Syntax::Plain, ColorFormat::RGB, None),
+            r#"<div class="bg-[rgb(255,_0,_0)] text-[rgb(0,_0,_255)] p-[4px]">"#
+        );
+
+        let scss = "$a: #fff; b { color: rgba($a, .5); }";
+        assert_eq!(
+            convert_source(scss, Syntax::Scss, ColorFormat::RGB, None),
+            "$a: rgb(255, 255, 255); b { color: rgba($a, .5); }"
+        );
+    }
+}
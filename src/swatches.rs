@@ -0,0 +1,734 @@
+// Swatch file formats: Adobe ASE, Photoshop ACO, GIMP GPL, Paint.NET and LibreOffice SOC
+
+use std::fmt;
+
+use crate::color_space::*;
+use crate::{BigColor, ColorFormat};
+
+/// Palette file formats understood by [`read_palette`] and [`write_palette`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwatchFormat {
+    /// Adobe Swatch Exchange (`.ase`)
+    Ase,
+    /// Photoshop color swatches (`.aco`)
+    Aco,
+    /// GIMP palette (`.gpl`)
+    Gpl,
+    /// Paint.NET palette (`.txt`)
+    PaintNet,
+    /// LibreOffice / OpenOffice color table (`.soc`)
+    Soc,
+}
+
+impl SwatchFormat {
+    /// Guesses the format from a file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "ase" => Some(SwatchFormat::Ase),
+            "aco" => Some(SwatchFormat::Aco),
+            "gpl" => Some(SwatchFormat::Gpl),
+            "txt" => Some(SwatchFormat::PaintNet),
+            "soc" => Some(SwatchFormat::Soc),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the start of a file
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        let text = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]);
+        if bytes.starts_with(b"ASEF") {
+            Some(SwatchFormat::Ase)
+        } else if text.starts_with("GIMP Palette") {
+            Some(SwatchFormat::Gpl)
+        } else if text.contains("color-table") {
+            Some(SwatchFormat::Soc)
+        } else if bytes.len() >= 4 && bytes[0] == 0 && matches!(bytes[1], 1 | 2) {
+            Some(SwatchFormat::Aco)
+        } else if text.trim_start().starts_with(';') {
+            Some(SwatchFormat::PaintNet)
+        } else {
+            None
+        }
+    }
+}
+
+/// A swatch's color as stored in the file, so it can be written back without
+/// being forced through sRGB
+#[derive(Debug, Clone, Copy)]
+pub enum SourceColor {
+    /// sRGB channels from 0.0 to 1.0
+    Rgb([f32; 3]),
+    /// Ink coverage from 0 to 100
+    Cmyk(CMYK),
+    /// CIE Lab (D50)
+    Lab(Lab),
+    /// Grey level from 0.0 (black) to 1.0 (white)
+    Gray(f32),
+}
+
+impl SourceColor {
+    /// Converts the stored color to a BigColor
+    pub fn to_color(&self) -> BigColor {
+        match *self {
+            SourceColor::Rgb(rgb) => {
+                let xyz = linear_srgb_to_xyz_d65(rgb.map(|c| srgb_to_linear(clamp_01(c))), 1.0);
+                BigColor::from_xyz_d65(xyz, ColorFormat::HEX)
+            }
+            SourceColor::Cmyk(cmyk) => BigColor::from_cmyk(cmyk.c, cmyk.m, cmyk.y, cmyk.k, 1.0),
+            SourceColor::Lab(lab) => {
+                let xyz = xyz_d50_to_xyz_d65(lab_to_xyz_d50(Lab { alpha: 1.0, ..lab }));
+                BigColor::from_xyz_d65(xyz, ColorFormat::LAB)
+            }
+            SourceColor::Gray(value) => SourceColor::Rgb([value; 3]).to_color(),
+        }
+    }
+
+    /// Returns sRGB channels from 0.0 to 1.0
+    fn to_rgb(self) -> [f32; 3] {
+        match self {
+            SourceColor::Rgb(rgb) => rgb,
+            SourceColor::Gray(value) => [value; 3],
+            other => rgb_channels(&other.to_color()),
+        }
+    }
+}
+
+/// Returns the sRGB channels of a color from 0.0 to 1.0
+fn rgb_channels(color: &BigColor) -> [f32; 3] {
+    oklch_to_linear_srgb(color.to_oklch()).map(|c| clamp_01(linear_to_srgb(c)))
+}
+
+/// A named color in a palette
+#[derive(Debug, Clone)]
+pub struct Swatch {
+    pub name: Option<String>,
+    pub color: BigColor,
+    /// The color in the model the file stored it in
+    pub source: SourceColor,
+}
+
+impl Swatch {
+    /// Creates an sRGB swatch from a color
+    pub fn new(name: Option<&str>, color: BigColor) -> Self {
+        Swatch {
+            name: name.map(str::to_string),
+            source: SourceColor::Rgb(rgb_channels(&color)),
+            color,
+        }
+    }
+
+    /// Creates a swatch that keeps its stored model
+    pub fn from_source(name: Option<&str>, source: SourceColor) -> Self {
+        Swatch {
+            name: name.map(str::to_string),
+            color: source.to_color(),
+            source,
+        }
+    }
+
+    /// Returns 8-bit sRGB channels
+    fn rgb8(&self) -> [u8; 3] {
+        self.source.to_rgb().map(|c| (c * 255.0).round() as u8)
+    }
+}
+
+/// A named group of swatches, as found in ASE files
+#[derive(Debug, Clone)]
+pub struct SwatchGroup {
+    pub name: String,
+    pub swatches: Vec<Swatch>,
+}
+
+/// A named collection of swatches
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    pub name: Option<String>,
+    /// Swatches outside any group
+    pub swatches: Vec<Swatch>,
+    pub groups: Vec<SwatchGroup>,
+}
+
+impl Palette {
+    /// Creates a palette of unnamed sRGB swatches
+    pub fn from_colors(name: Option<&str>, colors: &[BigColor]) -> Self {
+        Palette {
+            name: name.map(str::to_string),
+            swatches: colors.iter().map(|color| Swatch::new(None, color.clone())).collect(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Iterates over every swatch, ungrouped ones first
+    pub fn all_swatches(&self) -> impl Iterator<Item = &Swatch> {
+        self.swatches
+            .iter()
+            .chain(self.groups.iter().flat_map(|group| group.swatches.iter()))
+    }
+
+    /// Returns the colors of every swatch
+    pub fn colors(&self) -> Vec<BigColor> {
+        self.all_swatches().map(|swatch| swatch.color.clone()).collect()
+    }
+
+    /// Returns the swatch closest to a color with its distance (ΔEOK)
+    pub fn nearest(&self, color: &BigColor) -> Option<(&Swatch, f32)> {
+        self.all_swatches()
+            .map(|swatch| (swatch, swatch.color.delta_e(color)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Errors raised while reading a palette file
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwatchError {
+    /// The file ended in the middle of a record
+    UnexpectedEnd,
+    /// The file does not start with the expected header
+    InvalidHeader(SwatchFormat),
+    /// A color model or color space the format allows but we cannot read
+    UnsupportedModel(String),
+    /// A malformed line or record
+    Invalid(String),
+}
+
+impl fmt::Display for SwatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SwatchError::UnexpectedEnd => write!(f, "unexpected end of file"),
+            SwatchError::InvalidHeader(format) => write!(f, "not a {:?} file", format),
+            SwatchError::UnsupportedModel(model) => write!(f, "unsupported color model {}", model),
+            SwatchError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SwatchError {}
+
+/// Reads a palette file
+pub fn read_palette(bytes: &[u8], format: SwatchFormat) -> Result<Palette, SwatchError> {
+    match format {
+        SwatchFormat::Ase => read_ase(bytes),
+        SwatchFormat::Aco => read_aco(bytes),
+        SwatchFormat::Gpl => read_gpl(&String::from_utf8_lossy(bytes)),
+        SwatchFormat::PaintNet => read_paint_net(&String::from_utf8_lossy(bytes)),
+        SwatchFormat::Soc => read_soc(&String::from_utf8_lossy(bytes)),
+    }
+}
+
+/// Writes a palette file
+///
+/// ASE and ACO keep each swatch's source model. The text formats only hold
+/// sRGB, and formats without groups list grouped swatches after the others.
+pub fn write_palette(palette: &Palette, format: SwatchFormat) -> Vec<u8> {
+    match format {
+        SwatchFormat::Ase => write_ase(palette),
+        SwatchFormat::Aco => write_aco(palette),
+        SwatchFormat::Gpl => write_gpl(palette).into_bytes(),
+        SwatchFormat::PaintNet => write_paint_net(palette).into_bytes(),
+        SwatchFormat::Soc => write_soc(palette).into_bytes(),
+    }
+}
+
+/// Big-endian reader over a byte slice
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SwatchError> {
+        let end = self.position.checked_add(len).ok_or(SwatchError::UnexpectedEnd)?;
+        let slice = self.bytes.get(self.position..end).ok_or(SwatchError::UnexpectedEnd)?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, SwatchError> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, SwatchError> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, SwatchError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    /// Reads `len` UTF-16 code units, dropping a trailing null
+    fn utf16(&mut self, len: usize) -> Result<String, SwatchError> {
+        let units: Vec<u16> = self
+            .take(len * 2)?
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        let units = units.strip_suffix(&[0]).unwrap_or(&units);
+        String::from_utf16(units).map_err(|_| SwatchError::Invalid("invalid UTF-16 name".to_string()))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+}
+
+/// Appends a null-terminated UTF-16 string, without its length
+fn push_utf16(out: &mut Vec<u8>, text: &str) {
+    for unit in text.encode_utf16().chain([0]) {
+        out.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+/// Returns the length of a null-terminated UTF-16 string in code units
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count() + 1
+}
+
+// ASE block types
+const ASE_GROUP_START: u16 = 0xc001;
+const ASE_GROUP_END: u16 = 0xc002;
+const ASE_COLOR: u16 = 0x0001;
+
+fn read_ase(bytes: &[u8]) -> Result<Palette, SwatchError> {
+    let mut reader = Reader::new(bytes);
+    if reader.take(4)? != b"ASEF" {
+        return Err(SwatchError::InvalidHeader(SwatchFormat::Ase));
+    }
+    reader.take(4)?; // version 1.0
+    let blocks = reader.u32()?;
+
+    let mut palette = Palette::default();
+    let mut group: Option<SwatchGroup> = None;
+
+    for _ in 0..blocks {
+        let block_type = reader.u16()?;
+        let len = reader.u32()? as usize;
+        let mut block = Reader::new(reader.take(len)?);
+
+        match block_type {
+            ASE_GROUP_START => {
+                let name_len = block.u16()? as usize;
+                let name = block.utf16(name_len)?;
+                if let Some(open) = group.replace(SwatchGroup { name, swatches: Vec::new() }) {
+                    palette.groups.push(open);
+                }
+            }
+            ASE_GROUP_END => palette.groups.extend(group.take()),
+            ASE_COLOR => {
+                let name_len = block.u16()? as usize;
+                let name = block.utf16(name_len)?;
+                let model = block.take(4)?;
+                let source = match model {
+                    b"RGB " => SourceColor::Rgb([block.f32()?, block.f32()?, block.f32()?]),
+                    b"CMYK" => {
+                        let [c, m, y, k] = [block.f32()?, block.f32()?, block.f32()?, block.f32()?].map(|v| v * 100.0);
+                        SourceColor::Cmyk(CMYK { c, m, y, k, a: 1.0 })
+                    }
+                    // Lightness is stored from 0.0 to 1.0, a and b as they are
+                    b"LAB " => SourceColor::Lab(Lab {
+                        l: block.f32()? * 100.0,
+                        a: block.f32()?,
+                        b: block.f32()?,
+                        alpha: 1.0,
+                    }),
+                    b"Gray" => SourceColor::Gray(block.f32()?),
+                    other => return Err(SwatchError::UnsupportedModel(String::from_utf8_lossy(other).into_owned())),
+                };
+                let swatch = Swatch::from_source(Some(&name).filter(|n| !n.is_empty()).map(|n| n.as_str()), source);
+                match &mut group {
+                    Some(group) => group.swatches.push(swatch),
+                    None => palette.swatches.push(swatch),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    palette.groups.extend(group);
+    Ok(palette)
+}
+
+/// Encodes one ASE color entry
+fn ase_color_block(swatch: &Swatch) -> Vec<u8> {
+    let name = swatch.name.as_deref().unwrap_or("");
+    let mut block = Vec::new();
+    block.extend_from_slice(&(utf16_len(name) as u16).to_be_bytes());
+    push_utf16(&mut block, name);
+
+    let (model, values): (&[u8; 4], Vec<f32>) = match swatch.source {
+        SourceColor::Rgb(rgb) => (b"RGB ", rgb.to_vec()),
+        SourceColor::Cmyk(cmyk) => (b"CMYK", [cmyk.c, cmyk.m, cmyk.y, cmyk.k].map(|v| v / 100.0).to_vec()),
+        SourceColor::Lab(lab) => (b"LAB ", vec![lab.l / 100.0, lab.a, lab.b]),
+        SourceColor::Gray(value) => (b"Gray", vec![value]),
+    };
+    block.extend_from_slice(model);
+    for value in values {
+        block.extend_from_slice(&value.to_be_bytes());
+    }
+    block.extend_from_slice(&2u16.to_be_bytes()); // normal (not global or spot) color
+    block
+}
+
+fn write_ase(palette: &Palette) -> Vec<u8> {
+    let mut blocks: Vec<(u16, Vec<u8>)> = palette
+        .swatches
+        .iter()
+        .map(|swatch| (ASE_COLOR, ase_color_block(swatch)))
+        .collect();
+    for group in &palette.groups {
+        let mut start = Vec::new();
+        start.extend_from_slice(&(utf16_len(&group.name) as u16).to_be_bytes());
+        push_utf16(&mut start, &group.name);
+        blocks.push((ASE_GROUP_START, start));
+        blocks.extend(group.swatches.iter().map(|swatch| (ASE_COLOR, ase_color_block(swatch))));
+        blocks.push((ASE_GROUP_END, Vec::new()));
+    }
+
+    let mut out = b"ASEF".to_vec();
+    out.extend_from_slice(&[0, 1, 0, 0]);
+    out.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+    for (block_type, data) in blocks {
+        out.extend_from_slice(&block_type.to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(&data);
+    }
+    out
+}
+
+// ACO color spaces
+const ACO_RGB: u16 = 0;
+const ACO_HSB: u16 = 1;
+const ACO_CMYK: u16 = 2;
+const ACO_LAB: u16 = 7;
+const ACO_GRAY: u16 = 8;
+
+/// Decodes one ACO color record
+fn read_aco_color(reader: &mut Reader) -> Result<SourceColor, SwatchError> {
+    let space = reader.u16()?;
+    let [w, x, y, z] = [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+    let unit = |v: u16| v as f32 / 65535.0;
+
+    Ok(match space {
+        ACO_RGB => SourceColor::Rgb([unit(w), unit(x), unit(y)]),
+        ACO_HSB => {
+            let rgb = hsv_to_rgb(unit(w), unit(x), unit(y));
+            SourceColor::Rgb([rgb.r, rgb.g, rgb.b].map(|c| c as f32 / 255.0))
+        }
+        // Photoshop stores ink inverted: 0 is full coverage
+        ACO_CMYK => {
+            let [c, m, y, k] = [w, x, y, z].map(|v| (1.0 - unit(v)) * 100.0);
+            SourceColor::Cmyk(CMYK { c, m, y, k, a: 1.0 })
+        }
+        ACO_LAB => SourceColor::Lab(Lab {
+            l: w as f32 / 100.0,
+            a: x as i16 as f32 / 100.0,
+            b: y as i16 as f32 / 100.0,
+            alpha: 1.0,
+        }),
+        // Grey is stored as ink from 0 (white) to 10000 (black)
+        ACO_GRAY => SourceColor::Gray(1.0 - (w as f32 / 10000.0).min(1.0)),
+        other => return Err(SwatchError::UnsupportedModel(format!("ACO color space {}", other))),
+    })
+}
+
+fn read_aco(bytes: &[u8]) -> Result<Palette, SwatchError> {
+    let mut reader = Reader::new(bytes);
+    let mut palette = Palette::default();
+
+    // Version 1 holds unnamed colors; an optional version 2 section repeats them with names
+    while !reader.is_empty() {
+        let version = reader.u16()?;
+        if !matches!(version, 1 | 2) {
+            return Err(SwatchError::InvalidHeader(SwatchFormat::Aco));
+        }
+        let count = reader.u16()?;
+        let mut swatches = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let source = read_aco_color(&mut reader)?;
+            let name = if version == 2 {
+                let len = reader.u32()? as usize;
+                Some(reader.utf16(len)?)
+            } else {
+                None
+            };
+            swatches.push(Swatch::from_source(name.as_deref().filter(|n| !n.is_empty()), source));
+        }
+        palette.swatches = swatches;
+    }
+
+    Ok(palette)
+}
+
+/// Encodes one ACO color record
+fn write_aco_color(out: &mut Vec<u8>, source: SourceColor) {
+    let unit = |v: f32| (clamp_01(v) * 65535.0).round() as u16;
+    let (space, values) = match source {
+        SourceColor::Rgb([r, g, b]) => (ACO_RGB, [unit(r), unit(g), unit(b), 0]),
+        SourceColor::Cmyk(cmyk) => {
+            let ink = |v: f32| unit(1.0 - v / 100.0);
+            (ACO_CMYK, [ink(cmyk.c), ink(cmyk.m), ink(cmyk.y), ink(cmyk.k)])
+        }
+        SourceColor::Lab(lab) => {
+            let signed = |v: f32| ((v * 100.0).round().clamp(-12800.0, 12700.0) as i16) as u16;
+            (ACO_LAB, [(lab.l.clamp(0.0, 100.0) * 100.0).round() as u16, signed(lab.a), signed(lab.b), 0])
+        }
+        SourceColor::Gray(value) => (ACO_GRAY, [((1.0 - clamp_01(value)) * 10000.0).round() as u16, 0, 0, 0]),
+    };
+    out.extend_from_slice(&space.to_be_bytes());
+    for value in values {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_aco(palette: &Palette) -> Vec<u8> {
+    let swatches: Vec<&Swatch> = palette.all_swatches().collect();
+    let mut out = Vec::new();
+    for version in [1u16, 2] {
+        out.extend_from_slice(&version.to_be_bytes());
+        out.extend_from_slice(&(swatches.len() as u16).to_be_bytes());
+        for swatch in &swatches {
+            write_aco_color(&mut out, swatch.source);
+            if version == 2 {
+                let name = swatch.name.as_deref().unwrap_or("");
+                out.extend_from_slice(&(utf16_len(name) as u32).to_be_bytes());
+                push_utf16(&mut out, name);
+            }
+        }
+    }
+    out
+}
+
+fn read_gpl(text: &str) -> Result<Palette, SwatchError> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(SwatchError::InvalidHeader(SwatchFormat::Gpl));
+    }
+
+    let mut palette = Palette::default();
+    for (index, line) in lines.enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix("Name:") {
+            palette.name = Some(name.trim().to_string());
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let mut channel = || -> Result<f32, SwatchError> {
+            parts
+                .next()
+                .and_then(|part| part.parse::<u8>().ok())
+                .map(|value| value as f32 / 255.0)
+                .ok_or_else(|| SwatchError::Invalid(format!("line {}: expected R G B [name]", index + 2)))
+        };
+        let rgb = [channel()?, channel()?, channel()?];
+        let name = parts.collect::<Vec<_>>().join(" ");
+        let name = Some(name.as_str()).filter(|name| !name.is_empty());
+        palette.swatches.push(Swatch::from_source(name, SourceColor::Rgb(rgb)));
+    }
+    Ok(palette)
+}
+
+fn write_gpl(palette: &Palette) -> String {
+    let mut out = String::from("GIMP Palette\n");
+    out.push_str(&format!("Name: {}\n", palette.name.as_deref().unwrap_or("Untitled")));
+    out.push_str("#\n");
+    for swatch in palette.all_swatches() {
+        let [r, g, b] = swatch.rgb8();
+        let name = swatch.name.clone().unwrap_or_else(|| swatch.color.to_hex_string(false));
+        out.push_str(&format!("{:3} {:3} {:3}\t{}\n", r, g, b, name));
+    }
+    out
+}
+
+fn read_paint_net(text: &str) -> Result<Palette, SwatchError> {
+    let mut palette = Palette::default();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        // Colors are AARRGGBB; alpha is dropped since swatches are opaque
+        let value = (line.len() == 8)
+            .then(|| u32::from_str_radix(line, 16).ok())
+            .flatten()
+            .ok_or_else(|| SwatchError::Invalid(format!("line {}: expected AARRGGBB", index + 1)))?;
+        let [_, r, g, b] = value.to_be_bytes();
+        let rgb = [r, g, b].map(|c| c as f32 / 255.0);
+        palette.swatches.push(Swatch::from_source(None, SourceColor::Rgb(rgb)));
+    }
+    Ok(palette)
+}
+
+fn write_paint_net(palette: &Palette) -> String {
+    let mut out = String::from(";paint.net Palette File\n");
+    if let Some(name) = &palette.name {
+        out.push_str(&format!(";{}\n", name));
+    }
+    for swatch in palette.all_swatches() {
+        let [r, g, b] = swatch.rgb8();
+        out.push_str(&format!("FF{:02X}{:02X}{:02X}\n", r, g, b));
+    }
+    out
+}
+
+/// Returns the value of an XML attribute in an element's source text
+///
+/// The name only matches a whole attribute name outside quoted values, so
+/// `name` does not match `tools:name` or `xname`.
+pub(crate) fn xml_attribute(element: &str, name: &str) -> Option<String> {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in element.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if previous.is_whitespace() && element[i..].starts_with(name) => {
+                let rest = element[i + name.len()..].trim_start();
+                if let Some(value) = rest.strip_prefix('=').map(str::trim_start) {
+                    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+                    let end = value[1..].find(quote)?;
+                    return Some(xml_unescape(&value[1..end + 1]));
+                }
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+pub(crate) fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn read_soc(text: &str) -> Result<Palette, SwatchError> {
+    if !text.contains("color-table") {
+        return Err(SwatchError::InvalidHeader(SwatchFormat::Soc));
+    }
+
+    let mut palette = Palette::default();
+    for (position, _) in text.match_indices("<draw:color ") {
+        let end = text[position..].find('>').map_or(text.len(), |p| position + p);
+        let element = &text[position..end];
+        let value = xml_attribute(element, "draw:color")
+            .ok_or_else(|| SwatchError::Invalid("draw:color element without a color".to_string()))?;
+        let color = BigColor::new(&value);
+        if !color.is_valid() {
+            return Err(SwatchError::Invalid(format!("invalid color {}", value)));
+        }
+        let name = xml_attribute(element, "draw:name");
+        palette.swatches.push(Swatch::new(name.as_deref(), color));
+    }
+    Ok(palette)
+}
+
+fn write_soc(palette: &Palette) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<ooo:color-table xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" ",
+        "xmlns:draw=\"urn:oasis:names:tc:opendocument:xmlns:drawing:1.0\" ",
+        "xmlns:xlink=\"http://www.w3.org/1999/xlink\" xmlns:svg=\"http://www.w3.org/2000/svg\" ",
+        "xmlns:ooo=\"http://openoffice.org/2004/office\">\n",
+    ));
+    for swatch in palette.all_swatches() {
+        let [r, g, b] = swatch.rgb8();
+        let hex = format!("#{:02x}{:02x}{:02x}", r, g, b);
+        let name = swatch.name.clone().unwrap_or_else(|| hex.clone());
+        out.push_str(&format!("<draw:color draw:name=\"{}\" draw:color=\"{}\"/>\n", xml_escape(&name), hex));
+    }
+    out.push_str("</ooo:color-table>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Palette {
+        let cmyk = SourceColor::Cmyk(CMYK { c: 100.0, m: 45.0, y: 0.0, k: 4.0, a: 1.0 });
+        Palette {
+            name: Some("Brand".to_string()),
+            swatches: vec![
+                Swatch::new(Some("Blue & Co"), BigColor::new("#1a6ef5")),
+                Swatch::from_source(Some("Mid grey"), SourceColor::Gray(0.5)),
+            ],
+            groups: vec![SwatchGroup {
+                name: "Print".to_string(),
+                swatches: vec![Swatch::from_source(Some("Process Blue"), cmyk)],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_binary_formats_keep_source_models() {
+        for format in [SwatchFormat::Ase, SwatchFormat::Aco] {
+            let bytes = write_palette(&sample(), format);
+            assert_eq!(SwatchFormat::detect(&bytes), Some(format));
+            let palette = read_palette(&bytes, format).unwrap();
+            let swatches: Vec<&Swatch> = palette.all_swatches().collect();
+            assert_eq!(swatches.len(), 3);
+            assert_eq!(swatches[0].name.as_deref(), Some("Blue & Co"));
+            assert_eq!(swatches[0].color.to_hex_string(false), "#1a6ef5");
+            assert!(matches!(swatches[1].source, SourceColor::Gray(g) if (g - 0.5).abs() < 1e-3));
+            match swatches[2].source {
+                SourceColor::Cmyk(cmyk) => assert!((cmyk.m - 45.0).abs() < 0.01 && (cmyk.k - 4.0).abs() < 0.01),
+                other => panic!("{:?} lost its CMYK model: {:?}", format, other),
+            }
+        }
+
+        // Only ASE keeps groups
+        let ase = read_palette(&write_palette(&sample(), SwatchFormat::Ase), SwatchFormat::Ase).unwrap();
+        assert_eq!(ase.groups[0].name, "Print");
+    }
+
+    #[test]
+    fn test_text_formats_round_trip() {
+        for format in [SwatchFormat::Gpl, SwatchFormat::PaintNet, SwatchFormat::Soc] {
+            let bytes = write_palette(&sample(), format);
+            assert_eq!(SwatchFormat::detect(&bytes), Some(format));
+            let palette = read_palette(&bytes, format).unwrap();
+            let hexes: Vec<String> = palette.colors().iter().map(|c| c.to_hex_string(false)).collect();
+            assert_eq!(hexes[0], "#1a6ef5", "{:?}", format);
+            assert_eq!(hexes.len(), 3);
+            if format != SwatchFormat::PaintNet {
+                assert_eq!(palette.swatches[0].name.as_deref(), Some("Blue & Co"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reports_truncated_files() {
+        let bytes = write_palette(&sample(), SwatchFormat::Ase);
+        assert_eq!(read_palette(&bytes[..20], SwatchFormat::Ase).unwrap_err(), SwatchError::UnexpectedEnd);
+        assert!(read_palette(b"not a palette", SwatchFormat::Gpl).is_err());
+    }
+
+    #[test]
+    fn test_nearest_swatch() {
+        let palette = Palette::from_colors(None, &[BigColor::new("#1a6ef5"), BigColor::new("#f5a31a")]);
+        let (swatch, distance) = palette.nearest(&BigColor::new("#1b6ff4")).unwrap();
+        assert_eq!(swatch.color.to_hex_string(false), "#1a6ef5");
+        assert!(distance < 0.01);
+        assert!(Palette::default().nearest(&BigColor::new("#fff")).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+// Terminal colors: ANSI escape sequences and 16/256-color quantization
+
+use lazy_static::lazy_static;
+
+use crate::color_space::*;
+use crate::BigColor;
+
+/// Whether a sequence sets the text or the background color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Foreground,
+    Background,
+}
+
+/// Colors a terminal can display, from richest to none
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Picks a depth from the `NO_COLOR`, `COLORTERM` and `TERM` conventions
+    pub fn from_env_vars(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::None;
+        }
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            None | Some("dumb") => ColorDepth::None,
+            Some(term) if term.contains("direct") => ColorDepth::TrueColor,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            Some(_) => ColorDepth::Ansi16,
+        }
+    }
+
+    /// Detects the depth of the current process's terminal from its environment
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        ColorDepth::from_env_vars(var("NO_COLOR").as_deref(), var("COLORTERM").as_deref(), var("TERM").as_deref())
+    }
+}
+
+/// The colors a terminal shows for ANSI indices 0 to 15
+#[derive(Debug, Clone)]
+pub struct Ansi16Palette {
+    pub colors: [BigColor; 16],
+}
+
+impl Ansi16Palette {
+    /// Creates a palette from 16 hex strings in ANSI order
+    pub fn from_hex(colors: [&str; 16]) -> Self {
+        Ansi16Palette { colors: colors.map(BigColor::new) }
+    }
+
+    /// The xterm defaults
+    pub fn xterm() -> Self {
+        Ansi16Palette::from_hex([
+            "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+            "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+        ])
+    }
+
+    /// The VGA text-mode colors used by the Linux console
+    pub fn vga() -> Self {
+        Ansi16Palette::from_hex([
+            "#000000", "#aa0000", "#00aa00", "#aa5500", "#0000aa", "#aa00aa", "#00aaaa", "#aaaaaa",
+            "#555555", "#ff5555", "#55ff55", "#ffff55", "#5555ff", "#ff55ff", "#55ffff", "#ffffff",
+        ])
+    }
+}
+
+impl Default for Ansi16Palette {
+    fn default() -> Self {
+        Ansi16Palette::xterm()
+    }
+}
+
+/// Levels of each channel in the xterm 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Returns the RGB channels of an xterm-256 color from 16 to 255
+fn xterm_rgb(index: u8) -> [u8; 3] {
+    if index >= 232 {
+        let level = 8 + 10 * (index - 232);
+        [level; 3]
+    } else {
+        let cube = index - 16;
+        [cube / 36, (cube / 6) % 6, cube % 6].map(|i| CUBE_LEVELS[i as usize])
+    }
+}
+
+fn oklab_of(rgb: [u8; 3]) -> OKLab {
+    xyz_d65_to_oklab(rgb_to_xyz_d65(rgb[0], rgb[1], rgb[2], 1.0))
+}
+
+lazy_static! {
+    /// OKLab coordinates of xterm colors 16 to 255, which every terminal agrees on
+    static ref XTERM_OKLAB: Vec<OKLab> = (16..=255).map(|index| oklab_of(xterm_rgb(index))).collect();
+}
+
+/// Returns the color of an xterm-256 index, using `palette` for 0 to 15
+pub fn ansi256_color(index: u8, palette: Option<&Ansi16Palette>) -> BigColor {
+    if index < 16 {
+        return match palette {
+            Some(palette) => palette.colors[index as usize].clone(),
+            None => Ansi16Palette::xterm().colors[index as usize].clone(),
+        };
+    }
+    let [r, g, b] = xterm_rgb(index);
+    BigColor::from_rgb(r, g, b, 1.0)
+}
+
+/// Returns the xterm-256 index closest to a color by ΔEOK. Only the cube and
+/// grey ramp (16 to 255) are considered, since 0 to 15 vary between terminals.
+pub fn nearest_ansi256(color: &BigColor) -> u8 {
+    let target = color.to_oklab();
+    let (offset, _) = XTERM_OKLAB
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| (i, delta_e_ok(target, *candidate)))
+        .fold((0, f32::MAX), |best, next| if next.1 < best.1 { next } else { best });
+    16 + offset as u8
+}
+
+/// Returns the index from 0 to 15 closest to a color by ΔEOK in the given
+/// palette, or the xterm defaults
+pub fn nearest_ansi16(color: &BigColor, palette: Option<&Ansi16Palette>) -> u8 {
+    let default_palette;
+    let palette = match palette {
+        Some(palette) => palette,
+        None => {
+            default_palette = Ansi16Palette::xterm();
+            &default_palette
+        }
+    };
+    let target = color.to_oklab();
+    let (index, _) = palette
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| (i, delta_e_ok(target, candidate.to_oklab())))
+        .fold((0, f32::MAX), |best, next| if next.1 < best.1 { next } else { best });
+    index as u8
+}
+
+/// Returns a 24-bit escape sequence, `\x1b[38;2;r;g;bm` or `\x1b[48;2;r;g;bm`
+pub fn truecolor_sequence(color: &BigColor, layer: Layer) -> String {
+    let rgb = color.to_rgb();
+    let code = if layer == Layer::Foreground { 38 } else { 48 };
+    format!("\x1b[{};2;{};{};{}m", code, rgb.r, rgb.g, rgb.b)
+}
+
+/// Returns the escape sequence for a color at the given depth, quantizing for
+/// 256 and 16-color terminals. `ColorDepth::None` gives an empty string.
+pub fn escape_sequence(color: &BigColor, layer: Layer, depth: ColorDepth, palette: Option<&Ansi16Palette>) -> String {
+    let foreground = layer == Layer::Foreground;
+    match depth {
+        ColorDepth::TrueColor => truecolor_sequence(color, layer),
+        ColorDepth::Ansi256 => {
+            format!("\x1b[{};5;{}m", if foreground { 38 } else { 48 }, nearest_ansi256(color))
+        }
+        ColorDepth::Ansi16 => {
+            let index = nearest_ansi16(color, palette);
+            let base = match (foreground, index < 8) {
+                (true, true) => 30,
+                (true, false) => 90 - 8,
+                (false, true) => 40,
+                (false, false) => 100 - 8,
+            };
+            format!("\x1b[{}m", base + index)
+        }
+        ColorDepth::None => String::new(),
+    }
+}
+
+/// The sequence that resets colors and attributes
+pub const RESET: &str = "\x1b[0m";
+
+/// A color set by an escape sequence
+#[derive(Debug, Clone)]
+pub struct AnsiColor {
+    pub layer: Layer,
+    pub color: BigColor,
+    /// The palette index for 16 and 256-color sequences
+    pub index: Option<u8>,
+}
+
+/// Reads the colors set by an SGR escape sequence such as `\x1b[38;2;26;110;245m`,
+/// `\x1b[48;5;33m` or `\x1b[1;91m`
+///
+/// The escape character may also be written as the text `\e`, `\x1b`, `\033`
+/// or `\u001b`, and colon-separated parameters (`38:2::26:110:245`) are accepted.
+/// 16-color indices are looked up in `palette`, or the xterm defaults.
+pub fn parse_ansi_sequence(sequence: &str, palette: Option<&Ansi16Palette>) -> Vec<AnsiColor> {
+    let mut text = sequence.trim().to_string();
+    for escape in ["\\x1b", "\\x1B", "\\033", "\\u001b", "\\u{1b}", "\\e"] {
+        text = text.replace(escape, "\x1b");
+    }
+    let Some(body) = text.strip_prefix("\x1b[").and_then(|rest| rest.strip_suffix('m')) else {
+        return Vec::new();
+    };
+
+    let mut colors = Vec::new();
+    let mut params = body.split(';').peekable();
+    while let Some(param) = params.next() {
+        let mut parts: Vec<&str> = param.split(':').collect();
+        let Ok(code) = parts[0].parse::<u16>() else { continue };
+        let layer = match code {
+            30..=38 | 90..=97 => Layer::Foreground,
+            40..=48 | 100..=107 => Layer::Background,
+            _ => continue,
+        };
+
+        let (color, index) = match code {
+            30..=37 | 40..=47 => {
+                let index = (code % 10) as u8;
+                (ansi256_color(index, palette), Some(index))
+            }
+            90..=97 | 100..=107 => {
+                let index = (code % 10) as u8 + 8;
+                (ansi256_color(index, palette), Some(index))
+            }
+            _ => {
+                // Extended color: semicolon form takes the next parameters,
+                // colon form carries them in the same parameter
+                if parts.len() == 1 {
+                    match params.peek() {
+                        Some(&"5") => {
+                            parts.extend(params.by_ref().take(2));
+                        }
+                        Some(&"2") => {
+                            parts.extend(params.by_ref().take(4));
+                        }
+                        _ => continue,
+                    }
+                }
+                let numbers: Vec<Option<u8>> = parts[1..].iter().map(|part| part.parse().ok()).collect();
+                match numbers.as_slice() {
+                    [Some(5), Some(index)] => (ansi256_color(*index, palette), Some(*index)),
+                    // The colon form may include an empty color space id
+                    [Some(2), Some(r), Some(g), Some(b)] | [Some(2), _, Some(r), Some(g), Some(b)] => {
+                        (BigColor::from_rgb(*r, *g, *b, 1.0), None)
+                    }
+                    _ => continue,
+                }
+            }
+        };
+        colors.push(AnsiColor { layer, color, index });
+    }
+    colors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantizes_perceptually() {
+        assert_eq!(nearest_ansi256(&BigColor::new("#ff0000")), 196);
+        assert_eq!(nearest_ansi256(&BigColor::new("#808080")), 244);
+        assert_eq!(nearest_ansi16(&BigColor::new("#1a6ef5"), None), 12);
+        assert_eq!(nearest_ansi16(&BigColor::new("#a05000"), Some(&Ansi16Palette::vga())), 3);
+
+        let blue = BigColor::new("#1a6ef5");
+        assert_eq!(escape_sequence(&blue, Layer::Background, ColorDepth::Ansi16, None), "\x1b[104m");
+        assert_eq!(escape_sequence(&blue, Layer::Foreground, ColorDepth::None, None), "");
+    }
+
+    #[test]
+    fn test_parses_sequences() {
+        let colors = parse_ansi_sequence("\\e[1;38;2;26;110;245;48;5;196m", None);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0].layer, Layer::Foreground);
+        assert_eq!(colors[0].color.to_hex_string(false), "#1a6ef5");
+        assert_eq!(colors[1].index, Some(196));
+        assert_eq!(colors[1].color.to_hex_string(false), "#ff0000");
+
+        let colon = parse_ansi_sequence("\x1b[38:2::26:110:245m", None);
+        assert_eq!(colon[0].color.to_hex_string(false), "#1a6ef5");
+        let bright = parse_ansi_sequence("\x1b[91m", Some(&Ansi16Palette::vga()));
+        assert_eq!(bright[0].color.to_hex_string(false), "#ff5555");
+    }
+
+    #[test]
+    fn test_detects_depth() {
+        assert_eq!(ColorDepth::from_env_vars(None, Some("truecolor"), Some("xterm")), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env_vars(None, None, Some("xterm-256color")), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env_vars(Some("1"), Some("truecolor"), None), ColorDepth::None);
+        assert_eq!(ColorDepth::from_env_vars(None, None, Some("dumb")), ColorDepth::None);
+    }
+}
@@ -0,0 +1,717 @@
+// Terminal color schemes: iTerm2, Alacritty, Windows Terminal, kitty and base16/base24
+
+use std::collections::HashMap;
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::accessibility::{ensure_contrast, ContrastTarget};
+use crate::color_space::*;
+use crate::swatches::{xml_escape, xml_unescape};
+use crate::terminal::Ansi16Palette;
+use crate::{mix, BigColor, ColorFormat};
+
+/// A terminal color scheme: the 16 ANSI colors plus the UI colors
+#[derive(Debug, Clone)]
+pub struct TerminalTheme {
+    pub name: Option<String>,
+    /// Black, red, green, yellow, blue, magenta, cyan and white, then their bright variants
+    pub ansi: [BigColor; 16],
+    pub foreground: BigColor,
+    pub background: BigColor,
+    pub cursor: Option<BigColor>,
+    /// Color of the character under a block cursor
+    pub cursor_text: Option<BigColor>,
+    pub selection_background: Option<BigColor>,
+    pub selection_foreground: Option<BigColor>,
+}
+
+impl TerminalTheme {
+    /// Returns the ANSI colors as a palette for 16-color quantization
+    pub fn palette(&self) -> Ansi16Palette {
+        Ansi16Palette { colors: self.ansi.clone() }
+    }
+}
+
+/// Terminal color scheme file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalThemeFormat {
+    /// iTerm2 `.itermcolors` property list
+    ITerm2,
+    /// Alacritty TOML `[colors]` tables
+    Alacritty,
+    /// A Windows Terminal `schemes` entry
+    WindowsTerminal,
+    /// kitty `.conf` color settings
+    Kitty,
+    /// base16 YAML scheme
+    Base16,
+    /// base24 YAML scheme, with distinct bright colors
+    Base24,
+}
+
+impl TerminalThemeFormat {
+    /// Guesses the format from a file extension. YAML files are read as
+    /// base24, which also accepts base16 schemes.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "itermcolors" => Some(TerminalThemeFormat::ITerm2),
+            "toml" => Some(TerminalThemeFormat::Alacritty),
+            "json" => Some(TerminalThemeFormat::WindowsTerminal),
+            "conf" => Some(TerminalThemeFormat::Kitty),
+            "yaml" | "yml" => Some(TerminalThemeFormat::Base24),
+            _ => None,
+        }
+    }
+}
+
+/// Errors from reading a terminal color scheme
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalThemeError {
+    /// A required color is not defined; the key is in kitty's naming, such as `color4`
+    MissingColor(String),
+    /// A color setting has a value that is not a color
+    InvalidColor { key: String, value: String },
+}
+
+impl fmt::Display for TerminalThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TerminalThemeError::MissingColor(key) => write!(f, "missing color {}", key),
+            TerminalThemeError::InvalidColor { key, value } => write!(f, "invalid value {} for {}", value, key),
+        }
+    }
+}
+
+impl std::error::Error for TerminalThemeError {}
+
+/// Names of the ANSI colors in order, as Alacritty spells them
+const ANSI_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// Settings collected from a file, keyed by kitty's names
+#[derive(Default)]
+struct RawTheme {
+    name: Option<String>,
+    values: HashMap<String, String>,
+}
+
+/// Parses `#rrggbb`, `rrggbb`, `0xrrggbb` and the 3-digit forms
+fn parse_hex(value: &str) -> Option<BigColor> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    let digits = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .unwrap_or(value);
+    if matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(BigColor::new(format!("#{}", digits)))
+    } else {
+        None
+    }
+}
+
+impl RawTheme {
+    fn set(&mut self, key: impl Into<String>, value: &str) {
+        self.values.insert(key.into(), value.trim().to_string());
+    }
+
+    fn required(&self, key: &str) -> Result<BigColor, TerminalThemeError> {
+        let value = self
+            .values
+            .get(key)
+            .ok_or_else(|| TerminalThemeError::MissingColor(key.to_string()))?;
+        parse_hex(value).ok_or_else(|| TerminalThemeError::InvalidColor {
+            key: key.to_string(),
+            value: value.clone(),
+        })
+    }
+
+    /// Optional settings may hold keywords such as Alacritty's `CellForeground`,
+    /// which are not colors and are skipped
+    fn optional(&self, key: &str) -> Option<BigColor> {
+        self.values.get(key).and_then(|value| parse_hex(value))
+    }
+
+    fn into_theme(self) -> Result<TerminalTheme, TerminalThemeError> {
+        let mut ansi = Vec::with_capacity(16);
+        for index in 0..16 {
+            ansi.push(self.required(&format!("color{}", index))?);
+        }
+        Ok(TerminalTheme {
+            ansi: ansi.try_into().expect("16 colors"),
+            foreground: self.required("foreground")?,
+            background: self.required("background")?,
+            cursor: self.optional("cursor"),
+            cursor_text: self.optional("cursor_text_color"),
+            selection_background: self.optional("selection_background"),
+            selection_foreground: self.optional("selection_foreground"),
+            name: self.name,
+        })
+    }
+}
+
+/// Reads a terminal color scheme
+pub fn read_terminal_theme(text: &str, format: TerminalThemeFormat) -> Result<TerminalTheme, TerminalThemeError> {
+    match format {
+        TerminalThemeFormat::ITerm2 => read_iterm2(text),
+        TerminalThemeFormat::Alacritty => read_alacritty(text),
+        TerminalThemeFormat::WindowsTerminal => read_windows_terminal(text),
+        TerminalThemeFormat::Kitty => read_kitty(text),
+        TerminalThemeFormat::Base16 | TerminalThemeFormat::Base24 => read_base16(text),
+    }
+}
+
+/// Writes a terminal color scheme
+///
+/// base16 has no slots for distinct bright colors, so bright colors are
+/// dropped; its remaining slots are filled by mixing the theme's colors.
+pub fn write_terminal_theme(theme: &TerminalTheme, format: TerminalThemeFormat) -> String {
+    match format {
+        TerminalThemeFormat::ITerm2 => write_iterm2(theme),
+        TerminalThemeFormat::Alacritty => write_alacritty(theme),
+        TerminalThemeFormat::WindowsTerminal => write_windows_terminal(theme),
+        TerminalThemeFormat::Kitty => write_kitty(theme),
+        TerminalThemeFormat::Base16 => write_base16(theme, false),
+        TerminalThemeFormat::Base24 => write_base16(theme, true),
+    }
+}
+
+fn hex(color: &BigColor) -> String {
+    color.to_hex_string(false)
+}
+
+/// The optional UI colors with kitty's key names
+fn ui_colors(theme: &TerminalTheme) -> [(&'static str, Option<&BigColor>); 4] {
+    [
+        ("cursor", theme.cursor.as_ref()),
+        ("cursor_text_color", theme.cursor_text.as_ref()),
+        ("selection_background", theme.selection_background.as_ref()),
+        ("selection_foreground", theme.selection_foreground.as_ref()),
+    ]
+}
+
+// iTerm2
+
+/// iTerm2 key names for the non-ANSI colors, with kitty's names
+const ITERM2_KEYS: [(&str, &str); 6] = [
+    ("Foreground Color", "foreground"),
+    ("Background Color", "background"),
+    ("Cursor Color", "cursor"),
+    ("Cursor Text Color", "cursor_text_color"),
+    ("Selection Color", "selection_background"),
+    ("Selected Text Color", "selection_foreground"),
+];
+
+lazy_static! {
+    static ref PLIST_COLOR: Regex = Regex::new(r"<key>([^<]+)</key>\s*<dict>([\s\S]*?)</dict>").unwrap();
+    static ref PLIST_COMPONENT: Regex =
+        Regex::new(r"<key>(Red|Green|Blue|Alpha) Component</key>\s*<(?:real|integer)>([^<]*)</(?:real|integer)>").unwrap();
+    static ref PLIST_SPACE: Regex = Regex::new(r"<key>Color Space</key>\s*<string>([^<]*)</string>").unwrap();
+}
+
+fn read_iterm2(text: &str) -> Result<TerminalTheme, TerminalThemeError> {
+    let mut raw = RawTheme::default();
+    for captures in PLIST_COLOR.captures_iter(text) {
+        let key = xml_unescape(&captures[1]);
+        let name = match key.strip_prefix("Ansi ").and_then(|rest| rest.strip_suffix(" Color")) {
+            Some(index) => format!("color{}", index),
+            None => match ITERM2_KEYS.iter().find(|(iterm, _)| *iterm == key) {
+                Some((_, name)) => name.to_string(),
+                None => continue,
+            },
+        };
+
+        let body = &captures[2];
+        let mut rgba = [0.0, 0.0, 0.0, 1.0];
+        for component in PLIST_COMPONENT.captures_iter(body) {
+            let slot = ["Red", "Green", "Blue", "Alpha"].iter().position(|c| *c == &component[1]).unwrap_or(0);
+            rgba[slot] = component[2].trim().parse().map_err(|_| TerminalThemeError::InvalidColor {
+                key: key.clone(),
+                value: component[2].to_string(),
+            })?;
+        }
+        let rgb = [rgba[0], rgba[1], rgba[2]].map(srgb_to_linear);
+        // Colors without a color space are device RGB, read here as sRGB
+        let xyz = match PLIST_SPACE.captures(body).map(|space| space[1].to_string()).as_deref() {
+            Some("P3") => linear_display_p3_to_xyz_d65(rgb, rgba[3]),
+            _ => linear_srgb_to_xyz_d65(rgb, rgba[3]),
+        };
+        raw.set(name, &hex(&BigColor::from_xyz_d65(xyz, ColorFormat::HEX)));
+    }
+    raw.into_theme()
+}
+
+fn write_iterm2(theme: &TerminalTheme) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n<dict>\n",
+    ));
+    let mut entry = |key: &str, color: &BigColor| {
+        let linear = xyz_d65_to_linear_srgb(oklab_to_xyz_d65(color.to_oklab()));
+        let [r, g, b] = linear.map(|c| clamp_01(linear_to_srgb(c)));
+        out.push_str(&format!("\t<key>{}</key>\n\t<dict>\n", xml_escape(key)));
+        out.push_str(&format!("\t\t<key>Alpha Component</key>\n\t\t<real>{}</real>\n", color.get_alpha()));
+        out.push_str(&format!("\t\t<key>Blue Component</key>\n\t\t<real>{}</real>\n", b));
+        out.push_str("\t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n");
+        out.push_str(&format!("\t\t<key>Green Component</key>\n\t\t<real>{}</real>\n", g));
+        out.push_str(&format!("\t\t<key>Red Component</key>\n\t\t<real>{}</real>\n", r));
+        out.push_str("\t</dict>\n");
+    };
+
+    for (index, color) in theme.ansi.iter().enumerate() {
+        entry(&format!("Ansi {} Color", index), color);
+    }
+    entry("Foreground Color", &theme.foreground);
+    entry("Background Color", &theme.background);
+    for ((iterm, _), (_, color)) in ITERM2_KEYS[2..].iter().zip(ui_colors(theme)) {
+        if let Some(color) = color {
+            entry(iterm, color);
+        }
+    }
+    out.push_str("</dict>\n</plist>\n");
+    out
+}
+
+// Alacritty
+
+fn read_alacritty(text: &str) -> Result<TerminalTheme, TerminalThemeError> {
+    let mut raw = RawTheme::default();
+    let mut table = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            table = name.trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.split(" #").next().unwrap_or(value);
+        let name = match (table.as_str(), key.trim()) {
+            ("colors.primary", "background") => "background".to_string(),
+            ("colors.primary", "foreground") => "foreground".to_string(),
+            ("colors.cursor", "cursor") => "cursor".to_string(),
+            ("colors.cursor", "text") => "cursor_text_color".to_string(),
+            ("colors.selection", "background") => "selection_background".to_string(),
+            ("colors.selection", "text") => "selection_foreground".to_string(),
+            (section @ ("colors.normal" | "colors.bright"), key) => match ANSI_NAMES.iter().position(|n| *n == key) {
+                Some(index) => format!("color{}", index + if section == "colors.bright" { 8 } else { 0 }),
+                None => continue,
+            },
+            _ => continue,
+        };
+        raw.set(name, value);
+    }
+    raw.into_theme()
+}
+
+fn write_alacritty(theme: &TerminalTheme) -> String {
+    let mut out = String::new();
+    if let Some(name) = &theme.name {
+        out.push_str(&format!("# {}\n\n", name));
+    }
+    out.push_str(&format!(
+        "[colors.primary]\nbackground = \"{}\"\nforeground = \"{}\"\n",
+        hex(&theme.background),
+        hex(&theme.foreground)
+    ));
+
+    if theme.cursor.is_some() || theme.cursor_text.is_some() {
+        out.push_str("\n[colors.cursor]\n");
+        if let Some(color) = &theme.cursor_text {
+            out.push_str(&format!("text = \"{}\"\n", hex(color)));
+        }
+        if let Some(color) = &theme.cursor {
+            out.push_str(&format!("cursor = \"{}\"\n", hex(color)));
+        }
+    }
+    if theme.selection_background.is_some() || theme.selection_foreground.is_some() {
+        out.push_str("\n[colors.selection]\n");
+        if let Some(color) = &theme.selection_foreground {
+            out.push_str(&format!("text = \"{}\"\n", hex(color)));
+        }
+        if let Some(color) = &theme.selection_background {
+            out.push_str(&format!("background = \"{}\"\n", hex(color)));
+        }
+    }
+
+    for (table, offset) in [("normal", 0), ("bright", 8)] {
+        out.push_str(&format!("\n[colors.{}]\n", table));
+        for (index, name) in ANSI_NAMES.iter().enumerate() {
+            out.push_str(&format!("{} = \"{}\"\n", name, hex(&theme.ansi[offset + index])));
+        }
+    }
+    out
+}
+
+// Windows Terminal
+
+/// Windows Terminal names for the ANSI colors, in order
+const WINDOWS_TERMINAL_ANSI: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+    "brightBlack", "brightRed", "brightGreen", "brightYellow", "brightBlue", "brightPurple", "brightCyan", "brightWhite",
+];
+
+lazy_static! {
+    static ref JSON_STRING_PAIR: Regex = Regex::new(r#""(\w+)"\s*:\s*"((?:[^"\\]|\\.)*)""#).unwrap();
+}
+
+/// Reads a single scheme object, as found in the `schemes` array of
+/// settings.json
+fn read_windows_terminal(text: &str) -> Result<TerminalTheme, TerminalThemeError> {
+    let mut raw = RawTheme::default();
+    for captures in JSON_STRING_PAIR.captures_iter(text) {
+        let (key, value) = (&captures[1], &captures[2]);
+        let name = match key {
+            "name" => {
+                raw.name = Some(value.replace("\\\"", "\"").replace("\\\\", "\\"));
+                continue;
+            }
+            "background" | "foreground" => key.to_string(),
+            "cursorColor" => "cursor".to_string(),
+            "selectionBackground" => "selection_background".to_string(),
+            _ => match WINDOWS_TERMINAL_ANSI.iter().position(|n| *n == key) {
+                Some(index) => format!("color{}", index),
+                None => continue,
+            },
+        };
+        raw.set(name, value);
+    }
+    raw.into_theme()
+}
+
+fn write_windows_terminal(theme: &TerminalTheme) -> String {
+    let name = theme.name.as_deref().unwrap_or("Untitled");
+    let mut entries = vec![
+        ("name".to_string(), name.replace('\\', "\\\\").replace('"', "\\\"")),
+        ("background".to_string(), hex(&theme.background)),
+        ("foreground".to_string(), hex(&theme.foreground)),
+    ];
+    if let Some(color) = &theme.cursor {
+        entries.push(("cursorColor".to_string(), hex(color)));
+    }
+    if let Some(color) = &theme.selection_background {
+        entries.push(("selectionBackground".to_string(), hex(color)));
+    }
+    for (key, color) in WINDOWS_TERMINAL_ANSI.iter().zip(&theme.ansi) {
+        entries.push((key.to_string(), hex(color)));
+    }
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|(key, value)| format!("    \"{}\": \"{}\"", key, value))
+        .collect();
+    format!("{{\n{}\n}}\n", lines.join(",\n"))
+}
+
+// kitty
+
+fn read_kitty(text: &str) -> Result<TerminalTheme, TerminalThemeError> {
+    let mut raw = RawTheme::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("## name:") {
+            raw.name = Some(name.trim().to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            raw.set(key, value);
+        }
+    }
+    raw.into_theme()
+}
+
+fn write_kitty(theme: &TerminalTheme) -> String {
+    let mut out = String::new();
+    if let Some(name) = &theme.name {
+        out.push_str(&format!("## name: {}\n\n", name));
+    }
+    out.push_str(&format!("foreground {}\nbackground {}\n", hex(&theme.foreground), hex(&theme.background)));
+    for (key, color) in ui_colors(theme) {
+        if let Some(color) = color {
+            out.push_str(&format!("{} {}\n", key, hex(color)));
+        }
+    }
+    out.push('\n');
+    for (index, color) in theme.ansi.iter().enumerate() {
+        out.push_str(&format!("color{} {}\n", index, hex(color)));
+    }
+    out
+}
+
+// base16 and base24
+
+/// The base slot shown for each ANSI color in base16 schemes
+const BASE16_ANSI: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05",
+    "base03", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+/// base24 replaces the bright colors with its own slots
+const BASE24_BRIGHT: [&str; 6] = ["base12", "base14", "base13", "base16", "base17", "base15"];
+
+/// Reads base16 and base24 schemes, in both the classic flat layout and the
+/// newer layout with a `palette` mapping
+fn read_base16(text: &str) -> Result<TerminalTheme, TerminalThemeError> {
+    let mut slots = HashMap::new();
+    let mut name = None;
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else { continue };
+        let value = value.split(" #").next().unwrap_or(value).trim().trim_matches(|c| c == '"' || c == '\'');
+        match key.trim() {
+            "scheme" | "name" => name = Some(value.to_string()),
+            key if key.len() == 6 && key.starts_with("base") => {
+                slots.insert(key.to_ascii_uppercase().replacen("BASE", "base", 1), value.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let is_base24 = BASE24_BRIGHT.iter().all(|slot| slots.contains_key(*slot));
+    let mut raw = RawTheme { name, ..Default::default() };
+    for (index, slot) in BASE16_ANSI.iter().enumerate() {
+        let slot = if is_base24 && (9..15).contains(&index) { BASE24_BRIGHT[index - 9] } else { slot };
+        if let Some(value) = slots.get(slot) {
+            raw.set(format!("color{}", index), value);
+        }
+    }
+    for (key, slot) in [
+        ("background", "base00"),
+        ("foreground", "base05"),
+        ("cursor", "base05"),
+        ("cursor_text_color", "base00"),
+        ("selection_background", "base02"),
+        ("selection_foreground", "base05"),
+    ] {
+        if let Some(value) = slots.get(slot) {
+            raw.set(key, value);
+        }
+    }
+    raw.into_theme()
+}
+
+fn write_base16(theme: &TerminalTheme, base24: bool) -> String {
+    let [_, red, green, yellow, blue, magenta, cyan, _, bright_black, ..] = &theme.ansi;
+    let (background, foreground) = (&theme.background, &theme.foreground);
+    let bright_white = &theme.ansi[15];
+    let toward = |from: &BigColor, to: &BigColor, amount: f32| mix(from, to, Some(amount));
+
+    let mut slots = vec![
+        ("base00", background.clone()),
+        ("base01", toward(background, foreground, 8.0)),
+        ("base02", theme.selection_background.clone().unwrap_or_else(|| toward(background, foreground, 16.0))),
+        ("base03", bright_black.clone()),
+        ("base04", toward(background, foreground, 70.0)),
+        ("base05", foreground.clone()),
+        ("base06", toward(foreground, bright_white, 50.0)),
+        ("base07", bright_white.clone()),
+        ("base08", red.clone()),
+        ("base09", toward(red, yellow, 50.0)),
+        ("base0A", yellow.clone()),
+        ("base0B", green.clone()),
+        ("base0C", cyan.clone()),
+        ("base0D", blue.clone()),
+        ("base0E", magenta.clone()),
+        ("base0F", toward(red, background, 40.0)),
+    ];
+    if base24 {
+        let black = BigColor::new("#000");
+        slots.push(("base10", toward(background, &black, 30.0)));
+        slots.push(("base11", toward(background, &black, 60.0)));
+        for (index, slot) in BASE24_BRIGHT.iter().enumerate() {
+            slots.push((slot, theme.ansi[9 + index].clone()));
+        }
+        slots.sort_by_key(|(slot, _)| *slot);
+    }
+
+    let name = theme.name.as_deref().unwrap_or("Untitled");
+    let mut out = format!("scheme: \"{}\"\nauthor: \"\"\n", name.replace('"', "\\\""));
+    for (slot, color) in slots {
+        out.push_str(&format!("{}: \"{}\"\n", slot, hex(&color).trim_start_matches('#')));
+    }
+    out
+}
+
+// Generation
+
+/// Options for [`generate_terminal_theme`]
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalThemeOptions {
+    /// Contrast every text color must reach against the background
+    pub contrast: ContrastTarget,
+    /// Maximum hue rotation, in degrees, of the ANSI hues toward the accent
+    pub harmonize_degrees: f32,
+}
+
+impl Default for TerminalThemeOptions {
+    fn default() -> Self {
+        TerminalThemeOptions {
+            contrast: ContrastTarget::Ratio(4.5),
+            harmonize_degrees: 15.0,
+        }
+    }
+}
+
+/// OKLCH hues of red, green, yellow, blue, magenta and cyan
+const ANSI_HUES: [f32; 6] = [27.0, 145.0, 95.0, 260.0, 330.0, 200.0];
+
+fn oklch(l: f32, c: f32, h: f32) -> BigColor {
+    let mapped = gamut_map_oklch(OKLCH { l, c, h, alpha: 1.0 });
+    BigColor::from_oklch(mapped.l, mapped.c, mapped.h, 1.0)
+}
+
+/// Generates a 16-color terminal theme from a background and an accent
+///
+/// The six chromatic hues are rotated toward the accent, and the one closest
+/// to it is replaced by the accent itself. Every color used as text meets
+/// `options.contrast` against the background: all but black on dark
+/// backgrounds, and all but white and bright white on light ones.
+pub fn generate_terminal_theme(
+    background: &BigColor,
+    accent: &BigColor,
+    options: Option<TerminalThemeOptions>,
+) -> TerminalTheme {
+    let options = options.unwrap_or_default();
+    let target = options.contrast;
+    let dark = background.is_dark();
+    let base = background.to_oklch();
+    let tint = base.c.min(0.03);
+    let accent_oklch = accent.to_oklch();
+    let chroma = accent_oklch.c.clamp(0.1, 0.2);
+
+    let readable = |color: BigColor| ensure_contrast(&color, background, target);
+    let (normal_l, bright_l) = if dark { (0.72, 0.82) } else { (0.5, 0.42) };
+
+    let closest = ANSI_HUES
+        .iter()
+        .enumerate()
+        .map(|(i, hue)| {
+            let difference = (hue - accent_oklch.h).rem_euclid(360.0);
+            (i, difference.min(360.0 - difference))
+        })
+        .fold((0, f32::MAX), |best, next| if next.1 < best.1 { next } else { best })
+        .0;
+
+    let mut chromatic = Vec::new();
+    let mut bright = Vec::new();
+    for (index, hue) in ANSI_HUES.iter().enumerate() {
+        let (normal, lifted) = if index == closest && accent_oklch.c > 0.02 {
+            let lift = if dark { 0.1 } else { -0.08 };
+            (accent.clone(), oklch(accent_oklch.l + lift, accent_oklch.c, accent_oklch.h))
+        } else {
+            let normal = oklch(normal_l, chroma, *hue).harmonize(accent, options.harmonize_degrees);
+            let lifted = oklch(bright_l, chroma * 1.1, *hue).harmonize(accent, options.harmonize_degrees);
+            (normal, lifted)
+        };
+        chromatic.push(readable(normal));
+        bright.push(readable(lifted));
+    }
+
+    let foreground = readable(oklch(if dark { 0.9 } else { 0.25 }, tint, base.h));
+    let (black, white, bright_black, bright_white) = if dark {
+        (
+            oklch(base.l + 0.08, tint, base.h),
+            oklch(0.86, tint, base.h),
+            readable(oklch(0.62, tint, base.h)),
+            oklch(0.97, tint, base.h),
+        )
+    } else {
+        (
+            readable(oklch(0.2, tint, base.h)),
+            oklch(0.9, tint, base.h),
+            readable(oklch(0.48, tint, base.h)),
+            oklch(0.98, tint, base.h),
+        )
+    };
+    let (black, white) = if dark { (black, readable(white)) } else { (black, white) };
+
+    let mut ansi = vec![black];
+    ansi.extend(chromatic);
+    ansi.push(white);
+    ansi.push(bright_black);
+    ansi.extend(bright);
+    ansi.push(if dark { readable(bright_white) } else { bright_white });
+
+    TerminalTheme {
+        name: None,
+        ansi: ansi.try_into().expect("16 colors"),
+        cursor: Some(readable(accent.clone())),
+        cursor_text: Some(background.clone()),
+        selection_background: Some(mix(background, accent, Some(25.0))),
+        selection_foreground: Some(foreground.clone()),
+        foreground,
+        background: background.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TerminalTheme {
+        let mut theme = generate_terminal_theme(&BigColor::new("#1d1f21"), &BigColor::new("#1a6ef5"), None);
+        theme.name = Some("Sample".to_string());
+        theme
+    }
+
+    #[test]
+    fn test_formats_round_trip() {
+        let theme = sample();
+        for format in [
+            TerminalThemeFormat::ITerm2,
+            TerminalThemeFormat::Alacritty,
+            TerminalThemeFormat::WindowsTerminal,
+            TerminalThemeFormat::Kitty,
+        ] {
+            let read = read_terminal_theme(&write_terminal_theme(&theme, format), format).unwrap();
+            for (written, read) in theme.ansi.iter().zip(&read.ansi) {
+                assert_eq!(hex(written), hex(read), "{:?}", format);
+            }
+            assert_eq!(hex(&read.background), "#1d1f21");
+            assert_eq!(hex(read.selection_background.as_ref().unwrap()), hex(theme.selection_background.as_ref().unwrap()));
+        }
+
+        // base24 keeps the chromatic and bright colors, but black and white
+        // become the background and foreground
+        let base24 = write_terminal_theme(&theme, TerminalThemeFormat::Base24);
+        let read = read_terminal_theme(&base24, TerminalThemeFormat::Base24).unwrap();
+        assert_eq!(read.name.as_deref(), Some("Sample"));
+        for index in (1..7).chain(8..16) {
+            assert_eq!(hex(&theme.ansi[index]), hex(&read.ansi[index]), "color{}", index);
+        }
+    }
+
+    #[test]
+    fn test_reads_base16_and_reports_missing_colors() {
+        let yaml = "scheme: \"Tomorrow Night\"\nbase00: \"1d1f21\"\nbase02: \"373b41\"\nbase03: \"969896\"\nbase05: \"c5c8c6\"\nbase07: \"ffffff\"\nbase08: \"cc6666\"\nbase0A: \"f0c674\"\nbase0B: \"b5bd68\"\nbase0C: \"8abeb7\"\nbase0D: \"81a2be\"\nbase0E: \"b294bb\"\n";
+        let theme = read_terminal_theme(yaml, TerminalThemeFormat::Base16).unwrap();
+        assert_eq!(theme.name.as_deref(), Some("Tomorrow Night"));
+        assert_eq!(hex(&theme.ansi[1]), "#cc6666");
+        assert_eq!(hex(&theme.ansi[9]), "#cc6666");
+        assert_eq!(hex(&theme.ansi[8]), "#969896");
+
+        let kitty = "foreground #ffffff\nbackground #000000\ncolor0 #000000\n";
+        assert_eq!(
+            read_terminal_theme(kitty, TerminalThemeFormat::Kitty).unwrap_err(),
+            TerminalThemeError::MissingColor("color1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_generated_colors_are_readable() {
+        let target = ContrastTarget::Ratio(4.5);
+        let dark = sample();
+        assert!(dark.ansi[1..].iter().all(|color| target.is_met(color, &dark.background)));
+        assert!(target.is_met(&dark.foreground, &dark.background));
+
+        let light = generate_terminal_theme(&BigColor::new("#fdf6e3"), &BigColor::new("#d33682"), None);
+        for (index, color) in light.ansi.iter().enumerate() {
+            if index != 7 && index != 15 {
+                assert!(target.is_met(color, &light.background), "color{}", index);
+            }
+        }
+    }
+}
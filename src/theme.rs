@@ -0,0 +1,303 @@
+// Semantic theme generation from a few brand colors
+
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::accessibility::{ensure_contrast, ContrastTarget};
+use crate::color_space::*;
+use crate::dark_mode::{to_dark_mode, DarkModeOptions};
+use crate::BigColor;
+
+/// Brand colors a theme is generated from
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeSeeds {
+    pub primary: BigColor,
+    pub secondary: BigColor,
+    /// Tint for surfaces, borders and text. Defaults to a greyed primary.
+    pub neutral: Option<BigColor>,
+}
+
+/// Options for theme generation
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ThemeOptions {
+    /// Contrast every text-on-surface pair must reach
+    pub contrast: ContrastTarget,
+    /// Maximum hue rotation, in degrees, of status colors toward the primary
+    pub harmonize_degrees: f32,
+}
+
+impl Default for ThemeOptions {
+    fn default() -> Self {
+        ThemeOptions {
+            contrast: ContrastTarget::default(),
+            harmonize_degrees: 15.0,
+        }
+    }
+}
+
+/// The semantic tokens of one theme variant
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeColors {
+    pub primary: BigColor,
+    pub on_primary: BigColor,
+    pub secondary: BigColor,
+    pub on_secondary: BigColor,
+    pub surface: BigColor,
+    pub surface_variant: BigColor,
+    pub border: BigColor,
+    pub text_primary: BigColor,
+    pub text_secondary: BigColor,
+    /// Disabled text is exempt from contrast requirements and deliberately faint
+    pub text_disabled: BigColor,
+    pub success: BigColor,
+    pub warning: BigColor,
+    pub danger: BigColor,
+    pub info: BigColor,
+}
+
+impl ThemeColors {
+    /// Returns every token with its kebab-case name
+    pub fn tokens(&self) -> Vec<(&'static str, &BigColor)> {
+        vec![
+            ("primary", &self.primary),
+            ("on-primary", &self.on_primary),
+            ("secondary", &self.secondary),
+            ("on-secondary", &self.on_secondary),
+            ("surface", &self.surface),
+            ("surface-variant", &self.surface_variant),
+            ("border", &self.border),
+            ("text-primary", &self.text_primary),
+            ("text-secondary", &self.text_secondary),
+            ("text-disabled", &self.text_disabled),
+            ("success", &self.success),
+            ("warning", &self.warning),
+            ("danger", &self.danger),
+            ("info", &self.info),
+        ]
+    }
+
+    /// Returns every (foreground, background) pair that must meet the contrast target
+    pub fn text_pairs(&self) -> Vec<(&BigColor, &BigColor)> {
+        let mut pairs = vec![
+            (&self.on_primary, &self.primary),
+            (&self.on_secondary, &self.secondary),
+        ];
+        for surface in [&self.surface, &self.surface_variant] {
+            for text in [
+                &self.text_primary,
+                &self.text_secondary,
+                &self.success,
+                &self.warning,
+                &self.danger,
+                &self.info,
+            ] {
+                pairs.push((text, surface));
+            }
+        }
+        pairs
+    }
+
+    /// Returns true if every text pair meets the given target
+    pub fn meets(&self, target: ContrastTarget) -> bool {
+        self.text_pairs()
+            .into_iter()
+            .all(|(foreground, background)| target.is_met(foreground, background))
+    }
+}
+
+/// A complete semantic token set with light and dark variants
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    pub light: ThemeColors,
+    pub dark: ThemeColors,
+}
+
+/// Base OKLCH hues of the status colors
+const SUCCESS_HUE: f32 = 150.0;
+const WARNING_HUE: f32 = 80.0;
+const DANGER_HUE: f32 = 27.0;
+const INFO_HUE: f32 = 250.0;
+
+/// Builds an in-gamut color from OKLCH components
+fn oklch(l: f32, c: f32, h: f32) -> BigColor {
+    let mapped = gamut_map_oklch(OKLCH { l, c, h, alpha: 1.0 });
+    BigColor::from_oklch(mapped.l, mapped.c, mapped.h, 1.0)
+}
+
+/// Makes a foreground meet the target against both surfaces
+fn on_surfaces(color: BigColor, surface: &BigColor, variant: &BigColor, target: ContrastTarget) -> BigColor {
+    let color = ensure_contrast(&color, variant, target);
+    ensure_contrast(&color, surface, target)
+}
+
+/// Picks black or white, whichever reads better on the fill. If neither meets
+/// the target, the fill itself is moved away from the text color.
+fn on_fill(fill: BigColor, target: ContrastTarget) -> (BigColor, BigColor) {
+    let white = BigColor::new("#fff");
+    let black = BigColor::new("#000");
+    let on = if target.measure(&white, &fill) >= target.measure(&black, &fill) { white } else { black };
+    if target.is_met(&on, &fill) {
+        return (fill, on);
+    }
+    let fill = ensure_contrast(&fill, &on, target);
+    (fill, on)
+}
+
+/// Lightness and chroma of one theme variant's neutral and status tokens
+struct VariantSpec {
+    surface: f32,
+    surface_variant: f32,
+    border: f32,
+    text_primary: f32,
+    text_secondary: f32,
+    text_disabled: f32,
+    status: f32,
+    warning: f32,
+}
+
+const LIGHT_SPEC: VariantSpec = VariantSpec {
+    surface: 0.985,
+    surface_variant: 0.955,
+    border: 0.87,
+    text_primary: 0.22,
+    text_secondary: 0.45,
+    text_disabled: 0.72,
+    status: 0.55,
+    warning: 0.72,
+};
+
+const DARK_SPEC: VariantSpec = VariantSpec {
+    surface: 0.18,
+    surface_variant: 0.23,
+    border: 0.36,
+    text_primary: 0.95,
+    text_secondary: 0.76,
+    text_disabled: 0.5,
+    status: 0.74,
+    warning: 0.82,
+};
+
+fn build_variant(
+    spec: &VariantSpec,
+    primary: BigColor,
+    secondary: BigColor,
+    neutral: OKLCH,
+    brand: &BigColor,
+    options: &ThemeOptions,
+) -> ThemeColors {
+    let target = options.contrast;
+    let tint = neutral.c.min(0.02);
+
+    let surface = oklch(spec.surface, tint * 0.4, neutral.h);
+    let surface_variant = oklch(spec.surface_variant, tint * 0.6, neutral.h);
+    let border = oklch(spec.border, tint, neutral.h);
+
+    let status = |l: f32, hue: f32| {
+        let color = oklch(l, 0.16, hue).harmonize(brand, options.harmonize_degrees);
+        on_surfaces(color, &surface, &surface_variant, target)
+    };
+
+    let (primary, on_primary) = on_fill(primary, target);
+    let (secondary, on_secondary) = on_fill(secondary, target);
+
+    ThemeColors {
+        primary,
+        on_primary,
+        secondary,
+        on_secondary,
+        text_primary: on_surfaces(oklch(spec.text_primary, tint, neutral.h), &surface, &surface_variant, target),
+        text_secondary: on_surfaces(oklch(spec.text_secondary, tint, neutral.h), &surface, &surface_variant, target),
+        text_disabled: oklch(spec.text_disabled, tint * 0.5, neutral.h),
+        success: status(spec.status, SUCCESS_HUE),
+        warning: status(spec.warning, WARNING_HUE),
+        danger: status(spec.status, DANGER_HUE),
+        info: status(spec.status, INFO_HUE),
+        surface,
+        surface_variant,
+        border,
+    }
+}
+
+/// Generates light and dark semantic themes from brand seeds
+///
+/// Status colors keep their conventional hues but are rotated toward the
+/// primary. Text, status and on-fill colors are adjusted in lightness until
+/// they meet `options.contrast` against the surfaces they sit on; a brand fill
+/// that no text color can reach the target on is itself shifted in lightness.
+pub fn generate_theme(seeds: &ThemeSeeds, options: Option<ThemeOptions>) -> Theme {
+    let options = options.unwrap_or_default();
+    let primary = seeds.primary.to_oklch();
+    let neutral = match &seeds.neutral {
+        Some(color) => color.to_oklch(),
+        None => OKLCH { c: primary.c.min(0.02), ..primary },
+    };
+
+    let light = build_variant(
+        &LIGHT_SPEC,
+        seeds.primary.clone(),
+        seeds.secondary.clone(),
+        neutral,
+        &seeds.primary,
+        &options,
+    );
+
+    let dark_options = DarkModeOptions {
+        light_surface: light.surface.clone(),
+        dark_surface: oklch(DARK_SPEC.surface, neutral.c.min(0.02) * 0.4, neutral.h),
+        ..Default::default()
+    };
+    let dark = build_variant(
+        &DARK_SPEC,
+        to_dark_mode(&seeds.primary, Some(dark_options.clone())),
+        to_dark_mode(&seeds.secondary, Some(dark_options)),
+        neutral,
+        &seeds.primary,
+        &options,
+    );
+
+    Theme { light, dark }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WCAG2Level, WCAG2Params, WCAG2Size};
+
+    fn seeds() -> ThemeSeeds {
+        ThemeSeeds {
+            primary: BigColor::new("#6b3fd4"),
+            secondary: BigColor::new("#1a6ef5"),
+            neutral: None,
+        }
+    }
+
+    #[test]
+    fn test_text_pairs_meet_wcag_target() {
+        let target = ContrastTarget::Wcag2(WCAG2Params {
+            level: WCAG2Level::AAA,
+            size: WCAG2Size::Small,
+        });
+        let theme = generate_theme(&seeds(), Some(ThemeOptions { contrast: target, ..Default::default() }));
+        assert!(theme.light.meets(target));
+        assert!(theme.dark.meets(target));
+    }
+
+    #[test]
+    fn test_text_pairs_meet_apca_target() {
+        let target = ContrastTarget::Apca(75.0);
+        let theme = generate_theme(&seeds(), Some(ThemeOptions { contrast: target, ..Default::default() }));
+        assert!(theme.light.meets(target));
+        assert!(theme.dark.meets(target));
+    }
+
+    #[test]
+    fn test_status_hues_lean_toward_primary() {
+        let theme = generate_theme(&seeds(), None);
+        let danger_hue = theme.light.danger.to_oklch().h;
+        // Red rotates toward the purple primary (hue ~290) by at most 15 degrees
+        assert!((DANGER_HUE - 16.0..DANGER_HUE).contains(&danger_hue));
+    }
+}
@@ -0,0 +1,485 @@
+// W3C Design Tokens Community Group (DTCG) color token import and export
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::color_space::*;
+use crate::{BigColor, ColorFormat};
+
+/// The value of a color token
+#[derive(Debug, Clone)]
+pub enum TokenValue {
+    Color(BigColor),
+    /// A reference to another token by its dotted path, such as `color.brand.500`
+    Alias(String),
+}
+
+/// A color token with its optional description
+#[derive(Debug, Clone)]
+pub struct ColorToken {
+    pub value: TokenValue,
+    pub description: Option<String>,
+}
+
+/// Errors raised while reading or resolving design tokens
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenError {
+    /// The document is not valid JSON
+    Json(String),
+    /// A color token's value could not be parsed
+    InvalidValue { path: String, message: String },
+    /// No color token exists at the requested path
+    UnknownToken(String),
+    /// An alias points to a path that does not exist
+    UnknownAlias { path: String, alias: String },
+    /// An alias points to a token that is not a color
+    NotAColor { path: String, alias: String },
+    /// Aliases form a cycle; the paths are listed in resolution order
+    AliasCycle(Vec<String>),
+    /// A path would place a token inside another token
+    NestedPath { path: String, token: String },
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::Json(message) => write!(f, "invalid JSON: {}", message),
+            TokenError::InvalidValue { path, message } => write!(f, "invalid value for {}: {}", path, message),
+            TokenError::UnknownToken(path) => write!(f, "no color token at {}", path),
+            TokenError::UnknownAlias { path, alias } => write!(f, "{} refers to unknown token {{{}}}", path, alias),
+            TokenError::NotAColor { path, alias } => write!(f, "{} refers to {{{}}}, which is not a color", path, alias),
+            TokenError::AliasCycle(paths) => write!(f, "alias cycle: {}", paths.join(" -> ")),
+            TokenError::NestedPath { path, token } => write!(f, "{} would nest a token inside token {}", path, token),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// How exported color values are written
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TokenValueFormat {
+    /// A hex string, `"#1a6ef5"`
+    #[default]
+    Hex,
+    /// A structured sRGB value with `colorSpace`, `components`, `alpha` and `hex`
+    Srgb,
+    /// A structured OKLCH value with `colorSpace`, `components`, `alpha` and `hex`
+    Oklch,
+}
+
+/// A set of color tokens keyed by dotted path
+#[derive(Debug, Clone, Default)]
+pub struct DesignTokens {
+    tokens: BTreeMap<String, ColorToken>,
+    /// Paths of tokens of other types, so aliases to them give a clear error
+    other_tokens: BTreeSet<String>,
+}
+
+/// Reads a number or the keyword `none` (treated as 0) from a component
+fn component(value: &Value) -> Option<f32> {
+    match value {
+        Value::Number(n) => n.as_f64().map(|n| n as f32),
+        Value::String(s) if s == "none" => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Parses a structured value such as
+/// `{"colorSpace": "oklch", "components": [0.6, 0.2, 260], "alpha": 1}`
+fn parse_structured(object: &Map<String, Value>) -> Result<BigColor, String> {
+    let space = object
+        .get("colorSpace")
+        .and_then(Value::as_str)
+        .ok_or("missing colorSpace")?;
+    let components = object
+        .get("components")
+        .and_then(Value::as_array)
+        .ok_or("missing components")?
+        .iter()
+        .map(component)
+        .collect::<Option<Vec<f32>>>()
+        .ok_or("components must be numbers or \"none\"")?;
+    let [c0, c1, c2] = components[..] else {
+        return Err(format!("expected 3 components, found {}", components.len()));
+    };
+    let alpha = match object.get("alpha") {
+        Some(value) => component(value).ok_or("alpha must be a number")?,
+        None => 1.0,
+    };
+
+    let color = match space {
+        "srgb" => {
+            let xyz = linear_srgb_to_xyz_d65([c0, c1, c2].map(srgb_to_linear), alpha);
+            BigColor::from_xyz_d65(xyz, ColorFormat::HEX)
+        }
+        "srgb-linear" => BigColor::from_xyz_d65(linear_srgb_to_xyz_d65([c0, c1, c2], alpha), ColorFormat::HEX),
+        "display-p3" => {
+            let xyz = linear_display_p3_to_xyz_d65([c0, c1, c2].map(srgb_to_linear), alpha);
+            BigColor::from_xyz_d65(xyz, ColorFormat::HEX)
+        }
+        "hsl" => {
+            let rgb = hsl_to_rgb(c0 / 360.0, c1 / 100.0, c2 / 100.0);
+            BigColor::from_rgb(rgb.r, rgb.g, rgb.b, alpha)
+        }
+        "hwb" => {
+            // HWB is HSV with value = 1 - blackness and saturation = 1 - whiteness / value.
+            // Whiteness and blackness adding up to more than 1 is a grey of
+            // white / (white + black), as in CSS Color 4.
+            let (mut white, mut black) = ((c1 / 100.0).max(0.0), (c2 / 100.0).max(0.0));
+            if white + black > 1.0 {
+                let sum = white + black;
+                white /= sum;
+                black /= sum;
+            }
+            let value = 1.0 - black;
+            let saturation = if value > 0.0 { 1.0 - white / value } else { 0.0 };
+            let rgb = hsv_to_rgb(c0 / 360.0, saturation.max(0.0), value.max(0.0));
+            BigColor::from_rgb(rgb.r, rgb.g, rgb.b, alpha)
+        }
+        "lab" => {
+            let xyz = lab_to_xyz_d50(Lab { l: c0, a: c1, b: c2, alpha });
+            BigColor::from_xyz_d65(xyz_d50_to_xyz_d65(xyz), ColorFormat::LAB)
+        }
+        "lch" => {
+            let xyz = lab_to_xyz_d50(lch_to_lab(LCH { l: c0, c: c1, h: c2, alpha }));
+            BigColor::from_xyz_d65(xyz_d50_to_xyz_d65(xyz), ColorFormat::LCH)
+        }
+        "oklab" => {
+            let mut color = BigColor::from_oklch(0.0, 0.0, 0.0, alpha);
+            color.color = oklab_to_oklch(OKLab { l: c0, a: c1, b: c2, alpha }).into();
+            color.format = ColorFormat::OKLAB;
+            color
+        }
+        "oklch" => BigColor::from_oklch(c0, c1, c2, alpha),
+        "xyz-d65" | "xyz" => BigColor::from_xyz_d65(XyzD65 { x: c0, y: c1, z: c2, a: alpha }, ColorFormat::HEX),
+        "xyz-d50" => {
+            let xyz = xyz_d50_to_xyz_d65(XyzD50 { x: c0, y: c1, z: c2, a: alpha });
+            BigColor::from_xyz_d65(xyz, ColorFormat::HEX)
+        }
+        other => {
+            // Spaces we cannot convert may still carry an sRGB fallback
+            let hex = object.get("hex").and_then(Value::as_str);
+            return hex
+                .map(BigColor::new)
+                .filter(BigColor::is_valid)
+                .ok_or_else(|| format!("unsupported colorSpace \"{}\" without a hex fallback", other));
+        }
+    };
+    Ok(color)
+}
+
+/// Parses a `$value`: an alias, a color string or a structured color
+fn parse_value(value: &Value) -> Result<TokenValue, String> {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            if let Some(path) = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                return Ok(TokenValue::Alias(path.to_string()));
+            }
+            let color = BigColor::new(s);
+            if color.is_valid() {
+                Ok(TokenValue::Color(color))
+            } else {
+                Err(format!("\"{}\" is not a color", s))
+            }
+        }
+        Value::Object(object) => parse_structured(object).map(TokenValue::Color),
+        _ => Err("expected a string or a structured color".to_string()),
+    }
+}
+
+/// Rounds a component for export so documents stay readable
+fn round(value: f32) -> f64 {
+    (value as f64 * 10_000.0).round() / 10_000.0
+}
+
+impl DesignTokens {
+    /// Creates an empty token set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the color tokens of a DTCG JSON document
+    ///
+    /// Groups may set `$type` for their children. Tokens of other types are
+    /// skipped. Tokens Studio's legacy `value`/`type` keys are accepted too.
+    pub fn parse(json: &str) -> Result<Self, TokenError> {
+        let root: Value = serde_json::from_str(json).map_err(|e| TokenError::Json(e.to_string()))?;
+        let mut tokens = DesignTokens::new();
+        if let Value::Object(object) = &root {
+            tokens.read_group(object, "", None)?;
+        }
+        Ok(tokens)
+    }
+
+    fn read_group(&mut self, group: &Map<String, Value>, prefix: &str, inherited: Option<&str>) -> Result<(), TokenError> {
+        let group_type = group.get("$type").and_then(Value::as_str).or(inherited);
+
+        for (key, child) in group {
+            let Value::Object(child) = child else { continue };
+            if key.starts_with('$') {
+                continue;
+            }
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            // Dots separate path segments and braces mark aliases, so a name
+            // with them could not be referenced and could collide with a group
+            if key.contains(['.', '{', '}']) {
+                let message = "names cannot contain '.', '{' or '}'".to_string();
+                return Err(TokenError::InvalidValue { path, message });
+            }
+
+            let (value, token_type) = match (child.get("$value"), child.get("value")) {
+                (Some(value), _) => (value, child.get("$type").and_then(Value::as_str).or(group_type)),
+                (None, Some(value)) if child.get("type").and_then(Value::as_str) == Some("color") => {
+                    (value, Some("color"))
+                }
+                _ => {
+                    self.read_group(child, &path, group_type)?;
+                    continue;
+                }
+            };
+
+            if token_type != Some("color") {
+                self.other_tokens.insert(path);
+                continue;
+            }
+            let value = parse_value(value).map_err(|message| TokenError::InvalidValue { path: path.clone(), message })?;
+            let description = child
+                .get("$description")
+                .or_else(|| child.get("description"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            self.check_nesting(&path)?;
+            self.tokens.insert(path, ColorToken { value, description });
+        }
+        Ok(())
+    }
+
+    /// Adds or replaces a token
+    ///
+    /// Fails if the path is inside an existing token, such as `a.b` next to
+    /// `a`, or has existing tokens inside it, since DTCG groups and tokens
+    /// cannot share a path.
+    pub fn insert(&mut self, path: &str, value: TokenValue) -> Result<(), TokenError> {
+        self.check_nesting(path)?;
+        self.tokens.insert(path.to_string(), ColorToken { value, description: None });
+        Ok(())
+    }
+
+    /// Fails if a token at `path` would sit inside another token or hold one
+    fn check_nesting(&self, path: &str) -> Result<(), TokenError> {
+        let nested = |outer: &str, inner: &str| inner.strip_prefix(outer).is_some_and(|rest| rest.starts_with('.'));
+        match self.tokens.keys().find(|token| nested(token, path) || nested(path, token)) {
+            Some(token) => Err(TokenError::NestedPath { path: path.to_string(), token: token.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the token at a path
+    pub fn get(&self, path: &str) -> Option<&ColorToken> {
+        self.tokens.get(path)
+    }
+
+    /// Iterates over all color tokens in path order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ColorToken)> {
+        self.tokens.iter().map(|(path, token)| (path.as_str(), token))
+    }
+
+    /// Returns the number of color tokens
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns true if there are no color tokens
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Resolves a token to its color, following aliases
+    pub fn resolve(&self, path: &str) -> Result<BigColor, TokenError> {
+        if !self.tokens.contains_key(path) {
+            return Err(TokenError::UnknownToken(path.to_string()));
+        }
+        self.resolve_chain(path, &mut Vec::new())
+    }
+
+    fn resolve_chain(&self, path: &str, chain: &mut Vec<String>) -> Result<BigColor, TokenError> {
+        if let Some(start) = chain.iter().position(|p| p == path) {
+            let mut cycle = chain[start..].to_vec();
+            cycle.push(path.to_string());
+            return Err(TokenError::AliasCycle(cycle));
+        }
+
+        match &self.tokens[path].value {
+            TokenValue::Color(color) => Ok(color.clone()),
+            TokenValue::Alias(target) => {
+                if !self.tokens.contains_key(target) {
+                    let (path, alias) = (path.to_string(), target.clone());
+                    return Err(if self.other_tokens.contains(target) {
+                        TokenError::NotAColor { path, alias }
+                    } else {
+                        TokenError::UnknownAlias { path, alias }
+                    });
+                }
+                chain.push(path.to_string());
+                let color = self.resolve_chain(target, chain);
+                chain.pop();
+                color
+            }
+        }
+    }
+
+    /// Resolves every token, failing on the first broken alias or cycle
+    pub fn resolve_all(&self) -> Result<BTreeMap<String, BigColor>, TokenError> {
+        self.tokens
+            .keys()
+            .map(|path| Ok((path.clone(), self.resolve(path)?)))
+            .collect()
+    }
+
+    /// Writes the tokens as a DTCG JSON document, nesting groups by path and
+    /// keeping aliases as references
+    pub fn to_json(&self, format: TokenValueFormat) -> String {
+        let mut root = Map::new();
+        for (path, token) in &self.tokens {
+            let mut entry = Map::new();
+            entry.insert("$type".to_string(), json!("color"));
+            let value = match &token.value {
+                TokenValue::Alias(target) => json!(format!("{{{}}}", target)),
+                TokenValue::Color(color) => color_value(color, format),
+            };
+            entry.insert("$value".to_string(), value);
+            if let Some(description) = &token.description {
+                entry.insert("$description".to_string(), json!(description));
+            }
+
+            // Walk down to the token's group, creating groups as needed
+            let mut group = &mut root;
+            let mut segments: Vec<&str> = path.split('.').collect();
+            let name = segments.pop().unwrap_or_default();
+            for segment in segments {
+                group = group
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .expect("insert and parse never nest tokens inside tokens");
+            }
+            group.insert(name.to_string(), Value::Object(entry));
+        }
+        serde_json::to_string_pretty(&Value::Object(root)).unwrap_or_default()
+    }
+}
+
+/// Writes one color as a `$value`
+fn color_value(color: &BigColor, format: TokenValueFormat) -> Value {
+    let hex = color.to_hex_string(false);
+    let alpha = color.get_alpha();
+    let (space, components) = match format {
+        TokenValueFormat::Hex if alpha < 1.0 => return json!(color.to_hex8_string(false)),
+        TokenValueFormat::Hex => return json!(hex),
+        TokenValueFormat::Srgb => {
+            let linear = oklch_to_linear_srgb(color.to_oklch());
+            ("srgb", linear.map(|c| round(clamp_01(linear_to_srgb(c)))))
+        }
+        TokenValueFormat::Oklch => {
+            let oklch = color.to_oklch();
+            ("oklch", [round(oklch.l), round(oklch.c), round(oklch.h)])
+        }
+    };
+    json!({
+        "colorSpace": space,
+        "components": components,
+        "alpha": round(alpha),
+        "hex": hex,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r##"{
+        "color": {
+            "$type": "color",
+            "brand": {
+                "500": { "$value": "#1a6ef5", "$description": "Primary brand" },
+                "600": { "$value": { "colorSpace": "oklch", "components": [0.5, 0.2, 260], "alpha": 0.5 } }
+            },
+            "action": { "$value": "{color.brand.500}" },
+            "link": { "$value": "{color.action}" }
+        },
+        "spacing": { "small": { "$type": "dimension", "$value": "4px" } },
+        "legacy": { "accent": { "value": "rgb(255, 0, 0)", "type": "color" } }
+    }"##;
+
+    #[test]
+    fn test_parses_groups_aliases_and_structured_values() {
+        let tokens = DesignTokens::parse(DOCUMENT).unwrap();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens.get("color.brand.500").unwrap().description.as_deref(), Some("Primary brand"));
+        assert_eq!(tokens.resolve("color.link").unwrap().to_hex_string(false), "#1a6ef5");
+        assert_eq!(tokens.resolve("color.brand.600").unwrap().get_alpha(), 0.5);
+        assert_eq!(tokens.resolve("legacy.accent").unwrap().to_hex_string(false), "#ff0000");
+
+        let srgb = r#"{"c": {"$type": "color", "$value": {"colorSpace": "srgb", "components": [1, 0, 0]}}}"#;
+        let tokens = DesignTokens::parse(srgb).unwrap();
+        assert_eq!(tokens.resolve("c").unwrap().to_hex_string(false), "#ff0000");
+
+        let hwb = r#"{"g": {"$type": "color", "$value": {"colorSpace": "hwb", "components": [0, 70, 60]}}}"#;
+        let grey = DesignTokens::parse(hwb).unwrap().resolve("g").unwrap().to_rgb();
+        assert_eq!((grey.r, grey.g, grey.b), (137, 137, 137));
+    }
+
+    #[test]
+    fn test_reports_cycles_and_broken_aliases() {
+        let mut tokens = DesignTokens::new();
+        tokens.insert("a", TokenValue::Alias("b".to_string())).unwrap();
+        tokens.insert("b", TokenValue::Alias("c".to_string())).unwrap();
+        tokens.insert("c", TokenValue::Alias("a".to_string())).unwrap();
+        tokens.insert("d", TokenValue::Alias("missing".to_string())).unwrap();
+        let cycle = ["a", "b", "c", "a"].map(String::from).to_vec();
+        assert_eq!(tokens.resolve("a"), Err(TokenError::AliasCycle(cycle)));
+        assert!(matches!(tokens.resolve("d"), Err(TokenError::UnknownAlias { .. })));
+
+        let json = r#"{"size": {"$type": "dimension", "$value": "4px"}, "c": {"$type": "color", "$value": "{size}"}}"#;
+        let tokens = DesignTokens::parse(json).unwrap();
+        assert!(matches!(tokens.resolve("c"), Err(TokenError::NotAColor { .. })));
+    }
+
+    #[test]
+    fn test_rejects_tokens_nested_in_tokens() {
+        let mut tokens = DesignTokens::new();
+        let red = || TokenValue::Color(BigColor::new("red"));
+        tokens.insert("a", red()).unwrap();
+        tokens.insert("a", red()).unwrap();
+        tokens.insert("ab.c", red()).unwrap();
+        let nested = TokenError::NestedPath { path: "a.b".to_string(), token: "a".to_string() };
+        assert_eq!(tokens.insert("a.b", red()), Err(nested));
+        assert!(matches!(tokens.insert("ab", red()), Err(TokenError::NestedPath { .. })));
+        assert_eq!(tokens.len(), 2);
+        assert!(DesignTokens::parse(&tokens.to_json(TokenValueFormat::Hex)).is_ok());
+
+        let dotted = r#"{"a": {"$type": "color", "$value": "red"}, "a.b": {"$type": "color", "$value": "blue"}}"#;
+        assert!(matches!(DesignTokens::parse(dotted), Err(TokenError::InvalidValue { path, .. }) if path == "a.b"));
+        let shadowed = r#"{"a.b": {"$type": "color", "$value": "red"}, "a": {"b": {"$type": "color", "$value": "blue"}}}"#;
+        assert!(matches!(DesignTokens::parse(shadowed), Err(TokenError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_export_round_trips() {
+        let tokens = DesignTokens::parse(DOCUMENT).unwrap();
+        for format in [TokenValueFormat::Hex, TokenValueFormat::Srgb, TokenValueFormat::Oklch] {
+            let written = DesignTokens::parse(&tokens.to_json(format)).unwrap();
+            assert!(matches!(written.get("color.action").unwrap().value, TokenValue::Alias(_)));
+            assert_eq!(
+                written.resolve("color.brand.500").unwrap().to_hex_string(false),
+                "#1a6ef5",
+                "{:?}",
+                format
+            );
+        }
+    }
+}
@@ -0,0 +1,394 @@
+// Xcode asset catalog color sets (.colorset/Contents.json)
+
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::color_space::*;
+use crate::{BigColor, ColorFormat};
+
+/// Color space of a color set entry
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum XcodeColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    /// sRGB with components outside 0 to 1 for wide-gamut colors
+    ExtendedSrgb,
+    ExtendedLinearSrgb,
+    /// Grayscale with a 2.2 gamma, stored as a `white` component
+    GrayGamma22,
+    /// Grayscale with the sRGB curve, unclamped
+    ExtendedGray,
+}
+
+impl XcodeColorSpace {
+    /// The `color-space` string in Contents.json
+    pub fn name(self) -> &'static str {
+        match self {
+            XcodeColorSpace::Srgb => "srgb",
+            XcodeColorSpace::DisplayP3 => "display-p3",
+            XcodeColorSpace::ExtendedSrgb => "extended-srgb",
+            XcodeColorSpace::ExtendedLinearSrgb => "extended-linear-srgb",
+            XcodeColorSpace::GrayGamma22 => "gray-gamma-22",
+            XcodeColorSpace::ExtendedGray => "extended-gray",
+        }
+    }
+
+    /// Looks up a `color-space` string
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            XcodeColorSpace::Srgb,
+            XcodeColorSpace::DisplayP3,
+            XcodeColorSpace::ExtendedSrgb,
+            XcodeColorSpace::ExtendedLinearSrgb,
+            XcodeColorSpace::GrayGamma22,
+            XcodeColorSpace::ExtendedGray,
+        ]
+        .into_iter()
+        .find(|space| space.name() == name)
+    }
+
+    fn is_gray(self) -> bool {
+        matches!(self, XcodeColorSpace::GrayGamma22 | XcodeColorSpace::ExtendedGray)
+    }
+
+    fn is_extended(self) -> bool {
+        matches!(
+            self,
+            XcodeColorSpace::ExtendedSrgb | XcodeColorSpace::ExtendedLinearSrgb | XcodeColorSpace::ExtendedGray
+        )
+    }
+}
+
+/// How components are written, matching Xcode's Input Method menu
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ComponentEncoding {
+    /// `"0.102"`
+    #[default]
+    Float,
+    /// `"26"`
+    EightBit,
+    /// `"0x1A"`
+    Hex,
+}
+
+/// The light or dark appearance an entry applies to
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Luminosity {
+    #[default]
+    Any,
+    Light,
+    Dark,
+}
+
+/// One color of a color set with the appearance it applies to
+#[derive(Debug, Clone)]
+pub struct ColorsetEntry {
+    pub luminosity: Luminosity,
+    pub high_contrast: bool,
+    pub color_space: XcodeColorSpace,
+    pub color: BigColor,
+}
+
+/// Options for writing Contents.json
+#[derive(Debug, Clone, Copy)]
+pub struct ColorsetOptions {
+    pub encoding: ComponentEncoding,
+    /// Decimals of floating components
+    pub precision: usize,
+}
+
+impl Default for ColorsetOptions {
+    fn default() -> Self {
+        ColorsetOptions {
+            encoding: ComponentEncoding::Float,
+            precision: 3,
+        }
+    }
+}
+
+/// Errors from reading a Contents.json file
+#[derive(Debug, Clone, PartialEq)]
+pub enum XcodeError {
+    /// The document is not valid JSON
+    Json(String),
+    /// The document is JSON but not a color set
+    Invalid(String),
+    /// A color space this crate does not convert
+    UnsupportedColorSpace(String),
+}
+
+impl fmt::Display for XcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XcodeError::Json(message) => write!(f, "invalid JSON: {}", message),
+            XcodeError::Invalid(message) => write!(f, "invalid color set: {}", message),
+            XcodeError::UnsupportedColorSpace(space) => write!(f, "unsupported color space {}", space),
+        }
+    }
+}
+
+impl std::error::Error for XcodeError {}
+
+/// Reads a component written as a float, an 8-bit integer or a hex byte
+fn component(value: &Value) -> Option<f32> {
+    match value {
+        Value::Number(number) => {
+            let n = number.as_f64()? as f32;
+            Some(if number.is_f64() || n <= 1.0 { n } else { n / 255.0 })
+        }
+        Value::String(text) => {
+            let text = text.trim();
+            if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                Some(u8::from_str_radix(hex, 16).ok()? as f32 / 255.0)
+            } else if text.contains('.') {
+                text.parse().ok()
+            } else {
+                Some(text.parse::<u8>().ok()? as f32 / 255.0)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Applies a transfer function to the magnitude, keeping the sign of
+/// extended components
+fn signed(value: f32, transfer: fn(f32) -> f32) -> f32 {
+    value.signum() * transfer(value.abs())
+}
+
+/// Reads the `color` object of an entry
+fn parse_color(color: &Value) -> Result<(XcodeColorSpace, BigColor), XcodeError> {
+    let space_name = color.get("color-space").and_then(Value::as_str).unwrap_or("srgb");
+    let space = XcodeColorSpace::from_name(space_name)
+        .ok_or_else(|| XcodeError::UnsupportedColorSpace(space_name.to_string()))?;
+    let components = color
+        .get("components")
+        .and_then(Value::as_object)
+        .ok_or_else(|| XcodeError::Invalid("color without components".to_string()))?;
+    let read = |key: &str| {
+        components
+            .get(key)
+            .and_then(component)
+            .ok_or_else(|| XcodeError::Invalid(format!("missing or invalid {} component", key)))
+    };
+    let alpha = match components.get("alpha") {
+        Some(_) => read("alpha")?,
+        None => 1.0,
+    };
+
+    let xyz = if space.is_gray() {
+        let white = read("white")?;
+        let linear = match space {
+            XcodeColorSpace::GrayGamma22 => white.max(0.0).powf(2.2),
+            _ => signed(white, srgb_to_linear),
+        };
+        linear_srgb_to_xyz_d65([linear; 3], alpha)
+    } else {
+        let rgb = [read("red")?, read("green")?, read("blue")?];
+        match space {
+            XcodeColorSpace::DisplayP3 => linear_display_p3_to_xyz_d65(rgb.map(srgb_to_linear), alpha),
+            XcodeColorSpace::ExtendedLinearSrgb => linear_srgb_to_xyz_d65(rgb, alpha),
+            _ => linear_srgb_to_xyz_d65(rgb.map(|c| signed(c, srgb_to_linear)), alpha),
+        }
+    };
+    Ok((space, BigColor::from_xyz_d65(xyz, ColorFormat::RGB)))
+}
+
+/// Writes one component in the chosen encoding
+fn write_component(value: f32, options: &ColorsetOptions) -> Value {
+    match options.encoding {
+        ComponentEncoding::Float => json!(format!("{:.*}", options.precision, value)),
+        ComponentEncoding::EightBit => json!(((clamp_01(value) * 255.0).round() as u8).to_string()),
+        ComponentEncoding::Hex => json!(format!("0x{:02X}", (clamp_01(value) * 255.0).round() as u8)),
+    }
+}
+
+/// A color set: the colors of one named color in an asset catalog
+#[derive(Debug, Clone, Default)]
+pub struct Colorset {
+    pub entries: Vec<ColorsetEntry>,
+}
+
+impl Colorset {
+    /// Creates a color set with a single color for any appearance
+    pub fn new(color: BigColor, color_space: XcodeColorSpace) -> Self {
+        Colorset {
+            entries: vec![ColorsetEntry {
+                luminosity: Luminosity::Any,
+                high_contrast: false,
+                color_space,
+                color,
+            }],
+        }
+    }
+
+    /// Creates a color set with any and dark appearances from a pair of colors
+    pub fn light_dark(light: BigColor, dark: BigColor, color_space: XcodeColorSpace) -> Self {
+        Colorset::new(light, color_space).with_appearance(Luminosity::Dark, false, dark)
+    }
+
+    /// Adds or replaces the color for an appearance, using the color space of
+    /// the first entry
+    pub fn with_appearance(mut self, luminosity: Luminosity, high_contrast: bool, color: BigColor) -> Self {
+        let color_space = self.entries.first().map(|entry| entry.color_space).unwrap_or_default();
+        self.entries
+            .retain(|entry| !(entry.luminosity == luminosity && entry.high_contrast == high_contrast));
+        self.entries.push(ColorsetEntry { luminosity, high_contrast, color_space, color });
+        self
+    }
+
+    /// Returns the color iOS would pick for an appearance: an exact match,
+    /// then the same luminosity without high contrast, then the any entry
+    pub fn color(&self, luminosity: Luminosity, high_contrast: bool) -> Option<&BigColor> {
+        let find = |luminosity: Luminosity, high_contrast: bool| {
+            self.entries
+                .iter()
+                .find(|entry| entry.luminosity == luminosity && entry.high_contrast == high_contrast)
+                .map(|entry| &entry.color)
+        };
+        find(luminosity, high_contrast)
+            .or_else(|| find(luminosity, false))
+            .or_else(|| find(Luminosity::Any, high_contrast))
+            .or_else(|| find(Luminosity::Any, false))
+    }
+
+    /// Reads a Contents.json file
+    pub fn parse(json: &str) -> Result<Self, XcodeError> {
+        let root: Value = serde_json::from_str(json).map_err(|e| XcodeError::Json(e.to_string()))?;
+        let colors = root
+            .get("colors")
+            .and_then(Value::as_array)
+            .ok_or_else(|| XcodeError::Invalid("no colors array".to_string()))?;
+
+        let mut colorset = Colorset::default();
+        for entry in colors {
+            let Some(color) = entry.get("color") else { continue };
+            let (color_space, color) = parse_color(color)?;
+            let mut luminosity = Luminosity::Any;
+            let mut high_contrast = false;
+            for appearance in entry.get("appearances").and_then(Value::as_array).into_iter().flatten() {
+                let value = appearance.get("value").and_then(Value::as_str);
+                match (appearance.get("appearance").and_then(Value::as_str), value) {
+                    (Some("luminosity"), Some("light")) => luminosity = Luminosity::Light,
+                    (Some("luminosity"), Some("dark")) => luminosity = Luminosity::Dark,
+                    (Some("contrast"), Some("high")) => high_contrast = true,
+                    _ => {}
+                }
+            }
+            colorset.entries.push(ColorsetEntry { luminosity, high_contrast, color_space, color });
+        }
+        Ok(colorset)
+    }
+
+    /// Writes a Contents.json file
+    ///
+    /// Colors outside sRGB or Display P3 are clipped unless the entry uses an
+    /// extended color space. 8-bit and hex encodings always clip.
+    pub fn to_json(&self, options: Option<ColorsetOptions>) -> String {
+        let options = options.unwrap_or_default();
+        let colors: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let space = entry.color_space;
+                let oklab = entry.color.to_oklab();
+                let xyz = oklab_to_xyz_d65(oklab);
+                let clip = |value: f32| if space.is_extended() { value } else { clamp_01(value) };
+
+                let mut components = Map::new();
+                components.insert("alpha".to_string(), write_component(oklab.alpha, &options));
+                if space.is_gray() {
+                    let white = match space {
+                        XcodeColorSpace::GrayGamma22 => xyz.y.max(0.0).powf(1.0 / 2.2),
+                        _ => signed(xyz.y, linear_to_srgb),
+                    };
+                    components.insert("white".to_string(), write_component(clip(white), &options));
+                } else {
+                    let rgb = match space {
+                        XcodeColorSpace::DisplayP3 => xyz_d65_to_linear_display_p3(xyz).map(linear_to_srgb),
+                        XcodeColorSpace::ExtendedLinearSrgb => xyz_d65_to_linear_srgb(xyz),
+                        _ => xyz_d65_to_linear_srgb(xyz).map(|c| signed(c, linear_to_srgb)),
+                    };
+                    for (key, value) in ["red", "green", "blue"].into_iter().zip(rgb) {
+                        components.insert(key.to_string(), write_component(clip(value), &options));
+                    }
+                }
+
+                let mut appearances = Vec::new();
+                match entry.luminosity {
+                    Luminosity::Any => {}
+                    Luminosity::Light => appearances.push(json!({ "appearance": "luminosity", "value": "light" })),
+                    Luminosity::Dark => appearances.push(json!({ "appearance": "luminosity", "value": "dark" })),
+                }
+                if entry.high_contrast {
+                    appearances.push(json!({ "appearance": "contrast", "value": "high" }));
+                }
+
+                let mut object = Map::new();
+                if !appearances.is_empty() {
+                    object.insert("appearances".to_string(), Value::Array(appearances));
+                }
+                object.insert(
+                    "color".to_string(),
+                    json!({ "color-space": space.name(), "components": components }),
+                );
+                object.insert("idiom".to_string(), json!("universal"));
+                Value::Object(object)
+            })
+            .collect();
+
+        let root = json!({
+            "colors": colors,
+            "info": { "author": "xcode", "version": 1 },
+        });
+        serde_json::to_string_pretty(&root).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_encodings_and_appearances() {
+        let json = r#"{
+            "colors": [
+                { "color": { "color-space": "srgb",
+                    "components": { "alpha": "1.000", "red": "0x1A", "green": "110", "blue": "0.961" } },
+                  "idiom": "universal" },
+                { "appearances": [ { "appearance": "luminosity", "value": "dark" } ],
+                  "color": { "color-space": "display-p3",
+                    "components": { "alpha": "1.000", "red": "1.000", "green": "0.000", "blue": "0.000" } },
+                  "idiom": "universal" },
+                { "appearances": [ { "appearance": "contrast", "value": "high" } ],
+                  "color": { "color-space": "gray-gamma-22", "components": { "alpha": "0.5", "white": "0.000" } },
+                  "idiom": "universal" }
+            ],
+            "info": { "author": "xcode", "version": 1 }
+        }"#;
+        let colorset = Colorset::parse(json).unwrap();
+        assert_eq!(colorset.color(Luminosity::Light, false).unwrap().to_hex_string(false), "#1a6ef5");
+        // Display P3 red is outside sRGB
+        let dark = colorset.color(Luminosity::Dark, true).unwrap();
+        assert!(dark.to_oklch().c > BigColor::new("#ff0000").to_oklch().c);
+        assert_eq!(colorset.color(Luminosity::Any, true).unwrap().get_alpha(), 0.5);
+    }
+
+    #[test]
+    fn test_writes_color_spaces_and_encodings() {
+        let colorset = Colorset::light_dark(BigColor::new("#1a6ef5"), BigColor::new("#5b9bff"), XcodeColorSpace::Srgb);
+        let options = ColorsetOptions { encoding: ComponentEncoding::Hex, ..Default::default() };
+        let written = colorset.to_json(Some(options));
+        assert!(written.contains(r#""red": "0x1A""#));
+        assert!(written.contains(r#""value": "dark""#));
+
+        let reread = Colorset::parse(&written).unwrap();
+        assert_eq!(reread.color(Luminosity::Dark, false).unwrap().to_hex_string(false), "#5b9bff");
+
+        let p3 = Colorset::new(BigColor::new("#ff0000"), XcodeColorSpace::DisplayP3).to_json(None);
+        assert!(p3.contains(r#""color-space": "display-p3""#));
+        assert!(p3.contains(r#""red": "0.918""#));
+    }
+}
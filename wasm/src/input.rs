@@ -0,0 +1,71 @@
+// Conversion between JavaScript values and colors
+
+use bigcolor::BigColor;
+use js_sys::{Function, Object, Reflect, TypeError};
+use wasm_bindgen::{JsCast, JsValue};
+
+fn number(value: &JsValue, key: &str) -> Option<f64> {
+    Reflect::get(value, &JsValue::from_str(key)).ok()?.as_f64()
+}
+
+/// Saturation, lightness and value as TinyColor reads them: values above 1
+/// are percentages
+fn unit(value: f64) -> f32 {
+    if value > 1.0 {
+        (value / 100.0) as f32
+    } else {
+        value as f32
+    }
+}
+
+/// Reads a color string, a `BigColor` or an `{r, g, b}`, `{h, s, l}` or
+/// `{h, s, v}` object with an optional `a`. Strings that do not parse give an
+/// invalid color; other values throw a `TypeError`.
+pub(crate) fn color_from_js(value: &JsValue) -> Result<BigColor, JsValue> {
+    if let Some(input) = value.as_string() {
+        return Ok(BigColor::new(&input));
+    }
+    if value.is_object() {
+        // A BigColor instance: copy its exact OKLCH values
+        let to_oklch = Reflect::get(value, &JsValue::from_str("toOklch")).ok();
+        if let Some(to_oklch) = to_oklch.and_then(|f| f.dyn_into::<Function>().ok()) {
+            let oklch = to_oklch.call0(value)?;
+            let channel = |key| number(&oklch, key).unwrap_or_default() as f32;
+            return Ok(BigColor::from_oklch(channel("l"), channel("c"), channel("h"), channel("alpha")));
+        }
+
+        let alpha = number(value, "a").unwrap_or(1.0) as f32;
+        let channels = |keys: [&str; 3]| -> Option<[f64; 3]> {
+            Some([number(value, keys[0])?, number(value, keys[1])?, number(value, keys[2])?])
+        };
+        if let Some([r, g, b]) = channels(["r", "g", "b"]) {
+            let channel = |value: f64| value.round().clamp(0.0, 255.0) as u8;
+            return Ok(BigColor::from_rgb(channel(r), channel(g), channel(b), alpha));
+        }
+        if let Some([h, s, l]) = channels(["h", "s", "l"]) {
+            return Ok(BigColor::from_hsl(h as f32, unit(s), unit(l), alpha));
+        }
+        if let Some([h, s, v]) = channels(["h", "s", "v"]) {
+            return Ok(BigColor::from_hsv(h as f32, unit(s), unit(v), alpha));
+        }
+    }
+    Err(TypeError::new("expected a color string, a BigColor, or an {r, g, b}, {h, s, l} or {h, s, v} object").into())
+}
+
+/// Builds a plain object from numeric fields
+pub(crate) fn object(fields: &[(&str, f32)]) -> Object {
+    let object = Object::new();
+    for (key, value) in fields {
+        // Setting a property on a fresh plain object cannot fail
+        let _ = Reflect::set(&object, &JsValue::from_str(key), &JsValue::from_f64(*value as f64));
+    }
+    object
+}
+
+/// Reads an optional field of an options object
+pub(crate) fn option(options: &JsValue, key: &str) -> Option<JsValue> {
+    if !options.is_object() {
+        return None;
+    }
+    Reflect::get(options, &JsValue::from_str(key)).ok().filter(|value| !value.is_undefined())
+}
@@ -0,0 +1,476 @@
+// JavaScript bindings for bigcolor
+//
+// The API follows TinyColor's names and semantics so it can replace it:
+// modifications change the receiver and return it for chaining. Colors are
+// stored and manipulated in OKLCH.
+
+mod input;
+
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use bigcolor::{is_readable, mix, most_readable, readability, BigColor, ColorFormat, MostReadableArgs, WCAG2Level, WCAG2Params, WCAG2Size};
+use js_sys::{Array, Error, RangeError};
+use wasm_bindgen::prelude::*;
+
+use crate::input::{color_from_js, object, option};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+export interface Rgba { r: number; g: number; b: number; a: number }
+export interface Hsla { h: number; s: number; l: number; a: number }
+export interface Hsva { h: number; s: number; v: number; a: number }
+export interface Oklch { l: number; c: number; h: number; alpha: number }
+export interface Oklab { l: number; a: number; b: number; alpha: number }
+export type ColorInput =
+  | string
+  | BigColor
+  | { r: number; g: number; b: number; a?: number }
+  | { h: number; s: number; l: number; a?: number }
+  | { h: number; s: number; v: number; a?: number };
+export type ColorFormat =
+  | "rgb" | "prgb" | "hex" | "hex3" | "hex6" | "hex8" | "hsl" | "hsv" | "hsb"
+  | "lab" | "lch" | "oklab" | "oklch" | "cmyk" | "name";
+export interface WCAG2Options { level?: "AA" | "AAA"; size?: "small" | "large" }
+export interface MostReadableOptions extends WCAG2Options { includeFallbackColors?: boolean }
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ColorInput")]
+    pub type ColorInput;
+    #[wasm_bindgen(typescript_type = "WCAG2Options")]
+    pub type WCAG2Options;
+    #[wasm_bindgen(typescript_type = "MostReadableOptions")]
+    pub type MostReadableOptions;
+}
+
+/// Format names used by `toString` and `getFormat`, as in TinyColor
+const FORMATS: [(&str, ColorFormat); 15] = [
+    ("rgb", ColorFormat::RGB),
+    ("prgb", ColorFormat::PRGB),
+    ("hex", ColorFormat::HEX),
+    ("hex3", ColorFormat::HEX3),
+    ("hex6", ColorFormat::HEX6),
+    ("hex8", ColorFormat::HEX8),
+    ("hsl", ColorFormat::HSL),
+    ("hsv", ColorFormat::HSV),
+    ("hsb", ColorFormat::HSB),
+    ("lab", ColorFormat::LAB),
+    ("lch", ColorFormat::LCH),
+    ("oklab", ColorFormat::OKLAB),
+    ("oklch", ColorFormat::OKLCH),
+    ("cmyk", ColorFormat::CMYK),
+    ("name", ColorFormat::NAME),
+];
+
+fn format_by_name(name: &str) -> Option<ColorFormat> {
+    FORMATS.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, format)| *format)
+}
+
+fn format_name(format: ColorFormat) -> &'static str {
+    FORMATS.iter().find(|(_, f)| *f == format).map_or("invalid", |(name, _)| name)
+}
+
+/// Reads `{level, size}` options, defaulting to AA for small text
+fn wcag2_params(options: &JsValue) -> Result<WCAG2Params, JsValue> {
+    let text = |key| option(options, key).and_then(|value| value.as_string());
+    let level = match text("level").as_deref() {
+        None | Some("AA") => WCAG2Level::AA,
+        Some("AAA") => WCAG2Level::AAA,
+        Some(other) => return Err(RangeError::new(&format!("unknown WCAG level '{}'", other)).into()),
+    };
+    let size = match text("size").as_deref() {
+        None | Some("small") => WCAG2Size::Small,
+        Some("large") => WCAG2Size::Large,
+        Some(other) => return Err(RangeError::new(&format!("unknown text size '{}'", other)).into()),
+    };
+    Ok(WCAG2Params { level, size })
+}
+
+/// A color parsed from a string, an object or another `BigColor`
+///
+/// The color is shared between the handles that modifications return, so
+/// `c.lighten()` changes `c` as in TinyColor.
+#[wasm_bindgen(js_name = BigColor)]
+pub struct JsBigColor {
+    inner: Rc<RefCell<BigColor>>,
+}
+
+impl From<BigColor> for JsBigColor {
+    fn from(inner: BigColor) -> Self {
+        JsBigColor { inner: Rc::new(RefCell::new(inner)) }
+    }
+}
+
+fn wrap(colors: Vec<BigColor>) -> Vec<JsBigColor> {
+    colors.into_iter().map(JsBigColor::from).collect()
+}
+
+impl JsBigColor {
+    fn color(&self) -> Ref<'_, BigColor> {
+        self.inner.borrow()
+    }
+
+    /// Modifies the color in place and returns a handle to it for chaining
+    fn modified(&self, modify: impl FnOnce(&mut BigColor)) -> JsBigColor {
+        modify(&mut self.inner.borrow_mut());
+        JsBigColor { inner: Rc::clone(&self.inner) }
+    }
+}
+
+#[wasm_bindgen(js_class = BigColor)]
+impl JsBigColor {
+    /// Parses a color. Unparseable strings give a color whose `isValid()`
+    /// is false, as in TinyColor; other unsupported values throw a
+    /// `TypeError`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: &ColorInput) -> Result<JsBigColor, JsValue> {
+        color_from_js(input).map(JsBigColor::from)
+    }
+
+    /// Parses a color and throws an `Error` if it is invalid
+    pub fn parse(input: &ColorInput) -> Result<JsBigColor, JsValue> {
+        let color = color_from_js(input)?;
+        if !color.is_valid() {
+            let message = format!("invalid color '{}'", color.get_original_input());
+            return Err(Error::new(&message).into());
+        }
+        Ok(color.into())
+    }
+
+    /// Creates a color from OKLCH; values outside sRGB are kept
+    #[wasm_bindgen(js_name = fromOklch)]
+    pub fn from_oklch(l: f32, c: f32, h: f32, alpha: Option<f32>) -> JsBigColor {
+        BigColor::from_oklch(l, c, h, alpha.unwrap_or(1.0)).into()
+    }
+
+    #[wasm_bindgen(js_name = isValid)]
+    pub fn is_valid(&self) -> bool {
+        self.color().is_valid()
+    }
+
+    #[wasm_bindgen(js_name = getFormat, unchecked_return_type = "ColorFormat | \"invalid\"")]
+    pub fn get_format(&self) -> String {
+        format_name(self.color().get_format()).to_string()
+    }
+
+    #[wasm_bindgen(js_name = getOriginalInput)]
+    pub fn get_original_input(&self) -> String {
+        self.color().get_original_input().to_string()
+    }
+
+    #[wasm_bindgen(js_name = getAlpha)]
+    pub fn get_alpha(&self) -> f32 {
+        self.color().get_alpha()
+    }
+
+    #[wasm_bindgen(js_name = setAlpha)]
+    pub fn set_alpha(&self, alpha: f32) -> JsBigColor {
+        self.modified(|c| {
+            c.set_alpha(alpha);
+        })
+    }
+
+    #[wasm_bindgen(js_name = getBrightness)]
+    pub fn get_brightness(&self) -> f32 {
+        self.color().get_brightness()
+    }
+
+    #[wasm_bindgen(js_name = getLuminance)]
+    pub fn get_luminance(&self) -> f32 {
+        self.color().get_luminance()
+    }
+
+    #[wasm_bindgen(js_name = isDark)]
+    pub fn is_dark(&self) -> bool {
+        self.color().is_dark()
+    }
+
+    #[wasm_bindgen(js_name = isLight)]
+    pub fn is_light(&self) -> bool {
+        self.color().is_light()
+    }
+
+    #[wasm_bindgen(js_name = toRgb, unchecked_return_type = "Rgba")]
+    pub fn to_rgb(&self) -> JsValue {
+        let rgb = self.color().to_rgb();
+        object(&[("r", rgb.r as f32), ("g", rgb.g as f32), ("b", rgb.b as f32), ("a", rgb.a)]).into()
+    }
+
+    #[wasm_bindgen(js_name = toRgbString)]
+    pub fn to_rgb_string(&self) -> String {
+        self.color().to_rgb_string()
+    }
+
+    #[wasm_bindgen(js_name = toPercentageRgbString)]
+    pub fn to_percentage_rgb_string(&self) -> String {
+        self.color().to_percentage_rgb_string()
+    }
+
+    #[wasm_bindgen(js_name = toHsl, unchecked_return_type = "Hsla")]
+    pub fn to_hsl(&self) -> JsValue {
+        let hsl = self.color().to_hsl();
+        object(&[("h", hsl.h), ("s", hsl.s), ("l", hsl.l), ("a", hsl.a)]).into()
+    }
+
+    #[wasm_bindgen(js_name = toHslString)]
+    pub fn to_hsl_string(&self) -> String {
+        self.color().to_hsl_string()
+    }
+
+    #[wasm_bindgen(js_name = toHsv, unchecked_return_type = "Hsva")]
+    pub fn to_hsv(&self) -> JsValue {
+        let hsv = self.color().to_hsv();
+        object(&[("h", hsv.h), ("s", hsv.s), ("v", hsv.v), ("a", hsv.a)]).into()
+    }
+
+    #[wasm_bindgen(js_name = toHsvString)]
+    pub fn to_hsv_string(&self) -> String {
+        self.color().to_hsv_string()
+    }
+
+    /// Hex digits without the `#`
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self, allow_3_char: Option<bool>) -> String {
+        self.color().to_hex(allow_3_char.unwrap_or(false))
+    }
+
+    #[wasm_bindgen(js_name = toHexString)]
+    pub fn to_hex_string(&self, allow_3_char: Option<bool>) -> String {
+        self.color().to_hex_string(allow_3_char.unwrap_or(false))
+    }
+
+    #[wasm_bindgen(js_name = toHex8)]
+    pub fn to_hex8(&self, allow_4_char: Option<bool>) -> String {
+        self.color().to_hex8(allow_4_char.unwrap_or(false))
+    }
+
+    #[wasm_bindgen(js_name = toHex8String)]
+    pub fn to_hex8_string(&self, allow_4_char: Option<bool>) -> String {
+        self.color().to_hex8_string(allow_4_char.unwrap_or(false))
+    }
+
+    #[wasm_bindgen(js_name = toOklch, unchecked_return_type = "Oklch")]
+    pub fn to_oklch(&self) -> JsValue {
+        let oklch = self.color().to_oklch();
+        object(&[("l", oklch.l), ("c", oklch.c), ("h", oklch.h), ("alpha", oklch.alpha)]).into()
+    }
+
+    #[wasm_bindgen(js_name = toOklchString)]
+    pub fn to_oklch_string(&self) -> String {
+        self.color().to_oklch_string()
+    }
+
+    #[wasm_bindgen(js_name = toOklab, unchecked_return_type = "Oklab")]
+    pub fn to_oklab(&self) -> JsValue {
+        let oklab = self.color().to_oklab();
+        object(&[("l", oklab.l), ("a", oklab.a), ("b", oklab.b), ("alpha", oklab.alpha)]).into()
+    }
+
+    #[wasm_bindgen(js_name = toOklabString)]
+    pub fn to_oklab_string(&self) -> String {
+        self.color().to_oklab_string()
+    }
+
+    #[wasm_bindgen(js_name = toLabString)]
+    pub fn to_lab_string(&self) -> String {
+        self.color().to_lab_string()
+    }
+
+    #[wasm_bindgen(js_name = toLchString)]
+    pub fn to_lch_string(&self) -> String {
+        self.color().to_lch_string()
+    }
+
+    #[wasm_bindgen(js_name = toCmykString)]
+    pub fn to_cmyk_string(&self) -> String {
+        self.color().to_cmyk_string()
+    }
+
+    /// The CSS color name, or `false` when the color has none
+    #[wasm_bindgen(js_name = toName, unchecked_return_type = "string | false")]
+    pub fn to_name(&self) -> JsValue {
+        self.color().to_name().map_or(JsValue::FALSE, JsValue::from_str)
+    }
+
+    /// Writes the color in a format, or in the format it was parsed from
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self, #[wasm_bindgen(unchecked_param_type = "ColorFormat")] format: Option<String>) -> Result<String, JsValue> {
+        let format = match format {
+            Some(name) => Some(format_by_name(&name).ok_or_else(|| RangeError::new(&format!("unknown color format '{}'", name)))?),
+            None => None,
+        };
+        Ok(BigColor::to_string(&self.color(), format))
+    }
+
+    /// An independent copy that later modifications of this color leave alone
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_js(&self) -> JsBigColor {
+        self.color().clone().into()
+    }
+
+    pub fn lighten(&self, amount: Option<f32>) -> JsBigColor {
+        self.modified(|c| {
+            c.lighten(amount);
+        })
+    }
+
+    pub fn brighten(&self, amount: Option<f32>) -> JsBigColor {
+        self.modified(|c| {
+            c.brighten(amount);
+        })
+    }
+
+    pub fn darken(&self, amount: Option<f32>) -> JsBigColor {
+        self.modified(|c| {
+            c.darken(amount);
+        })
+    }
+
+    pub fn saturate(&self, amount: Option<f32>) -> JsBigColor {
+        self.modified(|c| {
+            c.saturate(amount);
+        })
+    }
+
+    pub fn desaturate(&self, amount: Option<f32>) -> JsBigColor {
+        self.modified(|c| {
+            c.desaturate(amount);
+        })
+    }
+
+    pub fn greyscale(&self) -> JsBigColor {
+        self.modified(|c| {
+            c.greyscale();
+        })
+    }
+
+    pub fn spin(&self, amount: f32) -> JsBigColor {
+        self.modified(|c| {
+            c.spin(amount);
+        })
+    }
+
+    pub fn analogous(&self, results: Option<usize>, slices: Option<usize>) -> Vec<JsBigColor> {
+        wrap(self.color().analogous(results, slices))
+    }
+
+    pub fn monochromatic(&self, results: Option<usize>) -> Vec<JsBigColor> {
+        wrap(self.color().monochromatic(results))
+    }
+
+    pub fn complement(&self) -> JsBigColor {
+        self.color().complement().into()
+    }
+
+    pub fn splitcomplement(&self) -> Vec<JsBigColor> {
+        wrap(self.color().split_complement())
+    }
+
+    pub fn triad(&self) -> Vec<JsBigColor> {
+        wrap(self.color().triad())
+    }
+
+    pub fn tetrad(&self) -> Vec<JsBigColor> {
+        wrap(self.color().tetrad())
+    }
+
+    pub fn polyad(&self, number: usize) -> Vec<JsBigColor> {
+        wrap(self.color().polyad(number))
+    }
+
+    /// WCAG 2 contrast ratio against another color, from 1 to 21
+    #[wasm_bindgen(js_name = getContrastRatio)]
+    pub fn get_contrast_ratio(&self, other: &ColorInput) -> Result<f32, JsValue> {
+        Ok(self.color().get_contrast_ratio(&color_from_js(other)?))
+    }
+
+    /// APCA lightness contrast (Lc) of this color as text on the background
+    #[wasm_bindgen(js_name = getApcaContrast)]
+    pub fn get_apca_contrast(&self, background: &ColorInput) -> Result<f32, JsValue> {
+        Ok(self.color().get_apca_contrast(&color_from_js(background)?))
+    }
+
+    /// Perceptual distance (ΔEOK) to another color
+    #[wasm_bindgen(js_name = deltaE)]
+    pub fn delta_e(&self, other: &ColorInput) -> Result<f32, JsValue> {
+        Ok(self.color().delta_e(&color_from_js(other)?))
+    }
+}
+
+/// Parses a color, like calling `tinycolor(input)`
+#[wasm_bindgen]
+pub fn bigcolor(input: &ColorInput) -> Result<JsBigColor, JsValue> {
+    JsBigColor::new(input)
+}
+
+#[wasm_bindgen]
+pub fn equals(color1: &ColorInput, color2: &ColorInput) -> Result<bool, JsValue> {
+    Ok(color_from_js(color1)? == color_from_js(color2)?)
+}
+
+#[wasm_bindgen]
+pub fn random() -> JsBigColor {
+    bigcolor::random().into()
+}
+
+/// Mixes two colors; `amount` is the percentage of the second, 50 by default
+#[wasm_bindgen(js_name = mix)]
+pub fn mix_js(color1: &ColorInput, color2: &ColorInput, amount: Option<f32>) -> Result<JsBigColor, JsValue> {
+    Ok(mix(&color_from_js(color1)?, &color_from_js(color2)?, amount).into())
+}
+
+/// WCAG 2 contrast ratio between two colors
+#[wasm_bindgen(js_name = readability)]
+pub fn readability_js(color1: &ColorInput, color2: &ColorInput) -> Result<f32, JsValue> {
+    Ok(readability(&color_from_js(color1)?, &color_from_js(color2)?))
+}
+
+#[wasm_bindgen(js_name = isReadable)]
+pub fn is_readable_js(color1: &ColorInput, color2: &ColorInput, options: Option<WCAG2Options>) -> Result<bool, JsValue> {
+    let params = wcag2_params(&options.map_or(JsValue::UNDEFINED, Into::into))?;
+    Ok(is_readable(&color_from_js(color1)?, &color_from_js(color2)?, Some(params)))
+}
+
+/// The color of the list with the most contrast on the base color
+#[wasm_bindgen(js_name = mostReadable)]
+pub fn most_readable_js(
+    base: &ColorInput,
+    #[wasm_bindgen(unchecked_param_type = "ColorInput[]")] colors: &Array,
+    options: Option<MostReadableOptions>,
+) -> Result<JsBigColor, JsValue> {
+    let options: JsValue = options.map_or(JsValue::UNDEFINED, Into::into);
+    let args = MostReadableArgs {
+        include_fallback_colors: option(&options, "includeFallbackColors").is_some_and(|value| value.is_truthy()),
+        wcag2: wcag2_params(&options)?,
+    };
+    let colors = colors.iter().map(|color| color_from_js(&color)).collect::<Result<Vec<_>, _>>()?;
+    Ok(most_readable(&color_from_js(base)?, &colors, Some(args)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_names() {
+        assert_eq!(format_by_name("OKLCH"), Some(ColorFormat::OKLCH));
+        assert_eq!(format_by_name("hex8"), Some(ColorFormat::HEX8));
+        assert_eq!(format_by_name("xyz"), None);
+        assert_eq!(format_name(BigColor::new("hsl(120, 50%, 50%)").get_format()), "hsl");
+        assert_eq!(format_name(BigColor::new("nope").get_format()), "invalid");
+    }
+
+    #[test]
+    fn test_modifications_change_the_receiver() {
+        let red = JsBigColor::from(BigColor::new("red"));
+        let copy = red.clone_js();
+        let darker = red.darken(Some(20.0)).spin(30.0);
+        let expected = BigColor::new("red").darken(Some(20.0)).spin(30.0).clone();
+        assert_eq!(*red.color(), expected);
+        assert_eq!(*darker.color(), expected);
+        assert_eq!(copy.to_hex_string(None), "#ff0000");
+        assert_eq!(red.analogous(Some(4), None).len(), 4);
+    }
+}
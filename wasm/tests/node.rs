@@ -0,0 +1,72 @@
+// Runs the JavaScript-facing API under Node: `npm run test:wasm`, which uses
+// the wasm-bindgen-test-runner configured in .cargo/config.toml
+#![cfg(target_arch = "wasm32")]
+
+use bigcolor_wasm::{is_readable_js, mix_js, most_readable_js, readability_js, JsBigColor};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn input(value: &str) -> JsValue {
+    JsValue::from_str(value)
+}
+
+fn color(value: &str) -> JsBigColor {
+    JsBigColor::new(input(value).unchecked_ref()).unwrap()
+}
+
+fn field(object: &JsValue, key: &str) -> f64 {
+    Reflect::get(object, &input(key)).unwrap().as_f64().unwrap()
+}
+
+#[wasm_bindgen_test]
+fn parses_strings_objects_and_colors() {
+    let blue = color("#1a6ef5");
+    assert!(blue.is_valid());
+    assert_eq!(blue.get_format(), "hex");
+    assert_eq!(field(&blue.to_rgb(), "g"), 110.0);
+
+    let object = js_sys::Object::new();
+    for (key, value) in [("r", 26.0), ("g", 110.0), ("b", 245.0)] {
+        Reflect::set(&object, &input(key), &JsValue::from_f64(value)).unwrap();
+    }
+    let from_object = JsBigColor::new(object.unchecked_ref()).unwrap();
+    assert_eq!(from_object.to_hex_string(None), "#1a6ef5");
+
+    // Another BigColor keeps its exact OKLCH values
+    let wide = JsBigColor::from_oklch(0.7, 0.3, 145.0, None);
+    let copy = JsBigColor::new(JsValue::from(wide.clone_js()).unchecked_ref()).unwrap();
+    assert_eq!(field(&copy.to_oklch(), "c"), field(&wide.to_oklch(), "c"));
+
+    assert!(!color("not a color").is_valid());
+    assert!(JsBigColor::parse(input("not a color").unchecked_ref()).is_err());
+    let error = JsBigColor::new(JsValue::from_f64(42.0).unchecked_ref()).err().unwrap();
+    assert!(error.is_instance_of::<js_sys::TypeError>());
+}
+
+#[wasm_bindgen_test]
+fn matches_tinycolor_operations() {
+    let red = color("red");
+    let lighter = bigcolor::BigColor::new("red").lighten(Some(20.0)).to_hex_string(false);
+    assert_eq!(red.clone_js().lighten(Some(20.0)).to_string_js(Some("hex".into())).unwrap(), lighter);
+
+    // Modifications change the receiver and return it, as in TinyColor
+    let shade = color("red");
+    shade.darken(Some(20.0)).spin(30.0);
+    assert_ne!(shade.to_hex_string(None), "#ff0000");
+    assert_eq!(red.to_hex_string(None), "#ff0000");
+    assert_eq!(red.to_name().as_string().as_deref(), Some("red"));
+    assert_eq!(color("#123456").to_name(), JsValue::FALSE);
+    assert_eq!(red.triad().len(), 3);
+    assert!(red.to_string_js(Some("xyz".into())).is_err());
+
+    let grey = input("#767676");
+    let white = input("white");
+    assert!((readability_js(grey.unchecked_ref(), white.unchecked_ref()).unwrap() - 4.54).abs() < 0.01);
+    assert!(is_readable_js(grey.unchecked_ref(), white.unchecked_ref(), None).unwrap());
+    assert_eq!(mix_js(input("#000").unchecked_ref(), white.unchecked_ref(), None).unwrap().to_hex_string(None), "#7f7f7f");
+
+    let list = Array::of2(&input("#2e0c3a"), &input("#ffffff"));
+    let best = most_readable_js(input("#ff0088").unchecked_ref(), &list, None).unwrap();
+    assert_eq!(best.to_hex_string(None), "#2e0c3a");
+}